@@ -12,6 +12,7 @@
 @group(2) @binding(6) var quaternary_texture: texture_2d<f32>;
 @group(2) @binding(7) var quaternary_sampler: sampler;
 @group(2) @binding(8) var<uniform> texel_size: vec4<f32>;
+@group(2) @binding(9) var<uniform> shadow: vec4<f32>;
 
 @fragment
 fn fragment(mesh: VertexOutput) -> @location(0) vec4<f32> {
@@ -38,7 +39,7 @@ fn fragment(mesh: VertexOutput) -> @location(0) vec4<f32> {
     let color = primary_noise + secondary_noise * 0.5 + tertiary_noise * 0.03 + quaternary_noise * 0.5;
 
     if color < 0.0 {
-        return vec4(0.0, 0.0, 0.0, 0.3);
+        return shadow;
     }
     return vec4(0.0, 0.0, 0.0, 0.0);
 }
@@ -7,6 +7,7 @@ const AMPLITUDE: f32 = 12.0;
 const TIME_SCALE_SINE: f32 = 1.0;
 const TIME_SCALE_EXP: f32 = 0.4;
 
+@group(2) @binding(0) var<uniform> tint: vec4<f32>;
 @group(2) @binding(1) var texture: texture_2d<f32>;
 @group(2) @binding(2) var texture_sampler: sampler;
 @group(2) @binding(3) var sine_texture: texture_2d<f32>;
@@ -64,12 +65,12 @@ fn fragment(mesh: VertexOutput) -> @location(0) vec4<f32> {
 
     let exp_t = (globals.time - exp_timestamp) * TIME_SCALE_EXP;
     if exp_t > 1.0 {
-        return textureSample(texture, texture_sampler, mesh.uv);
+        return textureSample(texture, texture_sampler, mesh.uv) * tint;
     }
     let exp = textureSample(exp_texture, exp_sampler, vec2(exp_t, 0.0)).x;
 
     if exp <= 0.1 {
-        return textureSample(texture, texture_sampler, mesh.uv);
+        return textureSample(texture, texture_sampler, mesh.uv) * tint;
     }
 
     let texel_size = 1.0 / vec2<f32>(textureDimensions(texture));
@@ -86,5 +87,5 @@ fn fragment(mesh: VertexOutput) -> @location(0) vec4<f32> {
     let offset = snapped_offset * texel_size.x;
 
     let uv = fract(snapped_mesh_uv + vec2(offset, 0.0));
-    return textureSample(texture, texture_sampler, uv);
+    return textureSample(texture, texture_sampler, uv) * tint;
 }
@@ -1,30 +1,25 @@
+import csv
+import sys
+
 import numpy as np
 
 from matplotlib import pyplot as plt
 
 
-FILE = "SIMULATION_PROGRESS_OUT.csv"
+# Written by `idle-garden simulate --series SIMULATION_PROGRESS_OUT.csv`.
+FILE = sys.argv[1] if len(sys.argv) > 1 else "SIMULATION_PROGRESS_OUT.csv"
+COLUMNS = ["time", "points", "points_cap", "pps", "seeds", "rebirths"]
 
 
-data = None
 with open(FILE) as f:
-    data = f.read()
-
-lines = data.strip().split('\n')
-time = []
-points = []
-point_caps = []
-ppss = []
-items = []
+    rows = list(csv.DictReader(f))
 
-for line in lines:
-    time_str, data = line.split(":")
-    point_str, point_cap_str, pps_str, items_str = data.split(";")
-    time.append(int(time_str))
-    points.append(int(point_str))
-    point_caps.append(int(point_cap_str))
-    ppss.append(int(pps_str))
-    items.append(eval(items_str))
+flora_ids = [column for column in rows[0].keys() if column not in COLUMNS]
+time = [int(row["time"]) for row in rows]
+points = [float(row["points"]) for row in rows]
+point_caps = [float(row["points_cap"]) for row in rows]
+ppss = [float(row["pps"]) for row in rows]
+items = [[int(row[flora_id]) for flora_id in flora_ids] for row in rows]
 
 items_np = np.array(items).T
 
@@ -51,9 +46,9 @@ plt.grid(True)
 # Plot each component of the vector
 plt.subplot(sub_plot_rows, sub_plot_columns, 3)
 for i, vec in enumerate(items_np):
-    plt.plot(time, vec, label=f"vec[{i}]")
+    plt.plot(time, vec, label=flora_ids[i])
 plt.xlabel("Time")
-plt.ylabel("Vector Component Value")
+plt.ylabel("Flora")
 plt.legend()
 plt.grid(True)
 
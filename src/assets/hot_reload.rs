@@ -0,0 +1,59 @@
+//! Hot reloading of the balance data in dev builds.
+//!
+//! Bevy's own file watcher pulls in a whole bunch of dependencies, we only care about a single
+//! file, so we simply poll its modification time and let the asset server reload it. The world
+//! picks the reloaded data up through the usual `AssetEvent`s.
+//!
+//! Only `flora.json` goes through the asset server. The other tables in `progression/` (upgrades,
+//! random events, day phases, ...) are embedded at compile time and need a restart, their
+//! `validate_*_json_data` tests make sure they always parse.
+
+use std::{
+    path::Path,
+    time::{Duration, SystemTime},
+};
+
+use bevy::{asset::io::file::FileAssetReader, prelude::*, time::common_conditions::on_real_timer};
+
+use crate::GameAssets;
+
+/// Must match the `file_path` of the `AssetPlugin`.
+const ASSETS_DIR: &str = "assets";
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+fn reload_changed_flora_registry(
+    asset_server: Res<AssetServer>,
+    assets: Res<GameAssets>,
+    mut last_modified: Local<Option<SystemTime>>,
+) {
+    let Some(asset_path) = assets.flora_registry.path() else {
+        return;
+    };
+    let path = FileAssetReader::get_base_path()
+        .join(ASSETS_DIR)
+        .join(asset_path.path());
+    let modified = modified_time(&path);
+
+    if last_modified.is_some() && modified != *last_modified {
+        info!("{} changed, reloading", asset_path);
+        asset_server.reload(asset_path.clone());
+    }
+    *last_modified = modified;
+}
+
+pub struct HotReloadPlugin;
+
+impl Plugin for HotReloadPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            reload_changed_flora_registry
+                .run_if(resource_exists::<GameAssets>.and(on_real_timer(Duration::from_secs(1)))),
+        );
+    }
+}
@@ -1,4 +1,6 @@
 mod events;
+#[cfg(all(debug_assertions, not(target_arch = "wasm32")))]
+mod hot_reload;
 
 pub use events::PlayerFootstepEvent;
 
@@ -7,11 +9,18 @@ use bevy_asset_loader::prelude::*;
 use bevy_enoki::prelude::*;
 use bevy_trickfilm::prelude::*;
 
-use crate::world::GrassMaterial;
+use crate::world::{FloraRegistry, GrassMaterial};
 
 pub const APIKEY: &str = include_str!("../../apikey.env");
 
+pub const ACHIEVEMENT_DATA_CORE: &str = include_str!("../../assets/progression/achievements.json");
 pub const FLORA_DATA_CORE: &str = include_str!("../../assets/progression/flora.json");
+pub const ADJACENCY_DATA_CORE: &str = include_str!("../../assets/progression/adjacency.json");
+pub const UPGRADE_DATA_CORE: &str = include_str!("../../assets/progression/upgrades.json");
+pub const STRUCTURE_DATA_CORE: &str = include_str!("../../assets/progression/structures.json");
+pub const RANDOM_EVENT_DATA_CORE: &str =
+    include_str!("../../assets/progression/random_events.json");
+pub const DAY_NIGHT_DATA_CORE: &str = include_str!("../../assets/progression/day_night.json");
 pub const FLORA_SHADER: &str = "shaders/flora_shader.wgsl";
 pub const GRASS_SHADER: &str = "shaders/grass_shader.wgsl";
 pub const CLOUDS_SHADER: &str = "shaders/clouds_shader.wgsl";
@@ -26,6 +35,15 @@ pub const PROGRESSION_CORE_FILE: &str = "assets/save/progression_core.json";
 pub const GAME_TELEMETRY_FILE: &str = "assets/save/telemetry";
 #[cfg(not(target_arch = "wasm32"))]
 pub const CONSENT_FILE: &str = "assets/save/consent";
+#[cfg(not(target_arch = "wasm32"))]
+pub const SAVE_SLOTS_DIR: &str = "assets/save/slots";
+/// Exists once the save from before slots existed was moved into a slot.
+#[cfg(not(target_arch = "wasm32"))]
+pub const LEGACY_SAVE_IMPORTED_FILE: &str = "assets/save/slots/legacy_imported";
+#[cfg(not(target_arch = "wasm32"))]
+pub const SAVE_CODE_EXPORT_FILE: &str = "assets/save/export.txt";
+#[cfg(not(target_arch = "wasm32"))]
+pub const SAVE_CODE_IMPORT_FILE: &str = "assets/save/import.txt";
 
 #[cfg(target_arch = "wasm32")]
 pub const WASM_MAP_DATA_KEY_STORAGE: &str = "map-grid";
@@ -36,11 +54,9 @@ pub const WASM_GAME_TELEMETRY_KEY_STORAGE: &str = "game-telemetry";
 #[cfg(target_arch = "wasm32")]
 pub const WASM_CONSENT_STORAGE: &str = "consent";
 #[cfg(target_arch = "wasm32")]
-pub const WASM_KEYS: [&str; 3] = [
-    WASM_PROGRESSION_CORE_KEY_STORAGE,
-    WASM_MAP_DATA_KEY_STORAGE,
-    WASM_GAME_TELEMETRY_KEY_STORAGE,
-];
+pub const WASM_SAVE_SLOT_KEY_PREFIX: &str = "save-slot-";
+#[cfg(target_arch = "wasm32")]
+pub const WASM_LEGACY_SAVE_IMPORTED_KEY_STORAGE: &str = "legacy-save-imported";
 
 const CUT_GRASS_PARTICLES_FILE: &str = "effects/cut_grass.ron";
 
@@ -49,6 +65,8 @@ pub struct GameAssetsPlugin;
 impl Plugin for GameAssetsPlugin {
     fn build(&self, app: &mut App) {
         app.add_plugins(events::GameAssetsEventsPlugin);
+        #[cfg(all(debug_assertions, not(target_arch = "wasm32")))]
+        app.add_plugins(hot_reload::HotReloadPlugin);
     }
 }
 
@@ -75,19 +93,6 @@ pub struct GameAssets {
     pub slash: Handle<Image>,
 
     // --- UI ---
-    #[asset(
-        paths(
-            "ui/icons/potatoe_icon.png",
-            "ui/icons/raddish_icon.png",
-            "ui/icons/carrot_icon.png",
-            "ui/icons/corn_icon.png",
-            "ui/icons/pumpkin_icon.png",
-            "ui/icons/onion_icon.png",
-        ),
-        collection(typed)
-    )]
-    pub flora_icons: Vec<Handle<Image>>,
-
     #[asset(path = "ui/building_grid.png")]
     pub building_grid: Handle<Image>,
 
@@ -121,22 +126,8 @@ pub struct GameAssets {
     #[asset(path = "map/building_selector_animation.trickfilm.ron#main")]
     pub building_selector_animation: Handle<AnimationClip2D>,
 
-    #[asset(
-        paths(
-            "map/potatoe.png",
-            "map/raddish.png",
-            "map/carrot.png",
-            "map/corn.png",
-            "map/pumpkin.png",
-            "map/onion.png",
-        ),
-        collection(typed)
-    )]
-    pub flora_images: Vec<Handle<Image>>,
-    #[asset(path = "map/corn_crop_left.png")]
-    pub corn_crop_left: Handle<Image>,
-    #[asset(path = "map/corn_crop_right.png")]
-    pub corn_crop_right: Handle<Image>,
+    #[asset(path = "progression/flora.json")]
+    pub flora_registry: Handle<FloraRegistry>,
 
     // --- EFFECTS ---
     #[asset(path = "effects/grass_snippet.png")]
@@ -240,6 +231,7 @@ impl FromWorld for EffectAssets {
         };
 
         let raw_grass_material = GrassMaterial {
+            tint: Vec4::ONE,
             texture: Some(assets.grass.clone()),
             discrete_sine: Some(assets.discrete_sine_texture.clone()),
             discrete_exp_damp: Some(assets.discrete_exp_damp_texture.clone()),
@@ -2,9 +2,13 @@ use bevy::{
     audio::{PlaybackMode, Volume},
     prelude::*,
 };
-use rand::{thread_rng, Rng};
+use rand::Rng;
 
-use crate::{assets::PlayerFootstepEvent, world::ProgressionCore, GameAssets};
+use crate::{
+    assets::PlayerFootstepEvent,
+    world::{ProgressionCore, RngStream, WorldRng},
+    GameAssets,
+};
 
 const DEFAULT_MUSIC_VOLUME: f32 = 0.05;
 const DEFAULT_SOUND_VOLUME: f32 = 0.3;
@@ -20,6 +24,7 @@ impl Plugin for GameAudioPlugin {
             spawn_bgm.run_if(
                 resource_exists::<GameAssets>
                     .and(resource_exists::<ProgressionCore>)
+                    .and(resource_exists::<WorldRng>)
                     .and(run_once),
             ),
         )
@@ -27,8 +32,8 @@ impl Plugin for GameAudioPlugin {
             Update,
             (
                 fade_in,
-                fade_out,
-                change_to_next_song,
+                fade_out.run_if(resource_exists::<WorldRng>),
+                change_to_next_song.run_if(resource_exists::<WorldRng>),
                 change_fade_on_core_music,
                 spawn_player_footstep_sound,
                 silence_all_sounds,
@@ -51,9 +56,14 @@ struct Bgm {
 #[derive(Component)]
 struct Sound;
 
-fn spawn_bgm(mut commands: Commands, assets: Res<GameAssets>, core: Res<ProgressionCore>) {
-    let mut rng = thread_rng();
-    let current_song_index = rng.gen_range(0..assets.bgms.len());
+fn spawn_bgm(
+    mut commands: Commands,
+    assets: Res<GameAssets>,
+    core: Res<ProgressionCore>,
+    mut world_rng: ResMut<WorldRng>,
+) {
+    let rng = world_rng.stream(RngStream::Music);
+    let current_song_index = rng.random_range(0..assets.bgms.len());
 
     commands.spawn((
         Bgm {
@@ -61,7 +71,7 @@ fn spawn_bgm(mut commands: Commands, assets: Res<GameAssets>, core: Res<Progress
             fade_out: false,
             fade_time: 3.0,
             next_song: Timer::from_seconds(
-                rng.gen_range(MIN_SONG_DURATION..MAX_SONG_DURATION),
+                rng.random_range(MIN_SONG_DURATION..MAX_SONG_DURATION),
                 TimerMode::Once,
             ),
             current_song_index,
@@ -101,6 +111,7 @@ fn fade_out(
     mut commands: Commands,
     assets: Res<GameAssets>,
     time: Res<Time>,
+    mut world_rng: ResMut<WorldRng>,
     q_bgm: Single<(Entity, &mut AudioSink, &mut Bgm)>,
 ) {
     let (entity, mut audio, mut bgm) = q_bgm.into_inner();
@@ -120,8 +131,9 @@ fn fade_out(
         bgm.fade_time = 10.0;
         audio.set_volume(Volume::SILENT);
 
-        let mut rng = thread_rng();
-        let shifted_index = rng.gen_range(0..assets.bgms.len() - 1);
+        let shifted_index = world_rng
+            .stream(RngStream::Music)
+            .random_range(0..assets.bgms.len() - 1);
 
         let index = if shifted_index >= bgm.current_song_index {
             shifted_index + 1
@@ -139,7 +151,7 @@ fn fade_out(
     }
 }
 
-fn change_to_next_song(time: Res<Time>, q_bgm: Single<&mut Bgm>) {
+fn change_to_next_song(time: Res<Time>, mut world_rng: ResMut<WorldRng>, q_bgm: Single<&mut Bgm>) {
     let mut bgm = q_bgm.into_inner();
     if !bgm.is_active {
         return;
@@ -147,9 +159,10 @@ fn change_to_next_song(time: Res<Time>, q_bgm: Single<&mut Bgm>) {
 
     bgm.next_song.tick(time.delta());
     if bgm.next_song.just_finished() {
-        let mut rng = thread_rng();
         bgm.next_song = Timer::from_seconds(
-            rng.gen_range(MIN_SONG_DURATION..MAX_SONG_DURATION),
+            world_rng
+                .stream(RngStream::Music)
+                .random_range(MIN_SONG_DURATION..MAX_SONG_DURATION),
             TimerMode::Once,
         );
         bgm.fade_time = 10.0;
@@ -188,15 +201,16 @@ fn spawn_player_footstep_sound(
         return;
     }
 
-    let mut rng = thread_rng();
+    // Just a bit of variety, this doesn't need to be reproducible.
+    let mut rng = rand::rng();
 
     commands.spawn((
         Sound,
         AudioPlayer(assets.player_footstep.clone()),
         PlaybackSettings {
             mode: PlaybackMode::Despawn,
-            speed: rng.gen_range(0.7..1.3),
-            volume: Volume::Linear(DEFAULT_SOUND_VOLUME * rng.gen_range(0.8..1.0)),
+            speed: rng.random_range(0.7..1.3),
+            volume: Volume::Linear(DEFAULT_SOUND_VOLUME * rng.random_range(0.8..1.0)),
             ..default()
         },
     ));
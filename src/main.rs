@@ -22,8 +22,6 @@ use bevy_enoki::prelude::*;
 use bevy_trickfilm::prelude::*;
 
 use bevy_asset_loader::prelude::*;
-#[cfg(debug_assertions)]
-use world::simulate_progression;
 
 #[cfg(target_arch = "wasm32")]
 use crate::world::GameTelemetryManager;
@@ -38,8 +36,12 @@ pub enum GameState {
     BachelorToggle,
     ConsentCheck,
     ConsentNotice,
+    SlotSelect,
     Gaming,
     Menu,
+    Prestige,
+    Achievements,
+    Statistics,
 }
 
 #[derive(Resource)]
@@ -50,16 +52,18 @@ pub struct BachelorBuild {
 struct BachelorBuildComponent;
 
 fn main() {
-    #[cfg(debug_assertions)]
-    use std::env::args;
-
-    #[cfg(debug_assertions)]
-    if args()
-        .find(|s| s.to_lowercase() == "simulate-progression")
-        .is_some()
+    #[cfg(not(target_arch = "wasm32"))]
     {
-        simulate_progression();
-        return;
+        let args = std::env::args().collect::<Vec<String>>();
+        let command =
+            world::run_save_code_command(&args).or_else(|| world::run_simulation_command(&args));
+        if let Some(result) = command {
+            if let Err(err) = result {
+                eprintln!("{}", err);
+                std::process::exit(1);
+            }
+            return;
+        }
     }
 
     let mut app = App::new();
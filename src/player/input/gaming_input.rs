@@ -63,6 +63,10 @@ fn handle_keyboard_inputs(
     input.cancel =
         keys.just_pressed(KeyCode::Escape) || mouse_buttons.just_pressed(MouseButton::Right);
     input.slash = mouse_buttons.just_pressed(MouseButton::Left);
+    input.demolish = keys.just_pressed(KeyCode::KeyX);
+    input.move_flora = keys.just_pressed(KeyCode::KeyM);
+    input.buy_land = keys.just_pressed(KeyCode::KeyL);
+    input.buy_many = keys.pressed(KeyCode::ShiftLeft) || keys.pressed(KeyCode::ShiftRight);
 
     let mut move_direction = Vec2::ZERO;
     if keys.pressed(KeyCode::KeyJ)
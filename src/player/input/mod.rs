@@ -67,6 +67,14 @@ pub struct GamingInput {
     pub confirm: bool,
     pub cancel: bool,
     pub slash: bool,
+    /// Toggle the demolish tool.
+    pub demolish: bool,
+    /// Toggle the tool to move placed flora.
+    pub move_flora: bool,
+    /// Toggle the tool to buy land.
+    pub buy_land: bool,
+    /// Held to buy several plants at once in the store.
+    pub buy_many: bool,
 
     pub menu: bool,
 
@@ -96,6 +104,10 @@ impl BitOrAssign for GamingInput {
         self.confirm |= rhs.confirm;
         self.cancel |= rhs.cancel;
         self.slash |= rhs.slash;
+        self.demolish |= rhs.demolish;
+        self.move_flora |= rhs.move_flora;
+        self.buy_land |= rhs.buy_land;
+        self.buy_many |= rhs.buy_many;
         self.menu |= rhs.menu;
     }
 }
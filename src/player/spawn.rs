@@ -3,7 +3,7 @@ use bevy_trickfilm::prelude::*;
 
 use crate::{
     world::{
-        DynamicCollider, ProgressionCore, StaticSensorCircle, Velocity, YSort,
+        Daylit, DynamicCollider, ProgressionCore, StaticSensorCircle, Velocity, YSort,
         PLAYER_COLLISION_GROUPS,
     },
     GameAssets,
@@ -52,6 +52,7 @@ fn spawn_player_from_args(commands: &mut Commands, assets: &GameAssets, pos: Vec
         ChildOf(root),
         animator,
         Sprite::from_atlas_image(assets.player.clone(), assets.player_layout.clone().into()),
+        Daylit,
     ));
 
     let scythe_socket = commands
@@ -70,6 +71,7 @@ fn spawn_player_from_args(commands: &mut Commands, assets: &GameAssets, pos: Vec
         ChildOf(scythe_socket),
         ScytheGFX,
         Sprite::from_image(assets.scythe.clone()),
+        Daylit,
         Transform::from_translation(SCYTHE_OFFSET),
     ));
 }
@@ -0,0 +1,293 @@
+use bevy::{
+    color::palettes::css::{GOLD, GRAY, RED},
+    prelude::*,
+    text::FontSmoothing,
+};
+use chrono::DateTime;
+
+use crate::{
+    world::{AchievementUnlocked, MapData, ProgressionCore},
+    GameAssets, GameState,
+};
+
+const DEFAULT_FONT_SIZE: f32 = 20.0;
+const TITLE_FONT_SIZE: f32 = 35.0;
+const ACHIEVEMENT_NAME_WIDTH: f32 = 220.0;
+const ACHIEVEMENT_DESCRIPTION_WIDTH: f32 = 420.0;
+const ACHIEVEMENT_STATUS_WIDTH: f32 = 260.0;
+const TOAST_TIME: f32 = 3.0;
+const TOAST_HEIGHT: f32 = 70.0;
+
+#[derive(Component)]
+struct AchievementsScreen;
+#[derive(Component)]
+struct BackButton;
+#[derive(Component)]
+struct AchievementToast {
+    timer: Timer,
+}
+
+fn text_font(assets: &GameAssets, font_size: f32) -> TextFont {
+    TextFont {
+        font: assets.pixel_font.clone(),
+        font_size,
+        font_smoothing: FontSmoothing::None,
+        ..default()
+    }
+}
+
+/// Either the unlock date or the progress towards the achievement.
+fn status_string(core: &ProgressionCore, map_data: &MapData, index: usize) -> String {
+    let achievement = &map_data.achievement_data()[index];
+
+    if let Some(timestamp) = core.achievements.unlocked_at(&achievement.id) {
+        let date = DateTime::from_timestamp(timestamp as i64, 0)
+            .map(|date| date.format("%Y-%m-%d").to_string())
+            .unwrap_or_default();
+        return format!("Unlocked {}", date);
+    }
+
+    match achievement.progress(core, map_data) {
+        Some((current, required)) => format!("{}/{}", current.min(required), required),
+        None => "Locked".to_string(),
+    }
+}
+
+fn spawn_achievements_screen(
+    mut commands: Commands,
+    assets: Res<GameAssets>,
+    core: Res<ProgressionCore>,
+    map_data: Res<MapData>,
+) {
+    let root = commands
+        .spawn((
+            AchievementsScreen,
+            GlobalZIndex(1000),
+            ImageNode {
+                image: Handle::<Image>::default(),
+                color: Color::BLACK.with_alpha(0.9),
+                ..default()
+            },
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(12.0),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                position_type: PositionType::Absolute,
+                ..default()
+            },
+        ))
+        .id();
+
+    let achievements = map_data.achievement_data();
+    commands.spawn((
+        ChildOf(root),
+        Text::new(format!(
+            "Achievements {}/{}",
+            core.achievements.unlocked.len(),
+            achievements.len()
+        )),
+        text_font(&assets, TITLE_FONT_SIZE),
+    ));
+
+    for (index, achievement) in achievements.iter().enumerate() {
+        let color: Color = if core.achievements.unlocked_at(&achievement.id).is_some() {
+            GOLD.into()
+        } else {
+            GRAY.into()
+        };
+
+        let row = commands
+            .spawn((
+                ChildOf(root),
+                Node {
+                    flex_direction: FlexDirection::Row,
+                    column_gap: Val::Px(20.0),
+                    ..default()
+                },
+            ))
+            .id();
+
+        for (string, width) in [
+            (achievement.name.clone(), ACHIEVEMENT_NAME_WIDTH),
+            (
+                achievement.description.clone(),
+                ACHIEVEMENT_DESCRIPTION_WIDTH,
+            ),
+            (
+                status_string(&core, &map_data, index),
+                ACHIEVEMENT_STATUS_WIDTH,
+            ),
+        ] {
+            commands.spawn((
+                ChildOf(row),
+                Node {
+                    width: Val::Px(width),
+                    ..default()
+                },
+                Text::new(string),
+                text_font(&assets, DEFAULT_FONT_SIZE),
+                TextColor(color),
+            ));
+        }
+    }
+
+    commands.spawn((
+        ChildOf(root),
+        BackButton,
+        Button,
+        Node {
+            margin: UiRect::top(Val::Px(20.0)),
+            ..default()
+        },
+        Text::new("Back"),
+        text_font(&assets, 25.0),
+        TextColor(Color::WHITE),
+    ));
+}
+
+fn despawn_achievements_screen(
+    mut commands: Commands,
+    q_achievements_screens: Query<Entity, With<AchievementsScreen>>,
+) {
+    for entity in &q_achievements_screens {
+        commands.entity(entity).despawn();
+    }
+}
+
+fn handle_back_button(
+    mut next_state: ResMut<NextState<GameState>>,
+    q_back_buttons: Query<&Interaction, (With<BackButton>, Changed<Interaction>)>,
+) {
+    if q_back_buttons
+        .iter()
+        .any(|interaction| *interaction == Interaction::Pressed)
+    {
+        next_state.set(GameState::Menu);
+    }
+}
+
+fn close_achievements_screen_on_escape(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if keys.just_pressed(KeyCode::Escape) {
+        next_state.set(GameState::Menu);
+    }
+}
+
+fn highlight_back_button(
+    mut q_back_buttons: Query<(&Interaction, &mut TextColor), With<BackButton>>,
+) {
+    for (interaction, mut color) in &mut q_back_buttons {
+        color.0 = if matches!(interaction, Interaction::Pressed | Interaction::Hovered) {
+            RED.into()
+        } else {
+            Color::WHITE
+        };
+    }
+}
+
+fn spawn_achievement_toasts(
+    mut commands: Commands,
+    assets: Res<GameAssets>,
+    map_data: Res<MapData>,
+    q_toasts: Query<(), With<AchievementToast>>,
+    mut ev_achievement_unlocked: EventReader<AchievementUnlocked>,
+) {
+    let mut count = q_toasts.iter().count();
+
+    for ev in ev_achievement_unlocked.read() {
+        let Some(achievement) = map_data.achievement_data().get(ev.index) else {
+            error!("unlocked achievement with invalid index: {}", ev.index);
+            continue;
+        };
+
+        let toast = commands
+            .spawn((
+                AchievementToast {
+                    timer: Timer::from_seconds(TOAST_TIME, TimerMode::Once),
+                },
+                GlobalZIndex(1100),
+                ImageNode {
+                    image: Handle::<Image>::default(),
+                    color: Color::BLACK.with_alpha(0.8),
+                    ..default()
+                },
+                Node {
+                    top: Val::Px(20.0 + count as f32 * (TOAST_HEIGHT + 10.0)),
+                    right: Val::Px(20.0),
+                    height: Val::Px(TOAST_HEIGHT),
+                    padding: UiRect::horizontal(Val::Px(20.0)),
+                    flex_direction: FlexDirection::Column,
+                    justify_content: JustifyContent::Center,
+                    position_type: PositionType::Absolute,
+                    ..default()
+                },
+            ))
+            .id();
+
+        commands.spawn((
+            ChildOf(toast),
+            Text::new("Achievement unlocked!"),
+            text_font(&assets, DEFAULT_FONT_SIZE),
+            TextColor(GOLD.into()),
+        ));
+        commands.spawn((
+            ChildOf(toast),
+            Text::new(achievement.name.clone()),
+            text_font(&assets, 25.0),
+        ));
+
+        count += 1;
+    }
+}
+
+fn despawn_achievement_toasts(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut q_toasts: Query<(Entity, &mut AchievementToast)>,
+) {
+    for (entity, mut toast) in &mut q_toasts {
+        toast.timer.tick(time.delta());
+        if toast.timer.finished() {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+pub struct UiAchievementsPlugin;
+
+impl Plugin for UiAchievementsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            OnEnter(GameState::Achievements),
+            spawn_achievements_screen.run_if(
+                resource_exists::<GameAssets>
+                    .and(resource_exists::<ProgressionCore>)
+                    .and(resource_exists::<MapData>),
+            ),
+        )
+        .add_systems(OnExit(GameState::Achievements), despawn_achievements_screen)
+        .add_systems(
+            Update,
+            (
+                handle_back_button,
+                close_achievements_screen_on_escape,
+                highlight_back_button,
+            )
+                .chain()
+                .run_if(in_state(GameState::Achievements)),
+        )
+        .add_systems(
+            Update,
+            (
+                spawn_achievement_toasts
+                    .run_if(resource_exists::<GameAssets>.and(resource_exists::<MapData>)),
+                despawn_achievement_toasts,
+            ),
+        );
+    }
+}
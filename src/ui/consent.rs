@@ -161,7 +161,7 @@ fn handle_button(
     };
 
     match interaction {
-        Interaction::Pressed => next_state.set(GameState::Gaming),
+        Interaction::Pressed => next_state.set(GameState::SlotSelect),
         Interaction::Hovered => image.color = GRAY_700.into(),
         Interaction::None => image.color = GRAY_500.into(),
     }
@@ -187,7 +187,7 @@ fn check_consent(mut next_state: ResMut<NextState<GameState>>) {
     if read_consent().is_empty() {
         next_state.set(GameState::ConsentNotice);
     } else {
-        next_state.set(GameState::Gaming);
+        next_state.set(GameState::SlotSelect);
     }
 }
 
@@ -0,0 +1,86 @@
+use bevy::{color::palettes::css::RED, prelude::*, text::FontSmoothing};
+
+use crate::{world::FloraRegistryLoadError, GameAssets};
+
+#[derive(Component)]
+struct DataErrorBanner;
+
+/// The pixel font isn't around if the flora data already failed while loading all the assets.
+fn text_font(assets: Option<&GameAssets>) -> TextFont {
+    match assets {
+        Some(assets) => TextFont {
+            font: assets.pixel_font.clone(),
+            font_size: 20.0,
+            font_smoothing: FontSmoothing::None,
+            ..default()
+        },
+        None => TextFont::from_font_size(16.0),
+    }
+}
+
+fn spawn_data_error_banner(
+    mut commands: Commands,
+    assets: Option<Res<GameAssets>>,
+    load_error: Res<FloraRegistryLoadError>,
+    q_banners: Query<Entity, With<DataErrorBanner>>,
+) {
+    for entity in &q_banners {
+        commands.entity(entity).despawn();
+    }
+
+    let banner = commands
+        .spawn((
+            DataErrorBanner,
+            GlobalZIndex(1200),
+            ImageNode {
+                image: Handle::<Image>::default(),
+                color: Color::BLACK.with_alpha(0.85),
+                ..default()
+            },
+            Node {
+                top: Val::Px(0.0),
+                width: Val::Percent(100.0),
+                padding: UiRect::all(Val::Px(10.0)),
+                flex_direction: FlexDirection::Column,
+                position_type: PositionType::Absolute,
+                ..default()
+            },
+        ))
+        .id();
+
+    commands.spawn((
+        ChildOf(banner),
+        Text::new("Failed to load the flora data"),
+        text_font(assets.as_deref()),
+        TextColor(RED.into()),
+    ));
+    commands.spawn((
+        ChildOf(banner),
+        Text::new(load_error.message.clone()),
+        text_font(assets.as_deref()),
+    ));
+}
+
+fn despawn_data_error_banner(
+    mut commands: Commands,
+    q_banners: Query<Entity, With<DataErrorBanner>>,
+) {
+    for entity in &q_banners {
+        commands.entity(entity).despawn();
+    }
+}
+
+pub struct UiDataErrorPlugin;
+
+impl Plugin for UiDataErrorPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                spawn_data_error_banner
+                    .run_if(resource_exists_and_changed::<FloraRegistryLoadError>),
+                despawn_data_error_banner.run_if(resource_removed::<FloraRegistryLoadError>),
+            ),
+        );
+    }
+}
@@ -0,0 +1,119 @@
+use bevy::{prelude::*, text::FontSmoothing};
+
+use crate::{
+    world::{utils::format_duration, MapData, ProgressionCore},
+    GameAssets, GameState,
+};
+
+use super::outline::TextOutline;
+
+#[derive(Component)]
+struct DayPhaseText;
+#[derive(Component)]
+struct DayPhaseModifiersText;
+
+fn text_font(assets: &GameAssets, font_size: f32) -> TextFont {
+    TextFont {
+        font: assets.pixel_font.clone(),
+        font_size,
+        font_smoothing: FontSmoothing::None,
+        ..default()
+    }
+}
+
+fn spawn_day_phase_texts(mut commands: Commands, assets: Res<GameAssets>) {
+    commands.spawn((
+        DayPhaseText,
+        Node {
+            right: Val::Percent(5.0),
+            top: Val::Px(20.0),
+            position_type: PositionType::Absolute,
+            ..default()
+        },
+        TextOutline::new(
+            String::new(),
+            1.0,
+            Color::WHITE,
+            Color::BLACK,
+            text_font(&assets, 25.0),
+            false,
+        ),
+    ));
+
+    commands.spawn((
+        DayPhaseModifiersText,
+        Node {
+            right: Val::Percent(5.0),
+            top: Val::Px(50.0),
+            position_type: PositionType::Absolute,
+            ..default()
+        },
+        TextOutline::new(
+            String::new(),
+            1.0,
+            Color::WHITE,
+            Color::BLACK,
+            text_font(&assets, 18.0),
+            false,
+        ),
+    ));
+}
+
+/// Show the day, the phase and how long it lasts, together with what the phase changes.
+fn update_day_phase_texts(
+    core: Res<ProgressionCore>,
+    map_data: Res<MapData>,
+    q_phase_text: Single<&mut TextOutline, With<DayPhaseText>>,
+    q_modifiers_text: Single<
+        &mut TextOutline,
+        (With<DayPhaseModifiersText>, Without<DayPhaseText>),
+    >,
+) {
+    let mut phase_text = q_phase_text.into_inner();
+    let mut modifiers_text = q_modifiers_text.into_inner();
+
+    let Some(phase) = core.day_phase(&map_data) else {
+        return;
+    };
+    let remaining = core
+        .day_night
+        .seconds_left_in_phase(map_data.day_phase_data())
+        .unwrap_or_default();
+
+    let text = format!(
+        "Day {} - {} {}",
+        core.day_night.day + 1,
+        phase.name,
+        format_duration(remaining)
+    );
+    if phase_text.text != text {
+        phase_text.text = text;
+    }
+
+    let modifiers = phase
+        .modifiers
+        .iter()
+        .map(|phase_modifier| {
+            phase_modifier
+                .modifier
+                .description(&phase_modifier.target, map_data.flora_registry())
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+    if modifiers_text.text != modifiers {
+        modifiers_text.text = modifiers;
+    }
+}
+
+pub struct UiDayNightPlugin;
+
+impl Plugin for UiDayNightPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnExit(GameState::AssetLoading), spawn_day_phase_texts)
+            .add_systems(
+                Update,
+                update_day_phase_texts
+                    .run_if(resource_exists::<ProgressionCore>.and(resource_exists::<MapData>)),
+            );
+    }
+}
@@ -3,10 +3,11 @@ use bevy::{
     prelude::*,
     text::FontSmoothing,
 };
+use serde::Deserialize;
 
 use crate::{
     player::GamingInput,
-    world::{GameTelemetryManager, ProgressionCore},
+    world::{GameTelemetryManager, ProgressionCore, SaveCodeResult},
     GameAssets, GameState,
 };
 
@@ -26,7 +27,7 @@ struct MenuScreen;
 #[derive(Component)]
 struct NonInteractable;
 
-#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+#[derive(Deserialize, PartialEq, Eq, Clone, Copy, Debug)]
 pub enum MenuAction {
     Continue,
     SendDataYes,
@@ -41,6 +42,11 @@ pub enum MenuAction {
     CancelReset,
     UnlockReset,
     Discord,
+    ExportSave,
+    ImportSave,
+    Prestige,
+    Achievements,
+    Statistics,
 }
 #[derive(Component, Debug)]
 struct MenuData {
@@ -83,6 +89,11 @@ impl MenuAction {
             Self::CancelReset => "Cancel",
             Self::UnlockReset => "SHOULD NEVER SEE THIS",
             Self::Discord => "SHOULD NEVER SEE THIS",
+            Self::ExportSave => "Export Save",
+            Self::ImportSave => "Import Save",
+            Self::Prestige => "Prestige",
+            Self::Achievements => "Achievements",
+            Self::Statistics => "Statistics",
         };
 
         s.to_string()
@@ -241,12 +252,48 @@ fn spawn_buttons(
         MenuAction::Survey,
     );
 
+    let prestige_button = spawn_button(
+        commands,
+        font.clone(),
+        DEFAULT_FONT_SIZE,
+        MenuAction::Prestige,
+    );
+    let achievements_button = spawn_button(
+        commands,
+        font.clone(),
+        DEFAULT_FONT_SIZE,
+        MenuAction::Achievements,
+    );
+    let statistics_button = spawn_button(
+        commands,
+        font.clone(),
+        DEFAULT_FONT_SIZE,
+        MenuAction::Statistics,
+    );
+    let export_button = spawn_button(
+        commands,
+        font.clone(),
+        DEFAULT_FONT_SIZE,
+        MenuAction::ExportSave,
+    );
+    let import_button = spawn_button(
+        commands,
+        font.clone(),
+        DEFAULT_FONT_SIZE,
+        MenuAction::ImportSave,
+    );
+
     let vertical_buttons = [
         continue_button,
         music_button,
         sound_button,
+        prestige_button,
+        achievements_button,
+        statistics_button,
         send_data,
         survey_button,
+        export_button,
+        import_button,
         reset_button,
     ];
 
@@ -848,6 +895,27 @@ fn open_survey_link(
     open_url_link(&url, err_str);
 }
 
+fn show_save_code_result(
+    mut q_texts: Query<(&mut Text, &MenuData)>,
+    mut ev_save_code_result: EventReader<SaveCodeResult>,
+) {
+    for ev in ev_save_code_result.read() {
+        let message = match (ev.action, ev.success) {
+            (MenuAction::ExportSave, true) => "Exported!",
+            (MenuAction::ExportSave, false) => "Export Failed",
+            (MenuAction::ImportSave, true) => "Imported to Slots",
+            (MenuAction::ImportSave, false) => "Invalid Code",
+            _ => continue,
+        };
+
+        for (mut text, data) in &mut q_texts {
+            if data.action == ev.action {
+                text.0 = message.to_string();
+            }
+        }
+    }
+}
+
 pub struct UiMenuPlugin;
 
 impl Plugin for UiMenuPlugin {
@@ -856,6 +924,7 @@ impl Plugin for UiMenuPlugin {
             .init_resource::<MenuNavigator>()
             .add_systems(OnEnter(GameState::Menu), spawn_menu)
             .add_systems(OnExit(GameState::Menu), despawn_menu)
+            .add_systems(Update, show_save_code_result)
             .add_systems(
                 PostUpdate,
                 (
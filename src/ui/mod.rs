@@ -1,14 +1,23 @@
+mod achievements;
 mod auto_save_icon;
 mod consent;
+mod data_error;
+mod day_night;
 mod debug;
 mod menu;
+mod offline_report;
 mod outline;
+mod prestige;
+mod random_events;
+mod slots;
+mod statistics;
 mod stats;
 mod store;
 
 pub use consent::Consent;
 pub use menu::{MenuAction, MenuActionEvent};
-pub use store::ItemPressed;
+pub use stats::StructurePressed;
+pub use store::{ItemPressed, UpgradePressed};
 
 use bevy::{prelude::*, window::WindowResized};
 
@@ -22,10 +31,18 @@ impl Plugin for UiPlugin {
             debug::UiDebugPlugin,
             outline::UiOutlinePlugin,
             consent::UiConsentPlugin,
+            data_error::UiDataErrorPlugin,
+            slots::UiSlotsPlugin,
             auto_save_icon::UiAutoSaveIconPlugin,
             stats::UiStatsPlugin,
             store::UiStorePlugin,
             menu::UiMenuPlugin,
+            prestige::UiPrestigePlugin,
+            achievements::UiAchievementsPlugin,
+            statistics::UiStatisticsPlugin,
+            offline_report::UiOfflineReportPlugin,
+            random_events::UiRandomEventsPlugin,
+            day_night::UiDayNightPlugin,
         ))
         .add_systems(Update, scale_ui);
     }
@@ -0,0 +1,266 @@
+use bevy::{
+    color::palettes::css::{LIME, RED, TOMATO},
+    prelude::*,
+    text::FontSmoothing,
+    ui::RelativeCursorPosition,
+};
+
+use crate::{
+    world::{
+        utils::{format_duration, format_money_string},
+        FloraRegistry, MapData, OfflineReport,
+    },
+    GameAssets, GameState,
+};
+
+const DEFAULT_FONT_SIZE: f32 = 25.0;
+const TITLE_FONT_SIZE: f32 = 35.0;
+
+#[derive(Component)]
+struct OfflineReportScreen;
+#[derive(Component)]
+struct CollectButton;
+
+fn text_font(assets: &GameAssets, font_size: f32) -> TextFont {
+    TextFont {
+        font: assets.pixel_font.clone(),
+        font_size,
+        font_smoothing: FontSmoothing::None,
+        ..default()
+    }
+}
+
+/// All the lines of the report, together with their color.
+fn report_lines(report: &OfflineReport, flora_registry: &FloraRegistry) -> Vec<(String, Color)> {
+    let mut away = format!(
+        "You were away for {}",
+        format_duration(report.elapsed_seconds)
+    );
+    if report.elapsed_seconds > report.counted_seconds {
+        away += &format!(
+            " (only {} counted)",
+            format_duration(report.counted_seconds)
+        );
+    }
+
+    let mut lines = vec![
+        (away, Color::WHITE),
+        (
+            format!("Earned: {}", format_money_string(report.points)),
+            LIME.into(),
+        ),
+    ];
+
+    for (index, points) in report.flora_points.iter().enumerate() {
+        if points.is_zero() {
+            continue;
+        }
+        let Some(flora) = flora_registry.by_index(index) else {
+            continue;
+        };
+        lines.push((
+            format!(
+                "{}: +{}",
+                flora_registry.name(flora),
+                format_money_string(*points)
+            ),
+            Color::WHITE,
+        ));
+    }
+
+    if !report.points_lost_to_cap.is_zero() {
+        lines.push((
+            format!(
+                "Cap reached, full for {}",
+                format_duration(report.seconds_at_cap)
+            ),
+            TOMATO.into(),
+        ));
+        lines.push((
+            format!(
+                "Lost to the cap: {}",
+                format_money_string(report.points_lost_to_cap)
+            ),
+            TOMATO.into(),
+        ));
+    }
+
+    if let Some(name) = &report.expired_event {
+        lines.push((format!("{} ended while you were away", name), Color::WHITE));
+    }
+    lines
+}
+
+fn spawn_offline_report_screen(
+    mut commands: Commands,
+    assets: Res<GameAssets>,
+    map_data: Res<MapData>,
+    report: Res<OfflineReport>,
+    q_offline_report_screens: Query<(), With<OfflineReportScreen>>,
+) {
+    if !q_offline_report_screens.is_empty() {
+        return;
+    }
+
+    // Nothing happened while the player was away (e.g. a fresh game), no need to bother them.
+    if report.points.is_zero()
+        && report.points_lost_to_cap.is_zero()
+        && report.expired_event.is_none()
+    {
+        commands.remove_resource::<OfflineReport>();
+        return;
+    }
+
+    let root = commands
+        .spawn((
+            OfflineReportScreen,
+            RelativeCursorPosition::default(),
+            GlobalZIndex(900),
+            ImageNode {
+                image: Handle::<Image>::default(),
+                color: Color::BLACK.with_alpha(0.6),
+                ..default()
+            },
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                position_type: PositionType::Absolute,
+                ..default()
+            },
+        ))
+        .id();
+
+    let panel = commands
+        .spawn((
+            ChildOf(root),
+            ImageNode {
+                image: Handle::<Image>::default(),
+                color: Color::BLACK.with_alpha(0.9),
+                ..default()
+            },
+            Node {
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(15.0),
+                align_items: AlignItems::Center,
+                padding: UiRect::all(Val::Px(40.0)),
+                ..default()
+            },
+        ))
+        .id();
+
+    commands.spawn((
+        ChildOf(panel),
+        Text::new("While you were away"),
+        text_font(&assets, TITLE_FONT_SIZE),
+    ));
+
+    for (line, color) in report_lines(&report, map_data.flora_registry()) {
+        commands.spawn((
+            ChildOf(panel),
+            Text::new(line),
+            text_font(&assets, DEFAULT_FONT_SIZE),
+            TextColor(color),
+        ));
+    }
+
+    commands.spawn((
+        ChildOf(panel),
+        CollectButton,
+        Button,
+        Node {
+            margin: UiRect::top(Val::Px(15.0)),
+            ..default()
+        },
+        Text::new("Collect"),
+        text_font(&assets, TITLE_FONT_SIZE),
+        TextColor(Color::WHITE),
+    ));
+}
+
+fn close_offline_report_screen(
+    mut commands: Commands,
+    q_offline_report_screens: Query<Entity, With<OfflineReportScreen>>,
+    q_collect_buttons: Query<&Interaction, (With<CollectButton>, Changed<Interaction>)>,
+) {
+    if !q_collect_buttons
+        .iter()
+        .any(|interaction| *interaction == Interaction::Pressed)
+    {
+        return;
+    }
+
+    for entity in &q_offline_report_screens {
+        commands.entity(entity).despawn();
+    }
+    commands.remove_resource::<OfflineReport>();
+}
+
+fn highlight_collect_button(
+    mut q_collect_buttons: Query<(&Interaction, &mut TextColor), With<CollectButton>>,
+) {
+    for (interaction, mut color) in &mut q_collect_buttons {
+        color.0 = if matches!(interaction, Interaction::Pressed | Interaction::Hovered) {
+            RED.into()
+        } else {
+            Color::WHITE
+        };
+    }
+}
+
+pub struct UiOfflineReportPlugin;
+
+impl Plugin for UiOfflineReportPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                spawn_offline_report_screen.run_if(
+                    resource_exists::<OfflineReport>
+                        .and(resource_exists::<GameAssets>)
+                        .and(resource_exists::<MapData>),
+                ),
+                close_offline_report_screen,
+                highlight_collect_button,
+            )
+                .chain()
+                .run_if(in_state(GameState::Gaming)),
+        );
+    }
+}
+
+#[test]
+fn validate_offline_report_lines() {
+    use crate::world::BigNumber;
+
+    let flora_registry = FloraRegistry::default();
+    let mut report = OfflineReport {
+        elapsed_seconds: 3600,
+        counted_seconds: 3600,
+        points: 500u64.into(),
+        flora_points: vec![BigNumber::ZERO; flora_registry.len()],
+        seconds_at_cap: 0,
+        points_lost_to_cap: BigNumber::ZERO,
+        expired_event: None,
+    };
+    report.flora_points[flora_registry.get("carrot").unwrap().index()] = 500u64.into();
+
+    let lines = report_lines(&report, &flora_registry);
+    assert_eq!(lines.len(), 3);
+    assert_eq!(lines[0].0, "You were away for 1h 0m");
+    assert_eq!(lines[2].0, "Carrot: +$500");
+
+    report.elapsed_seconds = 3600 * 20;
+    report.seconds_at_cap = 60;
+    report.points_lost_to_cap = 100u64.into();
+    let lines = report_lines(&report, &flora_registry);
+    assert_eq!(lines.len(), 5);
+    assert_eq!(lines[0].0, "You were away for 20h 0m (only 1h 0m counted)");
+    assert_eq!(lines[3].0, "Cap reached, full for 1m 0s");
+
+    report.expired_event = Some("Bumper Harvest".to_string());
+    let lines = report_lines(&report, &flora_registry);
+    assert_eq!(lines.len(), 6);
+    assert_eq!(lines[5].0, "Bumper Harvest ended while you were away");
+}
@@ -0,0 +1,281 @@
+use bevy::{
+    color::palettes::css::{GRAY, RED},
+    prelude::*,
+    text::FontSmoothing,
+};
+
+use crate::{
+    world::{
+        can_rebirth, seeds_for_run, utils::format_money_string, PrestigeAction,
+        PrestigeActionEvent, PrestigeUpgrade, ProgressionCore, PRESTIGE_POINTS_THRESHOLD,
+    },
+    GameAssets, GameState,
+};
+
+const DEFAULT_FONT_SIZE: f32 = 25.0;
+const TITLE_FONT_SIZE: f32 = 35.0;
+const UPGRADE_NAME_WIDTH: f32 = 420.0;
+const UPGRADES: [PrestigeUpgrade; 3] = [
+    PrestigeUpgrade::Pps,
+    PrestigeUpgrade::Grass,
+    PrestigeUpgrade::Cap,
+];
+
+#[derive(Component)]
+struct PrestigeScreen;
+
+#[derive(Component, Clone, Copy)]
+enum PrestigeText {
+    Seeds,
+    Rebirths,
+    LifetimePoints,
+    Upgrade(PrestigeUpgrade),
+}
+
+#[derive(Component, Clone, Copy, PartialEq)]
+enum PrestigeButton {
+    Rebirth,
+    Upgrade(PrestigeUpgrade),
+    Back,
+}
+
+impl PrestigeText {
+    fn string(self, core: &ProgressionCore) -> String {
+        match self {
+            Self::Seeds => format!("Seeds: {}", core.prestige.seeds),
+            Self::Rebirths => format!("Rebirths: {}", core.prestige.rebirths),
+            Self::LifetimePoints => format!(
+                "Earned this run: {}/{}",
+                format_money_string(core.lifetime_points),
+                format_money_string(PRESTIGE_POINTS_THRESHOLD.into())
+            ),
+            Self::Upgrade(upgrade) => format!(
+                "{} x{:.2} (Lv {})",
+                upgrade.string(),
+                core.prestige.multiplier(upgrade).to_f64(),
+                core.prestige.level(upgrade)
+            ),
+        }
+    }
+}
+
+impl PrestigeButton {
+    fn string(self, core: &ProgressionCore) -> String {
+        match self {
+            Self::Rebirth => format!("Rebirth (+{} Seeds)", seeds_for_run(core.lifetime_points)),
+            Self::Upgrade(upgrade) => {
+                format!("Buy ({} Seeds)", core.prestige.upgrade_cost(upgrade))
+            }
+            Self::Back => "Back".to_string(),
+        }
+    }
+
+    fn is_available(self, core: &ProgressionCore) -> bool {
+        match self {
+            Self::Rebirth => can_rebirth(core),
+            Self::Upgrade(upgrade) => core.prestige.seeds >= core.prestige.upgrade_cost(upgrade),
+            Self::Back => true,
+        }
+    }
+}
+
+fn text_font(assets: &GameAssets, font_size: f32) -> TextFont {
+    TextFont {
+        font: assets.pixel_font.clone(),
+        font_size,
+        font_smoothing: FontSmoothing::None,
+        ..default()
+    }
+}
+
+fn spawn_prestige_text(
+    commands: &mut Commands,
+    assets: &GameAssets,
+    parent: Entity,
+    text: PrestigeText,
+) -> Entity {
+    commands
+        .spawn((
+            ChildOf(parent),
+            text,
+            Text::default(),
+            text_font(assets, DEFAULT_FONT_SIZE),
+        ))
+        .id()
+}
+
+fn spawn_prestige_button(
+    commands: &mut Commands,
+    assets: &GameAssets,
+    parent: Entity,
+    button: PrestigeButton,
+) {
+    commands.spawn((
+        ChildOf(parent),
+        Button,
+        button,
+        Text::default(),
+        text_font(assets, DEFAULT_FONT_SIZE),
+        TextColor(Color::WHITE),
+    ));
+}
+
+fn spawn_prestige_screen(mut commands: Commands, assets: Res<GameAssets>) {
+    let root = commands
+        .spawn((
+            PrestigeScreen,
+            GlobalZIndex(1000),
+            ImageNode {
+                image: Handle::<Image>::default(),
+                color: Color::BLACK.with_alpha(0.9),
+                ..default()
+            },
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(30.0),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                position_type: PositionType::Absolute,
+                ..default()
+            },
+        ))
+        .id();
+
+    commands.spawn((
+        ChildOf(root),
+        Text::new("Prestige"),
+        text_font(&assets, TITLE_FONT_SIZE),
+    ));
+
+    for text in [
+        PrestigeText::Seeds,
+        PrestigeText::Rebirths,
+        PrestigeText::LifetimePoints,
+    ] {
+        spawn_prestige_text(&mut commands, &assets, root, text);
+    }
+
+    for upgrade in UPGRADES {
+        let row = commands
+            .spawn((
+                ChildOf(root),
+                Node {
+                    flex_direction: FlexDirection::Row,
+                    column_gap: Val::Px(30.0),
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+            ))
+            .id();
+
+        let name = spawn_prestige_text(&mut commands, &assets, row, PrestigeText::Upgrade(upgrade));
+        commands.entity(name).insert(Node {
+            width: Val::Px(UPGRADE_NAME_WIDTH),
+            ..default()
+        });
+        spawn_prestige_button(
+            &mut commands,
+            &assets,
+            row,
+            PrestigeButton::Upgrade(upgrade),
+        );
+    }
+
+    spawn_prestige_button(&mut commands, &assets, root, PrestigeButton::Rebirth);
+    spawn_prestige_button(&mut commands, &assets, root, PrestigeButton::Back);
+}
+
+fn despawn_prestige_screen(
+    mut commands: Commands,
+    q_prestige_screens: Query<Entity, With<PrestigeScreen>>,
+) {
+    for entity in &q_prestige_screens {
+        commands.entity(entity).despawn();
+    }
+}
+
+fn update_prestige_texts(
+    core: Res<ProgressionCore>,
+    mut q_texts: Query<(&mut Text, &PrestigeText)>,
+    mut q_buttons: Query<(&mut Text, &PrestigeButton), Without<PrestigeText>>,
+) {
+    for (mut text, prestige_text) in &mut q_texts {
+        text.0 = prestige_text.string(&core);
+    }
+    for (mut text, button) in &mut q_buttons {
+        text.0 = button.string(&core);
+    }
+}
+
+fn handle_prestige_buttons(
+    core: Res<ProgressionCore>,
+    mut next_state: ResMut<NextState<GameState>>,
+    q_buttons: Query<(&Interaction, &PrestigeButton), Changed<Interaction>>,
+    mut ev_prestige_action: EventWriter<PrestigeActionEvent>,
+) {
+    for (interaction, button) in &q_buttons {
+        if *interaction != Interaction::Pressed || !button.is_available(&core) {
+            continue;
+        }
+
+        let action = match *button {
+            PrestigeButton::Rebirth => PrestigeAction::Rebirth,
+            PrestigeButton::Upgrade(upgrade) => PrestigeAction::Upgrade(upgrade),
+            PrestigeButton::Back => {
+                next_state.set(GameState::Menu);
+                continue;
+            }
+        };
+        ev_prestige_action.write(PrestigeActionEvent { action });
+    }
+}
+
+fn close_prestige_screen_on_escape(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if keys.just_pressed(KeyCode::Escape) {
+        next_state.set(GameState::Menu);
+    }
+}
+
+fn highlight_prestige_buttons(
+    core: Res<ProgressionCore>,
+    mut q_buttons: Query<(&Interaction, &PrestigeButton, &mut TextColor)>,
+) {
+    for (interaction, button, mut color) in &mut q_buttons {
+        color.0 = if !button.is_available(&core) {
+            GRAY.into()
+        } else if matches!(interaction, Interaction::Pressed | Interaction::Hovered) {
+            RED.into()
+        } else {
+            Color::WHITE
+        };
+    }
+}
+
+pub struct UiPrestigePlugin;
+
+impl Plugin for UiPrestigePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(GameState::Prestige), spawn_prestige_screen)
+            .add_systems(OnExit(GameState::Prestige), despawn_prestige_screen)
+            .add_systems(
+                Update,
+                (
+                    handle_prestige_buttons,
+                    close_prestige_screen_on_escape,
+                    update_prestige_texts,
+                    highlight_prestige_buttons,
+                )
+                    .chain()
+                    .run_if(
+                        in_state(GameState::Prestige)
+                            .and(resource_exists::<GameAssets>)
+                            .and(resource_exists::<ProgressionCore>),
+                    ),
+            );
+    }
+}
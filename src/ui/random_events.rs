@@ -0,0 +1,169 @@
+use bevy::{
+    color::palettes::css::{LIME, TOMATO},
+    prelude::*,
+    text::FontSmoothing,
+};
+
+use crate::{
+    world::{utils::format_duration, MapData, ProgressionCore, RandomEventStarted},
+    GameAssets, GameState,
+};
+
+use super::outline::TextOutline;
+
+/// How long the announcement of a new event stays on screen.
+const ANNOUNCEMENT_DURATION: f32 = 3.0;
+
+#[derive(Component)]
+struct RandomEventText;
+#[derive(Component)]
+struct RandomEventAnnouncement {
+    timer: Timer,
+}
+
+fn spawn_random_event_text(mut commands: Commands, assets: Res<GameAssets>) {
+    commands.spawn((
+        RandomEventText,
+        Visibility::Hidden,
+        Node {
+            width: Val::Percent(100.0),
+            top: Val::Px(20.0),
+            position_type: PositionType::Absolute,
+            ..default()
+        },
+        TextOutline::new(
+            String::new(),
+            1.0,
+            Color::WHITE,
+            Color::BLACK,
+            TextFont {
+                font: assets.pixel_font.clone(),
+                font_size: 25.0,
+                font_smoothing: FontSmoothing::None,
+                ..default()
+            },
+            true,
+        ),
+    ));
+}
+
+/// Show the running event with its effect and how long it still goes on.
+fn update_random_event_text(
+    core: Res<ProgressionCore>,
+    map_data: Res<MapData>,
+    q_random_event_text: Single<(&mut TextOutline, &mut Visibility), With<RandomEventText>>,
+) {
+    let (mut outline, mut visibility) = q_random_event_text.into_inner();
+
+    let (Some(active), Some(event)) = (&core.random_events.active, core.active_event(&map_data))
+    else {
+        *visibility = Visibility::Hidden;
+        return;
+    };
+
+    let text = format!(
+        "{} ({}) {}",
+        event.name,
+        event
+            .modifier
+            .description(&event.target, map_data.flora_registry()),
+        format_duration(active.remaining)
+    );
+    let color = if event.modifier.is_harmful() {
+        TOMATO.into()
+    } else {
+        LIME.into()
+    };
+
+    if outline.text != text {
+        outline.text = text;
+    }
+    if outline.color != color {
+        outline.color = color;
+    }
+    *visibility = Visibility::Inherited;
+}
+
+fn spawn_random_event_announcements(
+    mut commands: Commands,
+    assets: Res<GameAssets>,
+    map_data: Res<MapData>,
+    mut ev_random_event_started: EventReader<RandomEventStarted>,
+    q_announcements: Query<Entity, With<RandomEventAnnouncement>>,
+) {
+    let Some(ev) = ev_random_event_started.read().last() else {
+        return;
+    };
+    let Some(event) = map_data.random_event_by_id(&ev.id) else {
+        error!(
+            "started random event '{}' doesn't exist, must never happen!",
+            ev.id
+        );
+        return;
+    };
+
+    for entity in &q_announcements {
+        commands.entity(entity).despawn();
+    }
+
+    let color = if event.modifier.is_harmful() {
+        TOMATO.into()
+    } else {
+        LIME.into()
+    };
+    commands.spawn((
+        RandomEventAnnouncement {
+            timer: Timer::from_seconds(ANNOUNCEMENT_DURATION, TimerMode::Once),
+        },
+        Node {
+            width: Val::Percent(100.0),
+            top: Val::Percent(30.0),
+            position_type: PositionType::Absolute,
+            ..default()
+        },
+        TextOutline::new(
+            format!("{}!", event.name),
+            1.0,
+            color,
+            Color::BLACK,
+            TextFont {
+                font: assets.pixel_font.clone(),
+                font_size: 50.0,
+                font_smoothing: FontSmoothing::None,
+                ..default()
+            },
+            true,
+        ),
+    ));
+}
+
+fn despawn_random_event_announcements(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut q_announcements: Query<(Entity, &mut RandomEventAnnouncement)>,
+) {
+    for (entity, mut announcement) in &mut q_announcements {
+        announcement.timer.tick(time.delta());
+        if announcement.timer.just_finished() {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+pub struct UiRandomEventsPlugin;
+
+impl Plugin for UiRandomEventsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnExit(GameState::AssetLoading), spawn_random_event_text)
+            .add_systems(
+                Update,
+                (
+                    update_random_event_text,
+                    despawn_random_event_announcements,
+                    spawn_random_event_announcements.run_if(resource_exists::<GameAssets>),
+                )
+                    .chain()
+                    .run_if(resource_exists::<ProgressionCore>.and(resource_exists::<MapData>)),
+            );
+    }
+}
@@ -0,0 +1,428 @@
+use bevy::{
+    color::palettes::css::{GRAY, RED},
+    input::{
+        keyboard::{Key, KeyboardInput},
+        ButtonState,
+    },
+    prelude::*,
+    text::FontSmoothing,
+};
+
+use crate::{
+    world::{SaveSlots, SlotAction, SlotActionEvent, MAX_SLOT_NAME_LENGTH},
+    GameAssets, GameState,
+};
+
+const DEFAULT_FONT_SIZE: f32 = 25.0;
+const TITLE_FONT_SIZE: f32 = 35.0;
+const SLOT_NAME_WIDTH: f32 = 450.0;
+/// The digits of `u64::MAX`.
+const MAX_SEED_LENGTH: usize = 20;
+
+#[derive(Component)]
+struct SlotScreen;
+#[derive(Component)]
+struct SlotList;
+
+#[derive(Component, Clone, Copy, PartialEq)]
+enum SlotButton {
+    New,
+    NewFromSeed,
+    Load(u64),
+    Rename(u64),
+    Duplicate(u64),
+    Delete(u64),
+}
+
+/// Local UI state of the slot picker, the slots themselves live in `SaveSlots`.
+#[derive(Resource, Default)]
+struct SlotPickerState {
+    /// Slot that is currently being renamed together with the typed name.
+    renaming: Option<(u64, String)>,
+    /// The typed world seed of a new garden.
+    seed: Option<String>,
+    /// Deleting is only done after pressing the button a second time.
+    pending_delete: Option<u64>,
+}
+
+impl SlotButton {
+    fn string(self, state: &SlotPickerState) -> String {
+        if let (Self::NewFromSeed, Some(seed)) = (self, &state.seed) {
+            return format!("Seed: {}_", seed);
+        }
+
+        let s = match self {
+            Self::New => "New Garden",
+            Self::NewFromSeed => "From Seed",
+            Self::Load(_) => "Load",
+            Self::Rename(id) if matches!(state.renaming, Some((renaming_id, _)) if renaming_id == id) => {
+                "Done"
+            }
+            Self::Rename(_) => "Rename",
+            Self::Duplicate(_) => "Copy",
+            Self::Delete(id) if state.pending_delete == Some(id) => "Sure?",
+            Self::Delete(_) => "Delete",
+        };
+
+        s.to_string()
+    }
+}
+
+fn text_font(assets: &GameAssets, font_size: f32) -> TextFont {
+    TextFont {
+        font: assets.pixel_font.clone(),
+        font_size,
+        font_smoothing: FontSmoothing::None,
+        ..default()
+    }
+}
+
+fn spawn_slot_button(
+    commands: &mut Commands,
+    assets: &GameAssets,
+    state: &SlotPickerState,
+    parent: Entity,
+    button: SlotButton,
+) {
+    commands.spawn((
+        ChildOf(parent),
+        Button,
+        button,
+        Text(button.string(state)),
+        text_font(assets, DEFAULT_FONT_SIZE),
+        TextColor(Color::WHITE),
+    ));
+}
+
+fn spawn_slot_screen(mut commands: Commands, assets: Res<GameAssets>) {
+    let root = commands
+        .spawn((
+            SlotScreen,
+            GlobalZIndex(1000),
+            ImageNode {
+                image: Handle::<Image>::default(),
+                color: Color::BLACK,
+                ..default()
+            },
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(40.0),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                position_type: PositionType::Absolute,
+                ..default()
+            },
+        ))
+        .id();
+
+    commands.spawn((
+        ChildOf(root),
+        Text::new("Select Garden"),
+        text_font(&assets, TITLE_FONT_SIZE),
+    ));
+
+    commands.spawn((
+        ChildOf(root),
+        SlotList,
+        Node {
+            flex_direction: FlexDirection::Column,
+            row_gap: Val::Px(20.0),
+            align_items: AlignItems::Center,
+            ..default()
+        },
+    ));
+}
+
+fn despawn_slot_screen(mut commands: Commands, q_slot_screens: Query<Entity, With<SlotScreen>>) {
+    for entity in &q_slot_screens {
+        commands.entity(entity).despawn();
+    }
+}
+
+fn rebuild_slot_list(
+    mut commands: Commands,
+    assets: Res<GameAssets>,
+    slots: Res<SaveSlots>,
+    state: Res<SlotPickerState>,
+    q_slot_list: Query<Entity, With<SlotList>>,
+) {
+    let Ok(list) = q_slot_list.single() else {
+        return;
+    };
+
+    commands.entity(list).despawn_related::<Children>();
+
+    for entry in &slots.entries {
+        let row = commands
+            .spawn((
+                ChildOf(list),
+                Node {
+                    flex_direction: FlexDirection::Row,
+                    column_gap: Val::Px(30.0),
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+            ))
+            .id();
+
+        let name = match &state.renaming {
+            Some((id, buffer)) if *id == entry.id => format!("{}_", buffer),
+            _ => entry.name.clone(),
+        };
+
+        commands.spawn((
+            ChildOf(row),
+            Text(name),
+            text_font(&assets, DEFAULT_FONT_SIZE),
+            Node {
+                width: Val::Px(SLOT_NAME_WIDTH),
+                ..default()
+            },
+        ));
+
+        for button in [
+            SlotButton::Load(entry.id),
+            SlotButton::Rename(entry.id),
+            SlotButton::Duplicate(entry.id),
+            SlotButton::Delete(entry.id),
+        ] {
+            spawn_slot_button(&mut commands, &assets, &state, row, button);
+        }
+    }
+
+    let row = commands
+        .spawn((
+            ChildOf(list),
+            Node {
+                flex_direction: FlexDirection::Row,
+                column_gap: Val::Px(30.0),
+                ..default()
+            },
+        ))
+        .id();
+    spawn_slot_button(&mut commands, &assets, &state, row, SlotButton::New);
+    spawn_slot_button(&mut commands, &assets, &state, row, SlotButton::NewFromSeed);
+}
+
+fn handle_slot_buttons(
+    mut state: ResMut<SlotPickerState>,
+    slots: Res<SaveSlots>,
+    q_buttons: Query<(&Interaction, &SlotButton), Changed<Interaction>>,
+    mut ev_slot_action: EventWriter<SlotActionEvent>,
+) {
+    for (interaction, button) in &q_buttons {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        if !matches!(button, SlotButton::Delete(_)) {
+            state.pending_delete = None;
+        }
+        if !matches!(button, SlotButton::NewFromSeed) {
+            state.seed = None;
+        }
+
+        let action = match *button {
+            SlotButton::New => Some(SlotAction::Create { seed: None }),
+            SlotButton::NewFromSeed => match state.seed.take() {
+                Some(seed) => seed
+                    .parse()
+                    .ok()
+                    .map(|seed| SlotAction::Create { seed: Some(seed) }),
+                None => {
+                    state.renaming = None;
+                    state.seed = Some(String::new());
+                    None
+                }
+            },
+            SlotButton::Load(id) => Some(SlotAction::Load { id }),
+            SlotButton::Duplicate(id) => Some(SlotAction::Duplicate { id }),
+            SlotButton::Rename(id) => match state.renaming.take() {
+                Some((renaming_id, name)) if renaming_id == id => {
+                    Some(SlotAction::Rename { id, name })
+                }
+                _ => {
+                    let name = slots
+                        .entries
+                        .iter()
+                        .find(|e| e.id == id)
+                        .map(|e| e.name.clone())
+                        .unwrap_or_default();
+                    state.renaming = Some((id, name));
+                    None
+                }
+            },
+            SlotButton::Delete(id) => {
+                if state.pending_delete == Some(id) {
+                    state.pending_delete = None;
+                    Some(SlotAction::Delete { id })
+                } else {
+                    state.pending_delete = Some(id);
+                    None
+                }
+            }
+        };
+
+        if let Some(action) = action {
+            ev_slot_action.write(SlotActionEvent { action });
+        }
+    }
+}
+
+fn handle_rename_input(
+    mut state: ResMut<SlotPickerState>,
+    mut ev_keyboard_input: EventReader<KeyboardInput>,
+    mut ev_slot_action: EventWriter<SlotActionEvent>,
+) {
+    if state.renaming.is_none() {
+        ev_keyboard_input.clear();
+        return;
+    }
+
+    for ev in ev_keyboard_input.read() {
+        if ev.state != ButtonState::Pressed {
+            continue;
+        }
+
+        let Some((id, buffer)) = state.renaming.as_mut() else {
+            return;
+        };
+
+        match &ev.logical_key {
+            Key::Enter => {
+                let action = SlotAction::Rename {
+                    id: *id,
+                    name: buffer.clone(),
+                };
+                ev_slot_action.write(SlotActionEvent { action });
+                state.renaming = None;
+                return;
+            }
+            Key::Escape => {
+                state.renaming = None;
+                return;
+            }
+            Key::Backspace => {
+                buffer.pop();
+            }
+            Key::Space if buffer.chars().count() < MAX_SLOT_NAME_LENGTH => {
+                buffer.push(' ');
+            }
+            Key::Character(chars) => {
+                for c in chars.chars().filter(|c| !c.is_control()) {
+                    if buffer.chars().count() < MAX_SLOT_NAME_LENGTH {
+                        buffer.push(c);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn handle_seed_input(
+    mut state: ResMut<SlotPickerState>,
+    mut ev_keyboard_input: EventReader<KeyboardInput>,
+    mut ev_slot_action: EventWriter<SlotActionEvent>,
+) {
+    if state.seed.is_none() {
+        ev_keyboard_input.clear();
+        return;
+    }
+
+    for ev in ev_keyboard_input.read() {
+        if ev.state != ButtonState::Pressed {
+            continue;
+        }
+
+        let Some(buffer) = state.seed.as_mut() else {
+            return;
+        };
+
+        match &ev.logical_key {
+            Key::Enter => {
+                // Seeds that don't fit simply don't create anything.
+                if let Ok(seed) = buffer.parse() {
+                    let action = SlotAction::Create { seed: Some(seed) };
+                    ev_slot_action.write(SlotActionEvent { action });
+                }
+                state.seed = None;
+                return;
+            }
+            Key::Escape => {
+                state.seed = None;
+                return;
+            }
+            Key::Backspace => {
+                buffer.pop();
+            }
+            Key::Character(chars) => {
+                for c in chars.chars().filter(|c| c.is_ascii_digit()) {
+                    if buffer.len() < MAX_SEED_LENGTH {
+                        buffer.push(c);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn highlight_slot_buttons(
+    state: Res<SlotPickerState>,
+    mut q_buttons: Query<(&Interaction, &SlotButton, &mut TextColor)>,
+) {
+    for (interaction, button, mut color) in &mut q_buttons {
+        let is_highlighted = matches!(interaction, Interaction::Pressed | Interaction::Hovered);
+        let is_pending_delete =
+            matches!(button, SlotButton::Delete(id) if state.pending_delete == Some(*id));
+        let is_typing_elsewhere = (state.renaming.is_some()
+            && !matches!(button, SlotButton::Rename(_)))
+            || (state.seed.is_some() && !matches!(button, SlotButton::NewFromSeed));
+
+        color.0 = if is_highlighted || is_pending_delete {
+            RED.into()
+        } else if is_typing_elsewhere {
+            GRAY.into()
+        } else {
+            Color::WHITE
+        };
+    }
+}
+
+fn reset_slot_picker_state(mut state: ResMut<SlotPickerState>) {
+    *state = SlotPickerState::default();
+}
+
+pub struct UiSlotsPlugin;
+
+impl Plugin for UiSlotsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SlotPickerState>()
+            .add_systems(OnEnter(GameState::SlotSelect), spawn_slot_screen)
+            .add_systems(
+                OnExit(GameState::SlotSelect),
+                (despawn_slot_screen, reset_slot_picker_state),
+            )
+            .add_systems(
+                Update,
+                (
+                    handle_slot_buttons,
+                    handle_rename_input,
+                    handle_seed_input,
+                    rebuild_slot_list.run_if(
+                        resource_changed::<SaveSlots>.or(resource_changed::<SlotPickerState>),
+                    ),
+                    highlight_slot_buttons,
+                )
+                    .chain()
+                    .run_if(
+                        in_state(GameState::SlotSelect)
+                            .and(resource_exists::<GameAssets>)
+                            .and(resource_exists::<SaveSlots>),
+                    ),
+            );
+    }
+}
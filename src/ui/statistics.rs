@@ -0,0 +1,273 @@
+use bevy::{
+    color::palettes::css::{GRAY, RED},
+    prelude::*,
+    text::FontSmoothing,
+};
+
+use crate::{
+    world::{
+        utils::{format_duration, format_money_string},
+        MapData, ProgressionCore,
+    },
+    GameAssets, GameState,
+};
+
+const DEFAULT_FONT_SIZE: f32 = 20.0;
+const TITLE_FONT_SIZE: f32 = 35.0;
+const LABEL_WIDTH: f32 = 300.0;
+const VALUE_WIDTH: f32 = 200.0;
+
+#[derive(Component)]
+struct StatisticsScreen;
+#[derive(Component)]
+struct BackButton;
+
+fn text_font(assets: &GameAssets, font_size: f32) -> TextFont {
+    TextFont {
+        font: assets.pixel_font.clone(),
+        font_size,
+        font_smoothing: FontSmoothing::None,
+        ..default()
+    }
+}
+
+fn general_rows(core: &ProgressionCore) -> Vec<[String; 2]> {
+    let statistics = &core.statistics;
+    vec![
+        [
+            "Points earned".to_string(),
+            format_money_string(statistics.points_earned),
+        ],
+        [
+            "Play time".to_string(),
+            format_duration(statistics.play_seconds),
+        ],
+        [
+            "Offline time".to_string(),
+            format_duration(statistics.offline_seconds),
+        ],
+        ["Grass cut".to_string(), statistics.grass_cut.to_string()],
+        ["Slashes".to_string(), statistics.slashes.to_string()],
+        [
+            "Flora bought".to_string(),
+            statistics.items_bought.to_string(),
+        ],
+        [
+            "Cap reached".to_string(),
+            statistics.times_capped.to_string(),
+        ],
+        ["Rebirths".to_string(), core.prestige.rebirths.to_string()],
+        // Lets players share their garden, see `SlotAction::Create`.
+        ["World seed".to_string(), core.world_seed.to_string()],
+    ]
+}
+
+fn flora_rows(core: &ProgressionCore, map_data: &MapData) -> Vec<[String; 3]> {
+    let statistics = &core.statistics;
+    let flora_registry = map_data.flora_registry();
+    flora_registry
+        .iter()
+        .map(|flora| {
+            [
+                flora_registry.name(flora).to_string(),
+                statistics.flora_bought(flora).to_string(),
+                format_money_string(statistics.flora_points(flora)),
+            ]
+        })
+        .collect()
+}
+
+fn spawn_row<const N: usize>(
+    commands: &mut Commands,
+    assets: &GameAssets,
+    parent: Entity,
+    columns: [String; N],
+    color: Color,
+) {
+    let row = commands
+        .spawn((
+            ChildOf(parent),
+            Node {
+                flex_direction: FlexDirection::Row,
+                ..default()
+            },
+        ))
+        .id();
+
+    for (i, column) in columns.into_iter().enumerate() {
+        commands.spawn((
+            ChildOf(row),
+            Node {
+                width: Val::Px(if i == 0 { LABEL_WIDTH } else { VALUE_WIDTH }),
+                ..default()
+            },
+            Text::new(column),
+            text_font(assets, DEFAULT_FONT_SIZE),
+            TextColor(color),
+        ));
+    }
+}
+
+fn spawn_statistics_screen(
+    mut commands: Commands,
+    assets: Res<GameAssets>,
+    core: Res<ProgressionCore>,
+    map_data: Res<MapData>,
+) {
+    let root = commands
+        .spawn((
+            StatisticsScreen,
+            GlobalZIndex(1000),
+            ImageNode {
+                image: Handle::<Image>::default(),
+                color: Color::BLACK.with_alpha(0.9),
+                ..default()
+            },
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(10.0),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                position_type: PositionType::Absolute,
+                ..default()
+            },
+        ))
+        .id();
+
+    commands.spawn((
+        ChildOf(root),
+        Text::new("Statistics"),
+        text_font(&assets, TITLE_FONT_SIZE),
+    ));
+
+    for row in general_rows(&core) {
+        spawn_row(&mut commands, &assets, root, row, Color::WHITE);
+    }
+
+    commands.spawn((
+        ChildOf(root),
+        Node {
+            height: Val::Px(10.0),
+            ..default()
+        },
+    ));
+    spawn_row(
+        &mut commands,
+        &assets,
+        root,
+        [
+            "Flora".to_string(),
+            "Bought".to_string(),
+            "Earned".to_string(),
+        ],
+        GRAY.into(),
+    );
+    for row in flora_rows(&core, &map_data) {
+        spawn_row(&mut commands, &assets, root, row, Color::WHITE);
+    }
+
+    commands.spawn((
+        ChildOf(root),
+        BackButton,
+        Button,
+        Node {
+            margin: UiRect::top(Val::Px(20.0)),
+            ..default()
+        },
+        Text::new("Back"),
+        text_font(&assets, 25.0),
+        TextColor(Color::WHITE),
+    ));
+}
+
+fn despawn_statistics_screen(
+    mut commands: Commands,
+    q_statistics_screens: Query<Entity, With<StatisticsScreen>>,
+) {
+    for entity in &q_statistics_screens {
+        commands.entity(entity).despawn();
+    }
+}
+
+fn handle_back_button(
+    mut next_state: ResMut<NextState<GameState>>,
+    q_back_buttons: Query<&Interaction, (With<BackButton>, Changed<Interaction>)>,
+) {
+    if q_back_buttons
+        .iter()
+        .any(|interaction| *interaction == Interaction::Pressed)
+    {
+        next_state.set(GameState::Menu);
+    }
+}
+
+fn close_statistics_screen_on_escape(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if keys.just_pressed(KeyCode::Escape) {
+        next_state.set(GameState::Menu);
+    }
+}
+
+fn highlight_back_button(
+    mut q_back_buttons: Query<(&Interaction, &mut TextColor), With<BackButton>>,
+) {
+    for (interaction, mut color) in &mut q_back_buttons {
+        color.0 = if matches!(interaction, Interaction::Pressed | Interaction::Hovered) {
+            RED.into()
+        } else {
+            Color::WHITE
+        };
+    }
+}
+
+pub struct UiStatisticsPlugin;
+
+impl Plugin for UiStatisticsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            OnEnter(GameState::Statistics),
+            spawn_statistics_screen.run_if(
+                resource_exists::<GameAssets>
+                    .and(resource_exists::<ProgressionCore>)
+                    .and(resource_exists::<MapData>),
+            ),
+        )
+        .add_systems(OnExit(GameState::Statistics), despawn_statistics_screen)
+        .add_systems(
+            Update,
+            (
+                handle_back_button,
+                close_statistics_screen_on_escape,
+                highlight_back_button,
+            )
+                .chain()
+                .run_if(in_state(GameState::Statistics)),
+        );
+    }
+}
+
+#[test]
+fn validate_statistics_rows() {
+    let map_data = MapData::default();
+    let corn = map_data.flora_registry().get("corn").unwrap();
+    let mut core = ProgressionCore::default();
+    core.statistics.play_seconds = 3 * 3600 + 60;
+    core.statistics.flora_bought = vec![0; map_data.flora_registry().len()];
+    core.statistics.flora_bought[corn.index()] = 4;
+    core.statistics.add_points_of_flora(corn, 250u64.into());
+
+    let general = general_rows(&core);
+    assert!(general.contains(&["Play time".to_string(), "3h 1m".to_string()]));
+    assert!(general.contains(&["World seed".to_string(), core.world_seed.to_string()]));
+
+    let flora = flora_rows(&core, &map_data);
+    assert_eq!(flora.len(), map_data.flora_registry().len());
+    assert_eq!(
+        flora[corn.index()],
+        ["Corn".to_string(), "4".to_string(), "$250".to_string()]
+    );
+}
@@ -7,7 +7,7 @@ use bevy::{
 
 use crate::{
     player::GamingInput,
-    world::{utils::format_money_string, ProgressionCore, POINTS_CAP_COST_INCREASE_PER_SILO},
+    world::{utils::format_money_string, MapData, ProgressionCore, Structure, SILO_ID},
     GameAssets, GameState,
 };
 
@@ -28,6 +28,12 @@ struct PointsPerSecondText;
 #[derive(Component)]
 struct UnaffordableOverlay;
 
+/// The `+Cap` button was pressed, it builds a silo.
+#[derive(Event)]
+pub struct StructurePressed {
+    pub structure: Structure,
+}
+
 fn spawn_stats(mut commands: Commands, assets: Res<GameAssets>) {
     let root = commands
         .spawn((
@@ -214,7 +220,7 @@ fn update_points_per_second_text(
         return;
     };
 
-    outline.text = format_money_string(core.pps.into()) + "/s";
+    outline.text = format_money_string(core.pps) + "/s";
 }
 
 fn handle_cap_increase_button_interaction(
@@ -231,6 +237,7 @@ fn handle_cap_increase_button_interaction(
 
 fn update_cap_increase_visuals(
     core: Res<ProgressionCore>,
+    map_data: Res<MapData>,
     q_cap_increase: Single<(&mut ImageNode, &CapIncrease)>,
     q_cost_text: Single<(&mut Visibility, &mut TextOutline), With<CapIncreaseCostText>>,
     q_unaffordable_overlay: Single<
@@ -248,19 +255,29 @@ fn update_cap_increase_visuals(
         (Color::WHITE, Visibility::Hidden)
     };
 
-    let cost = core.silos * POINTS_CAP_COST_INCREASE_PER_SILO;
-    cost_text.text = format_money_string(cost);
-    (cost_text.color, *unaffordable_overlay_visibility) = if cost > core.points {
-        (RED.into(), Visibility::Inherited)
+    let Some(silo) = map_data.structure_by_id(SILO_ID) else {
+        return;
+    };
+
+    // Silos that are bought but not placed yet are free.
+    cost_text.text = if core.unplaced_structures(&map_data, silo) > 0 {
+        "Free".to_string()
     } else {
-        (Color::WHITE, Visibility::Hidden)
+        format_money_string(core.structure_cost(&map_data, silo))
     };
+    (cost_text.color, *unaffordable_overlay_visibility) =
+        if core.can_place_structure(&map_data, silo) {
+            (Color::WHITE, Visibility::Hidden)
+        } else {
+            (RED.into(), Visibility::Inherited)
+        };
 }
 
-fn buy_silo(
+fn press_silo(
     gaming_input: Res<GamingInput>,
-    mut core: ResMut<ProgressionCore>,
+    map_data: Res<MapData>,
     q_cap_increase: Single<&CapIncrease>,
+    mut ev_structure_pressed: EventWriter<StructurePressed>,
 ) {
     if !gaming_input.confirm {
         return;
@@ -272,20 +289,19 @@ fn buy_silo(
         return;
     }
 
-    let cost = core.silos * POINTS_CAP_COST_INCREASE_PER_SILO;
-    if core.points < cost {
+    let Some(silo) = map_data.structure_by_id(SILO_ID) else {
+        error!("there is no silo in the structure data, must never happen!");
         return;
-    }
-
-    core.silos += 1;
-    core.points -= cost;
+    };
+    ev_structure_pressed.write(StructurePressed { structure: silo });
 }
 
 pub struct UiStatsPlugin;
 
 impl Plugin for UiStatsPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(OnExit(GameState::AssetLoading), spawn_stats)
+        app.add_event::<StructurePressed>()
+            .add_systems(OnExit(GameState::AssetLoading), spawn_stats)
             .add_systems(
                 Update,
                 (
@@ -293,10 +309,10 @@ impl Plugin for UiStatsPlugin {
                     update_points_per_second_text,
                     handle_cap_increase_button_interaction,
                     update_cap_increase_visuals,
-                    buy_silo,
+                    press_silo,
                 )
                     .chain()
-                    .run_if(resource_exists::<ProgressionCore>),
+                    .run_if(resource_exists::<ProgressionCore>.and(resource_exists::<MapData>)),
             );
     }
 }
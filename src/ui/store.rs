@@ -7,15 +7,21 @@ use bevy::{
 
 use crate::{
     player::GamingInput,
-    world::{utils::format_money_string, Flora, MapData, ProgressionCore},
-    GameAssets, GameState, DEFAULT_WINDOW_WIDTH,
+    world::{utils::format_money_string, Flora, FloraRegistry, MapData, ProgressionCore},
+    BachelorBuild, GameAssets, GameState, DEFAULT_WINDOW_WIDTH,
 };
 
 use super::outline::TextOutline;
 
 const STORE_ROOT_PADDING_VERTICAL: f32 = 40.0;
 const HORIZONTAL_ITEM_PADDING: f32 = 50.0;
-const NUMBER_OF_ITEMS_ON_PAGE: usize = 6;
+const NUMBER_OF_UPGRADES_ON_PAGE: usize = 3;
+const UPGRADE_ITEM_WIDTH: f32 = 190.0;
+const UPGRADE_ITEM_COLOR: Color = Color::srgba(0.0, 0.0, 0.0, 0.35);
+const STORE_TAB_WIDTH: f32 = 130.0;
+const STORE_TAB_HEIGHT: f32 = 35.0;
+/// Plants bought at once while holding shift, only without the building mode.
+const BULK_BUY_AMOUNT: u32 = 10;
 
 #[derive(Component)]
 struct StoreRoot;
@@ -33,26 +39,85 @@ struct ItemCountText;
 struct ItemCostText;
 #[derive(Component)]
 struct ItemUnaffordableOverlay;
+#[derive(Component)]
+struct UpgradeItemContainer;
+#[derive(Component)]
+struct UpgradeItem {
+    index: usize,
+}
+#[derive(Component, Clone, Copy)]
+enum UpgradeItemText {
+    Name,
+    Description,
+    Cost,
+}
+#[derive(Component)]
+struct StoreTabButton {
+    tab: StoreTab,
+}
 
+#[derive(Resource, Default, Clone, Copy, PartialEq)]
+enum StoreTab {
+    #[default]
+    Flora,
+    Upgrades,
+}
+
+/// One item per flora, in the order of the flora registry.
 #[derive(Resource, Default)]
 struct StorePageItems {
-    items: [Flora; NUMBER_OF_ITEMS_ON_PAGE],
-    is_affordable: [bool; NUMBER_OF_ITEMS_ON_PAGE],
+    items: Vec<Flora>,
+    is_affordable: Vec<bool>,
+}
+
+/// Indices into the upgrade data of the upgrades that are currently shown in the store.
+#[derive(Resource, Default)]
+struct StorePageUpgrades {
+    upgrades: [Option<usize>; NUMBER_OF_UPGRADES_ON_PAGE],
 }
 
 #[derive(Event)]
 pub struct ItemPressed {
     pub flora: Flora,
+    /// How many plants to buy at once, always one in the building mode.
+    pub amount: u32,
+}
+
+#[derive(Event)]
+pub struct UpgradePressed {
+    pub index: usize,
+}
+
+impl StoreTab {
+    fn string(self) -> &'static str {
+        match self {
+            Self::Flora => "Flora",
+            Self::Upgrades => "Upgrades",
+        }
+    }
 }
 
 impl StorePageItems {
-    fn get_by_index(&self, index: usize) -> Flora {
-        if index >= NUMBER_OF_ITEMS_ON_PAGE {
-            error!("trying to index store page items with index: {}, but only has: {} items total. Should never happen.", index, NUMBER_OF_ITEMS_ON_PAGE);
-            return Flora::default();
+    fn get_by_index(&self, index: usize) -> Option<Flora> {
+        if index >= self.items.len() {
+            error!("trying to index store page items with index: {}, but only has: {} items total. Should never happen.", index, self.items.len());
+            return None;
         }
 
-        self.items[index]
+        Some(self.items[index])
+    }
+
+    fn is_affordable(&self, index: usize) -> bool {
+        self.is_affordable.get(index).copied().unwrap_or_default()
+    }
+}
+
+fn buy_amount(gaming_input: &GamingInput, bachelor_build: Option<&BachelorBuild>) -> u32 {
+    let with_building = bachelor_build.is_none_or(|bachelor_build| bachelor_build.with_building);
+    if gaming_input.buy_many && !with_building {
+        BULK_BUY_AMOUNT
+    } else {
+        1
     }
 }
 
@@ -66,6 +131,7 @@ fn spawn_store_item(
     assets: &GameAssets,
     items_container: Entity,
     item: StoreItem,
+    icon: Handle<Image>,
 ) {
     let item_root = commands
         .spawn((
@@ -96,10 +162,7 @@ fn spawn_store_item(
             position_type: PositionType::Absolute,
             ..default()
         },
-        ImageNode {
-            image: Flora::default().icon(&assets),
-            ..default()
-        },
+        ImageNode::new(icon),
         ZIndex(1),
     ));
 
@@ -171,6 +234,114 @@ fn spawn_store_item(
     ));
 }
 
+fn spawn_upgrade_item(
+    commands: &mut Commands,
+    assets: &GameAssets,
+    upgrades_container: Entity,
+    item: UpgradeItem,
+) {
+    let item_root = commands
+        .spawn((
+            ChildOf(upgrades_container),
+            Button,
+            item,
+            Node {
+                width: Val::Px(UPGRADE_ITEM_WIDTH),
+                height: Val::Percent(75.0),
+                align_self: AlignSelf::Center,
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            BackgroundColor(UPGRADE_ITEM_COLOR),
+        ))
+        .id();
+
+    for (text, top, font_size) in [
+        (UpgradeItemText::Name, 10.0, 25.0),
+        (UpgradeItemText::Description, 45.0, 20.0),
+        (UpgradeItemText::Cost, 75.0, 25.0),
+    ] {
+        commands.spawn((
+            ChildOf(item_root),
+            text,
+            Node {
+                top: Val::Px(top),
+                position_type: PositionType::Absolute,
+                ..default()
+            },
+            TextOutline::new(
+                String::new(),
+                1.0,
+                Color::WHITE,
+                Color::BLACK,
+                TextFont {
+                    font: assets.pixel_font.clone(),
+                    font_size,
+                    font_smoothing: FontSmoothing::None,
+                    ..default()
+                },
+                true,
+            ),
+            ZIndex(3),
+        ));
+    }
+}
+
+fn spawn_store_tabs(commands: &mut Commands, assets: &GameAssets, root: Entity) {
+    let tabs_container = commands
+        .spawn((
+            ChildOf(root),
+            Node {
+                left: Val::Px(HORIZONTAL_ITEM_PADDING),
+                top: Val::Px(-STORE_TAB_HEIGHT),
+                column_gap: Val::Px(10.0),
+                flex_direction: FlexDirection::Row,
+                position_type: PositionType::Absolute,
+                ..default()
+            },
+        ))
+        .id();
+
+    for tab in [StoreTab::Flora, StoreTab::Upgrades] {
+        let tab_button = commands
+            .spawn((
+                ChildOf(tabs_container),
+                Button,
+                StoreTabButton { tab },
+                RelativeCursorPosition::default(),
+                Node {
+                    width: Val::Px(STORE_TAB_WIDTH),
+                    height: Val::Px(STORE_TAB_HEIGHT),
+                    justify_content: JustifyContent::Center,
+                    ..default()
+                },
+                BackgroundColor(UPGRADE_ITEM_COLOR),
+            ))
+            .id();
+
+        commands.spawn((
+            ChildOf(tab_button),
+            Node {
+                top: Val::Px(5.0),
+                ..default()
+            },
+            TextOutline::new(
+                tab.string().to_string(),
+                1.0,
+                Color::WHITE,
+                Color::BLACK,
+                TextFont {
+                    font: assets.pixel_font.clone(),
+                    font_size: 25.0,
+                    font_smoothing: FontSmoothing::None,
+                    ..default()
+                },
+                true,
+            ),
+        ));
+    }
+}
+
 fn spawn_store(mut commands: Commands, assets: Res<GameAssets>, images: Res<Assets<Image>>) {
     let Some(store_bar_image) = images.get(&assets.store_bar) else {
         return;
@@ -203,11 +374,30 @@ fn spawn_store(mut commands: Commands, assets: Res<GameAssets>, images: Res<Asse
         ))
         .id();
 
-    let items_container = commands
+    commands.spawn((
+        ChildOf(root),
+        StoreItemContainer,
+        Navigator::default(),
+        Node {
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            padding: UiRect {
+                left: Val::Px(HORIZONTAL_ITEM_PADDING),
+                right: Val::Px(HORIZONTAL_ITEM_PADDING),
+                ..default()
+            },
+            flex_direction: FlexDirection::Row,
+            justify_content: JustifyContent::SpaceBetween,
+            position_type: PositionType::Absolute,
+            ..default()
+        },
+    ));
+
+    let upgrades_container = commands
         .spawn((
             ChildOf(root),
-            StoreItemContainer,
-            Navigator::default(),
+            UpgradeItemContainer,
+            Visibility::Hidden,
             Node {
                 width: Val::Percent(100.0),
                 height: Val::Percent(100.0),
@@ -224,8 +414,49 @@ fn spawn_store(mut commands: Commands, assets: Res<GameAssets>, images: Res<Asse
         ))
         .id();
 
-    for index in 0..NUMBER_OF_ITEMS_ON_PAGE {
-        spawn_store_item(&mut commands, &assets, items_container, StoreItem { index });
+    for index in 0..NUMBER_OF_UPGRADES_ON_PAGE {
+        spawn_upgrade_item(
+            &mut commands,
+            &assets,
+            upgrades_container,
+            UpgradeItem { index },
+        );
+    }
+
+    spawn_store_tabs(&mut commands, &assets, root);
+}
+
+/// Keep one store item per flora, the number of flora changes when `flora.json` is reloaded.
+fn sync_store_items(
+    mut commands: Commands,
+    assets: Res<GameAssets>,
+    flora_registries: Res<Assets<FloraRegistry>>,
+    store_page: Res<StorePageItems>,
+    mut q_items_container: Query<(Entity, &mut Navigator), With<StoreItemContainer>>,
+    q_items: Query<Entity, With<StoreItem>>,
+) {
+    if q_items.iter().count() == store_page.items.len() {
+        return;
+    }
+    let Ok((items_container, mut navigator)) = q_items_container.single_mut() else {
+        return;
+    };
+    let Some(flora_registry) = flora_registries.get(&assets.flora_registry) else {
+        return;
+    };
+
+    navigator.highlighted_item = None;
+    for entity in &q_items {
+        commands.entity(entity).despawn();
+    }
+    for (index, flora) in store_page.items.iter().enumerate() {
+        spawn_store_item(
+            &mut commands,
+            &assets,
+            items_container,
+            StoreItem { index },
+            flora_registry.icon(*flora),
+        );
     }
 }
 
@@ -234,9 +465,12 @@ fn update_item_affordability(
     map_data: Res<MapData>,
     mut store_page: ResMut<StorePageItems>,
 ) {
-    for i in 0..NUMBER_OF_ITEMS_ON_PAGE {
-        store_page.is_affordable[i] = core.is_affordable(&map_data, &store_page.items[i]);
-    }
+    let is_affordable = store_page
+        .items
+        .iter()
+        .map(|flora| core.is_affordable(&map_data, flora))
+        .collect();
+    store_page.is_affordable = is_affordable;
 }
 
 fn reset_all_highlights(mut q_items: Query<&mut ImageNode, With<StoreItem>>) {
@@ -291,7 +525,7 @@ fn toggle_item_unaffordable_overlay(
             continue;
         };
 
-        *visibility = if store_page.is_affordable[item.index] {
+        *visibility = if store_page.is_affordable(item.index) {
             Visibility::Hidden
         } else {
             Visibility::Inherited
@@ -308,6 +542,8 @@ fn hide_item_cost_texts(mut q_cost_texts: Query<&mut Visibility, With<ItemCostTe
 fn update_item_cost_text(
     core: Res<ProgressionCore>,
     map_data: Res<MapData>,
+    gaming_input: Res<GamingInput>,
+    bachelor_build: Option<Res<BachelorBuild>>,
     store_page: Res<StorePageItems>,
     q_navigator: Query<&Navigator>,
     q_items: Query<&StoreItem>,
@@ -330,18 +566,23 @@ fn update_item_cost_text(
             continue;
         };
 
-        let color = if store_page.is_affordable[store_item.index] {
+        let Some(flora) = store_page.get_by_index(store_item.index) else {
+            continue;
+        };
+
+        let amount = buy_amount(&gaming_input, bachelor_build.as_deref());
+        let cost = core.flora_bulk_cost(&map_data, flora, amount);
+        let color = if core.points >= cost {
             Color::WHITE
         } else {
             RED.into()
         };
 
-        let item = store_page.items[store_item.index];
-        let cost = map_data
-            .flora_data(item.index())
-            .cost(core.flora[item.index()].into());
-
-        outline.text = format_money_string(cost.into());
+        outline.text = if amount > 1 {
+            format!("x{} {}", amount, format_money_string(cost))
+        } else {
+            format_money_string(cost)
+        };
         outline.color = color;
 
         *visibility = Visibility::Inherited;
@@ -350,6 +591,7 @@ fn update_item_cost_text(
 
 fn trigger_button_pressed(
     gaming_input: Res<GamingInput>,
+    bachelor_build: Option<Res<BachelorBuild>>,
     store_page: Res<StorePageItems>,
     q_navigator: Query<&Navigator>,
     q_items: Query<&StoreItem>,
@@ -374,9 +616,14 @@ fn trigger_button_pressed(
         return;
     };
 
-    let flora = store_page.get_by_index(item.index);
+    let Some(flora) = store_page.get_by_index(item.index) else {
+        return;
+    };
 
-    ev_item_pressed.write(ItemPressed { flora });
+    ev_item_pressed.write(ItemPressed {
+        flora,
+        amount: buy_amount(&gaming_input, bachelor_build.as_deref()),
+    });
 }
 
 fn update_store_item_count_texts(
@@ -386,43 +633,191 @@ fn update_store_item_count_texts(
     mut q_outlines: Query<&mut TextOutline, With<ItemCountText>>,
 ) {
     for (children, item) in &q_items {
+        let Some(flora) = store_page.get_by_index(item.index) else {
+            continue;
+        };
+
         for child in children {
             let Ok(mut outline) = q_outlines.get_mut(*child) else {
                 continue;
             };
 
-            outline.text = format!(
-                "x{}",
-                core.flora[store_page.get_by_index(item.index).index()]
-            );
+            outline.text = format!("x{}", core.flora_count(flora));
         }
     }
 }
 
 fn update_store_item_icons(
     assets: Res<GameAssets>,
+    flora_registries: Res<Assets<FloraRegistry>>,
     store_page: Res<StorePageItems>,
     q_items: Query<(&Children, &StoreItem)>,
     mut q_image_nodes: Query<&mut ImageNode, With<ItemIcon>>,
 ) {
+    let Some(flora_registry) = flora_registries.get(&assets.flora_registry) else {
+        return;
+    };
+
     for (children, item) in &q_items {
+        let Some(flora) = store_page.get_by_index(item.index) else {
+            continue;
+        };
+
         for child in children {
             let Ok(mut image_node) = q_image_nodes.get_mut(*child) else {
                 continue;
             };
 
-            image_node.image = store_page.get_by_index(item.index).icon(&assets);
+            image_node.image = flora_registry.icon(flora);
         }
     }
 }
 
-fn update_store_page_items(mut store_page: ResMut<StorePageItems>) {
-    store_page.items[0] = Flora::Raddish;
-    store_page.items[1] = Flora::Onion;
-    store_page.items[2] = Flora::Potatoe;
-    store_page.items[3] = Flora::Carrot;
-    store_page.items[4] = Flora::Corn;
-    store_page.items[5] = Flora::Pumpkin;
+fn update_store_page_items(
+    assets: Res<GameAssets>,
+    flora_registries: Res<Assets<FloraRegistry>>,
+    mut store_page: ResMut<StorePageItems>,
+) {
+    let Some(flora_registry) = flora_registries.get(&assets.flora_registry) else {
+        error!("flora registry is not loaded, must be loaded at this point");
+        return;
+    };
+
+    store_page.items = flora_registry.iter().collect();
+    store_page.is_affordable = vec![false; store_page.items.len()];
+}
+
+/// Show the first few upgrades that weren't bought yet, in the order of the upgrade data.
+fn update_store_page_upgrades(
+    core: Res<ProgressionCore>,
+    map_data: Res<MapData>,
+    mut upgrade_page: ResMut<StorePageUpgrades>,
+) {
+    let mut not_bought = map_data
+        .upgrade_data()
+        .iter()
+        .enumerate()
+        .filter(|(_, upgrade)| !core.has_upgrade(&upgrade.id))
+        .map(|(index, _)| index);
+
+    for upgrade in &mut upgrade_page.upgrades {
+        *upgrade = not_bought.next();
+    }
+}
+
+fn switch_store_tab(
+    gaming_input: Res<GamingInput>,
+    mut store_tab: ResMut<StoreTab>,
+    q_tab_buttons: Query<(&Interaction, &StoreTabButton)>,
+) {
+    if !gaming_input.confirm {
+        return;
+    }
+
+    for (interaction, tab_button) in &q_tab_buttons {
+        if *interaction != Interaction::None && *store_tab != tab_button.tab {
+            *store_tab = tab_button.tab;
+        }
+    }
+}
+
+fn update_store_tab_visuals(
+    store_tab: Res<StoreTab>,
+    mut q_tab_buttons: Query<(&StoreTabButton, &mut BackgroundColor)>,
+    mut q_items_container: Query<&mut Visibility, With<StoreItemContainer>>,
+    mut q_upgrades_container: Query<
+        &mut Visibility,
+        (With<UpgradeItemContainer>, Without<StoreItemContainer>),
+    >,
+) {
+    for (tab_button, mut color) in &mut q_tab_buttons {
+        color.0 = if tab_button.tab == *store_tab {
+            DARK_GRAY.into()
+        } else {
+            UPGRADE_ITEM_COLOR
+        };
+    }
+
+    let (items_visibility, upgrades_visibility) = match *store_tab {
+        StoreTab::Flora => (Visibility::Inherited, Visibility::Hidden),
+        StoreTab::Upgrades => (Visibility::Hidden, Visibility::Inherited),
+    };
+    for mut visibility in &mut q_items_container {
+        *visibility = items_visibility;
+    }
+    for mut visibility in &mut q_upgrades_container {
+        *visibility = upgrades_visibility;
+    }
+}
+
+fn update_upgrade_items(
+    core: Res<ProgressionCore>,
+    map_data: Res<MapData>,
+    upgrade_page: Res<StorePageUpgrades>,
+    mut q_items: Query<(
+        &Interaction,
+        &UpgradeItem,
+        &mut Visibility,
+        &mut BackgroundColor,
+    )>,
+    mut q_texts: Query<(&ChildOf, &UpgradeItemText, &mut TextOutline)>,
+) {
+    for (interaction, item, mut visibility, mut color) in &mut q_items {
+        *visibility = if upgrade_page.upgrades[item.index].is_some() {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+        color.0 = if *interaction == Interaction::None {
+            UPGRADE_ITEM_COLOR
+        } else {
+            DARK_GRAY.into()
+        };
+    }
+
+    for (parent, text, mut outline) in &mut q_texts {
+        let Ok((_, item, _, _)) = q_items.get(parent.parent()) else {
+            continue;
+        };
+        let Some(upgrade) =
+            upgrade_page.upgrades[item.index].and_then(|index| map_data.upgrade_data().get(index))
+        else {
+            continue;
+        };
+
+        outline.text = match text {
+            UpgradeItemText::Name => upgrade.name.clone(),
+            UpgradeItemText::Description => upgrade.description(map_data.flora_registry()),
+            UpgradeItemText::Cost => format_money_string(upgrade.cost),
+        };
+        if let UpgradeItemText::Cost = text {
+            outline.color = if core.points >= upgrade.cost {
+                Color::WHITE
+            } else {
+                RED.into()
+            };
+        }
+    }
+}
+
+fn trigger_upgrade_pressed(
+    gaming_input: Res<GamingInput>,
+    upgrade_page: Res<StorePageUpgrades>,
+    q_items: Query<(&Interaction, &UpgradeItem)>,
+    mut ev_upgrade_pressed: EventWriter<UpgradePressed>,
+) {
+    if !gaming_input.confirm {
+        return;
+    }
+
+    for (interaction, item) in &q_items {
+        if *interaction == Interaction::None {
+            continue;
+        }
+        if let Some(index) = upgrade_page.upgrades[item.index] {
+            ev_upgrade_pressed.write(UpgradePressed { index });
+        }
+    }
 }
 
 pub struct UiStorePlugin;
@@ -430,11 +825,14 @@ pub struct UiStorePlugin;
 impl Plugin for UiStorePlugin {
     fn build(&self, app: &mut App) {
         app.add_event::<ItemPressed>()
+            .add_event::<UpgradePressed>()
             .init_resource::<StorePageItems>()
+            .init_resource::<StorePageUpgrades>()
+            .init_resource::<StoreTab>()
             .add_systems(OnExit(GameState::AssetLoading), spawn_store)
             .add_systems(
                 PreUpdate,
-                update_item_affordability
+                (update_item_affordability, update_store_page_upgrades)
                     .run_if(resource_exists::<ProgressionCore>.and(resource_exists::<MapData>)),
             )
             .add_systems(
@@ -453,8 +851,24 @@ impl Plugin for UiStorePlugin {
             )
             .add_systems(
                 Update,
-                update_store_item_icons
-                    .run_if(resource_changed::<StorePageItems>.and(resource_exists::<GameAssets>)),
+                (
+                    update_store_page_items.run_if(on_event::<AssetEvent<FloraRegistry>>),
+                    sync_store_items,
+                    update_store_item_icons.run_if(resource_changed::<StorePageItems>),
+                )
+                    .chain()
+                    .run_if(resource_exists::<GameAssets>),
+            )
+            .add_systems(
+                Update,
+                (
+                    switch_store_tab.run_if(in_state(GameState::Gaming)),
+                    update_store_tab_visuals,
+                    trigger_upgrade_pressed.run_if(in_state(GameState::Gaming)),
+                    update_upgrade_items
+                        .run_if(resource_exists::<ProgressionCore>.and(resource_exists::<MapData>)),
+                )
+                    .chain(),
             )
             .add_systems(OnExit(GameState::AssetLoading), update_store_page_items);
     }
@@ -0,0 +1,399 @@
+//! Non-negative numbers of arbitrary magnitude for points, costs and points per second.
+//!
+//! The value is `mantissa * 10^exponent`. As long as the value is below `MANTISSA_LIMIT` the
+//! exponent stays at zero, so small numbers (everything the early game deals with) are exact
+//! integers, just like the `u64` we used before. Above that the mantissa is kept within
+//! `[MANTISSA_LIMIT / 10, MANTISSA_LIMIT)` and we only lose the digits nobody looks at anyways.
+//!
+//! Subtraction saturates at zero, there are no negative points.
+
+use std::{
+    cmp::Ordering,
+    fmt,
+    ops::{Add, AddAssign, Mul, MulAssign, Sub, SubAssign},
+};
+
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+const MANTISSA_DIGITS: i64 = 15;
+const MANTISSA_LIMIT: f64 = 1e15;
+/// When adding two numbers whose exponents differ by more than this, the smaller one doesn't
+/// change the result.
+const MAX_EXPONENT_DIFF: i64 = 2 * MANTISSA_DIGITS;
+
+/// Suffix of every group of three digits, after these we fall back to scientific notation.
+const SUFFIXES: [&str; 12] = [
+    "", "k", "M", "B", "T", "Qa", "Qi", "Sx", "Sp", "Oc", "No", "Dc",
+];
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct BigNumber {
+    mantissa: f64,
+    exponent: i64,
+}
+
+fn pow10(exponent: i64) -> f64 {
+    10f64.powi(exponent.clamp(i32::MIN as i64, i32::MAX as i64) as i32)
+}
+
+impl BigNumber {
+    pub const ZERO: Self = Self {
+        mantissa: 0.0,
+        exponent: 0,
+    };
+
+    pub fn new(mantissa: f64, exponent: i64) -> Self {
+        let (mut mantissa, mut exponent) = (mantissa, exponent);
+
+        if mantissa.is_nan() || mantissa <= 0.0 {
+            return Self::ZERO;
+        }
+        if mantissa.is_infinite() {
+            mantissa = f64::MAX;
+        }
+
+        if exponent < 0 {
+            mantissa *= pow10(exponent);
+            exponent = 0;
+        }
+
+        if mantissa >= MANTISSA_LIMIT {
+            let shift = mantissa.log10().floor() as i64 - (MANTISSA_DIGITS - 1);
+            mantissa /= pow10(shift);
+            exponent += shift;
+        } else if exponent > 0 && mantissa < MANTISSA_LIMIT / 10.0 {
+            let shift = ((MANTISSA_DIGITS - 1) - mantissa.log10().floor() as i64).min(exponent);
+            mantissa *= pow10(shift);
+            exponent -= shift;
+        }
+
+        // `log10` isn't exact, fix up whatever it got wrong.
+        while mantissa >= MANTISSA_LIMIT {
+            mantissa /= 10.0;
+            exponent += 1;
+        }
+        while exponent > 0 && mantissa < MANTISSA_LIMIT / 10.0 {
+            mantissa *= 10.0;
+            exponent -= 1;
+        }
+
+        Self { mantissa, exponent }
+    }
+
+    pub fn is_zero(self) -> bool {
+        self.mantissa == 0.0
+    }
+
+    pub fn log10(self) -> f64 {
+        self.mantissa.log10() + self.exponent as f64
+    }
+
+    /// Lossy conversion, returns `f64::INFINITY` if the number doesn't fit.
+    pub fn to_f64(self) -> f64 {
+        self.mantissa * pow10(self.exponent)
+    }
+
+    /// `self / other` as a plain float, handy for comparing how good something is.
+    pub fn ratio(self, other: Self) -> f64 {
+        if other.is_zero() {
+            return f64::MAX;
+        }
+        (self.mantissa / other.mantissa) * pow10(self.exponent - other.exponent)
+    }
+
+    pub fn floor(self) -> Self {
+        Self::new(self.mantissa.floor(), self.exponent)
+    }
+
+    pub fn round(self) -> Self {
+        Self::new(self.mantissa.round(), self.exponent)
+    }
+
+    pub fn powi(self, mut exp: u32) -> Self {
+        let mut base = self;
+        let mut result = Self::from(1u64);
+        while exp > 0 {
+            if exp & 1 == 1 {
+                result *= base;
+            }
+            base *= base;
+            exp >>= 1;
+        }
+        result
+    }
+
+    /// Lossy, goes through `log10`, only use this for balancing curves and not for exact math.
+    pub fn powf(self, exp: f64) -> Self {
+        if self.is_zero() {
+            return Self::ZERO;
+        }
+        if self.exponent == 0 {
+            return Self::from(self.mantissa.powf(exp));
+        }
+        let log10 = self.log10() * exp;
+        let exponent = log10.floor();
+        Self::new(10f64.powf(log10 - exponent), exponent as i64)
+    }
+
+    pub fn min(self, other: Self) -> Self {
+        if other < self {
+            other
+        } else {
+            self
+        }
+    }
+
+    /// Align the mantissa of the smaller number to the exponent of the larger one.
+    fn aligned_mantissa(&self, exponent: i64) -> f64 {
+        let diff = exponent - self.exponent;
+        if diff > MAX_EXPONENT_DIFF {
+            return 0.0;
+        }
+        self.mantissa / pow10(diff)
+    }
+
+    /// Returns the value divided by `10^shift`, rounded down.
+    fn scaled_down(&self, shift: i64) -> f64 {
+        let exponent = self.exponent - shift;
+        if exponent >= 0 {
+            (self.mantissa * pow10(exponent)).floor()
+        } else {
+            (self.mantissa / pow10(-exponent)).floor()
+        }
+    }
+}
+
+impl From<u64> for BigNumber {
+    fn from(value: u64) -> Self {
+        Self::new(value as f64, 0)
+    }
+}
+
+impl From<u32> for BigNumber {
+    fn from(value: u32) -> Self {
+        Self::new(value as f64, 0)
+    }
+}
+
+impl From<f64> for BigNumber {
+    fn from(value: f64) -> Self {
+        Self::new(value, 0)
+    }
+}
+
+impl PartialOrd for BigNumber {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        // Both are normalized, so a larger exponent always means a larger number.
+        match self.exponent.cmp(&other.exponent) {
+            Ordering::Equal => self.mantissa.partial_cmp(&other.mantissa),
+            ord => Some(ord),
+        }
+    }
+}
+
+impl Add for BigNumber {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        let (larger, smaller) = if self.exponent >= rhs.exponent {
+            (self, rhs)
+        } else {
+            (rhs, self)
+        };
+        Self::new(
+            larger.mantissa + smaller.aligned_mantissa(larger.exponent),
+            larger.exponent,
+        )
+    }
+}
+
+impl AddAssign for BigNumber {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sub for BigNumber {
+    type Output = Self;
+
+    /// Saturates at zero.
+    fn sub(self, rhs: Self) -> Self {
+        if rhs >= self {
+            return Self::ZERO;
+        }
+        Self::new(
+            self.mantissa - rhs.aligned_mantissa(self.exponent),
+            self.exponent,
+        )
+    }
+}
+
+impl SubAssign for BigNumber {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl Mul for BigNumber {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        Self::new(self.mantissa * rhs.mantissa, self.exponent + rhs.exponent)
+    }
+}
+
+impl MulAssign for BigNumber {
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs;
+    }
+}
+
+/// Short money like formatting, `24300001` becomes `24.3M`. Digits are always cut off, never
+/// rounded up, so that we never show more points than the player actually has.
+impl fmt::Display for BigNumber {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if *self < Self::from(10_000u64) {
+            return write!(f, "{}", self.mantissa.floor());
+        }
+
+        let group = (self.log10().floor() as i64 / 3).max(1);
+        // The value of the group (e.g. the millions) times 100, so that we have two decimals.
+        let hundredths = self.scaled_down(3 * group - 2) as u64;
+        let (whole, decimals) = (hundredths / 100, hundredths % 100);
+
+        let Some(suffix) = SUFFIXES.get(group as usize) else {
+            let exponent = self.log10().floor() as i64;
+            let hundredths = self.scaled_down(exponent - 2) as u64;
+            return write!(
+                f,
+                "{}.{:02}e{}",
+                hundredths / 100,
+                hundredths % 100,
+                exponent
+            );
+        };
+
+        // Thousands below 10k are printed in full, so they always have at least two digits.
+        if group > 1 && whole < 10 {
+            write!(f, "{}.{:02}{}", whole, decimals, suffix)
+        } else if whole < 100 {
+            write!(f, "{}.{}{}", whole, decimals / 10, suffix)
+        } else {
+            write!(f, "{}{}", whole, suffix)
+        }
+    }
+}
+
+impl Serialize for BigNumber {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if self.exponent == 0 {
+            serializer.serialize_str(&format!("{}", self.mantissa))
+        } else {
+            serializer.serialize_str(&format!("{}e{}", self.mantissa, self.exponent))
+        }
+    }
+}
+
+struct BigNumberVisitor;
+
+impl de::Visitor<'_> for BigNumberVisitor {
+    type Value = BigNumber;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "a non-negative number or a string of the form `<mantissa>e<exponent>`"
+        )
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<BigNumber, E> {
+        Ok(v.into())
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<BigNumber, E> {
+        Ok(BigNumber::new(v as f64, 0))
+    }
+
+    fn visit_f64<E: de::Error>(self, v: f64) -> Result<BigNumber, E> {
+        Ok(v.into())
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<BigNumber, E> {
+        let (mantissa, exponent) = v.split_once(['e', 'E']).unwrap_or((v, "0"));
+        let mantissa = mantissa.trim().parse::<f64>().map_err(E::custom)?;
+        let exponent = exponent.trim().parse::<i64>().map_err(E::custom)?;
+        Ok(BigNumber::new(mantissa, exponent))
+    }
+}
+
+impl<'de> Deserialize<'de> for BigNumber {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(BigNumberVisitor)
+    }
+}
+
+#[test]
+fn validate_big_number_arithmetic() {
+    let a = BigNumber::from(1_500u64);
+    let b = BigNumber::from(500u64);
+
+    assert_eq!(a + b, BigNumber::from(2_000u64));
+    assert_eq!(a - b, BigNumber::from(1_000u64));
+    assert_eq!(b - a, BigNumber::ZERO);
+    assert_eq!(a * b, BigNumber::from(750_000u64));
+    assert!(a > b);
+    assert_eq!(a.min(b), b);
+
+    let huge = BigNumber::new(1.0, 300);
+    assert!(huge * huge > huge);
+    assert_eq!(huge + b, huge);
+    assert!((huge * huge).log10() - 600.0 < 1e-9);
+    assert!(BigNumber::from(u64::MAX) + BigNumber::from(u64::MAX) > BigNumber::from(u64::MAX));
+}
+
+#[test]
+fn validate_big_number_powi() {
+    assert_eq!(BigNumber::from(2u64).powi(10), BigNumber::from(1_024u64));
+    assert_eq!(BigNumber::from(7u64).powi(0), BigNumber::from(1u64));
+
+    let log10 = BigNumber::from(1.5).powi(1_000).log10();
+    assert!((log10 - 1_000.0 * 1.5f64.log10()).abs() < 1e-6);
+
+    assert!((BigNumber::from(10_000u64).powf(0.5).to_f64() - 100.0).abs() < 1e-9);
+    assert_eq!(BigNumber::ZERO.powf(0.5), BigNumber::ZERO);
+}
+
+#[test]
+fn validate_big_number_serde_round_trip() {
+    for number in [
+        BigNumber::ZERO,
+        BigNumber::from(800u64),
+        BigNumber::from(1.5),
+        BigNumber::new(1.234, 567),
+    ] {
+        let json = serde_json::to_string(&number).unwrap();
+        assert_eq!(serde_json::from_str::<BigNumber>(&json).unwrap(), number);
+    }
+
+    // Saves and data files that still contain plain numbers.
+    assert_eq!(
+        serde_json::from_str::<BigNumber>("42").unwrap(),
+        BigNumber::from(42u64)
+    );
+    assert_eq!(
+        serde_json::from_str::<BigNumber>("2.5").unwrap(),
+        BigNumber::from(2.5)
+    );
+    assert!(serde_json::from_str::<BigNumber>("\"banana\"").is_err());
+}
+
+#[test]
+fn validate_big_number_format() {
+    assert_eq!(BigNumber::from(9_999u64).to_string(), "9999");
+    assert_eq!(BigNumber::from(24_399u64).to_string(), "24.3k");
+    assert_eq!(BigNumber::from(240_399u64).to_string(), "240k");
+    assert_eq!(BigNumber::from(1_050_000u64).to_string(), "1.05M");
+    assert_eq!(BigNumber::new(2.4, 13).to_string(), "24.0T");
+    assert_eq!(BigNumber::new(1.0, 33).to_string(), "1.00Dc");
+    assert_eq!(BigNumber::new(1.234, 100).to_string(), "1.23e100");
+}
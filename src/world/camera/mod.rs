@@ -4,19 +4,27 @@ use bevy::{math::bounding::Aabb2d, prelude::*};
 
 use crate::player::{GamingInput, Player};
 
-use super::map::MAP_SIZE;
+use super::map::{MapData, MAP_SIZE};
 use super::{DebugState, TILE_SIZE};
 
 /// The amount of pixels that the game camera will span in the height.
 const GAME_CAMERA_PROJECTION_SCALE: f32 = 300.0;
 const PROJECTION_FAR: f32 = 1e6;
+/// How far the camera can look past the edges of the garden.
+const BOUNDS_MARGIN: f32 = 1.5 * TILE_SIZE;
 
 pub struct CameraPlugin;
 
 impl Plugin for CameraPlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(Startup, spawn_camera)
-            .add_systems(Update, (zoom_camera,))
+            .add_systems(
+                Update,
+                (
+                    zoom_camera,
+                    update_camera_bounds.run_if(resource_exists_and_changed::<MapData>),
+                ),
+            )
             .configure_sets(
                 PostUpdate,
                 (
@@ -59,8 +67,11 @@ impl Default for MainCamera {
     fn default() -> Self {
         Self {
             bounds: Aabb2d {
-                min: Vec2::NEG_ONE * MAP_SIZE as f32 * TILE_SIZE * 0.5 - 2.0 * TILE_SIZE,
-                max: Vec2::ONE * MAP_SIZE as f32 * TILE_SIZE * 0.5 + TILE_SIZE,
+                min: Vec2::NEG_ONE * MAP_SIZE as f32 * TILE_SIZE * 0.5
+                    - 0.5 * TILE_SIZE
+                    - BOUNDS_MARGIN,
+                max: Vec2::ONE * MAP_SIZE as f32 * TILE_SIZE * 0.5 - 0.5 * TILE_SIZE
+                    + BOUNDS_MARGIN,
             },
         }
     }
@@ -107,6 +118,19 @@ fn spawn_camera(mut commands: Commands) {
     ));
 }
 
+/// The camera follows the garden as more land gets bought.
+fn update_camera_bounds(map_data: Res<MapData>, mut q_camera: Query<&mut MainCamera>) {
+    let Ok(mut main_camera) = q_camera.single_mut() else {
+        return;
+    };
+
+    let land_bounds = map_data.land_bounds();
+    main_camera.bounds = Aabb2d {
+        min: land_bounds.min - Vec2::splat(BOUNDS_MARGIN),
+        max: land_bounds.max + Vec2::splat(BOUNDS_MARGIN),
+    };
+}
+
 fn zoom_camera(
     debug_state: Res<DebugState>,
     gaming_input: Res<GamingInput>,
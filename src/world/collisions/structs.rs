@@ -4,13 +4,16 @@ pub const WORLD_GROUP: u32 = 1;
 pub const PLAYER_GROUP: u32 = 1 << 1;
 pub const GRASS_GROUP: u32 = 1 << 2;
 pub const SLASH_GROUP: u32 = 1 << 3;
+pub const CROP_GROUP: u32 = 1 << 4;
 
 pub const WORLD_COLLISION_GROUPS: CollisionGroups =
     CollisionGroups::new(WORLD_GROUP, WORLD_GROUP | PLAYER_GROUP);
 pub const PLAYER_COLLISION_GROUPS: CollisionGroups =
     CollisionGroups::new(PLAYER_GROUP, WORLD_GROUP);
 pub const GRASS_COLLISION_GROUPS: CollisionGroups = CollisionGroups::new(GRASS_GROUP, SLASH_GROUP);
-pub const SLASH_COLLISION_GROUPS: CollisionGroups = CollisionGroups::new(SLASH_GROUP, GRASS_GROUP);
+pub const SLASH_COLLISION_GROUPS: CollisionGroups =
+    CollisionGroups::new(SLASH_GROUP, GRASS_GROUP | CROP_GROUP);
+pub const CROP_COLLISION_GROUPS: CollisionGroups = CollisionGroups::new(CROP_GROUP, SLASH_GROUP);
 
 /// Sets that are used to control the camera's transform.
 /// Runs before the whole CameraSystemSet.
@@ -57,12 +60,6 @@ pub struct ColliderColor(pub Color);
 #[derive(Component, Default)]
 pub struct Velocity(pub Vec2);
 
-/// Marker component for world collisions.
-/// These are all colliders that have World group as their membership.
-/// E.g wall colliders, props or border colliders.
-#[derive(Component)]
-pub struct WorldCollider;
-
 impl DynamicCollider {
     pub fn new(radius: f32, offset: Vec2) -> Self {
         Self { radius, offset }
@@ -0,0 +1,222 @@
+//! Achievements.
+//!
+//! The achievements are defined in `achievements.json`. Most of them are unlocked once one of the
+//! lifetime `Statistics` (or some other part of the core) reaches a threshold, a few are unlocked
+//! directly by a menu action. Unlocks are stored together with their timestamp and
+//! survive rebirths.
+
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::ui::{MenuAction, MenuActionEvent};
+
+use super::{
+    statistics::StatisticsSystemSet, timestamp, MapData, ProgressionCore, ProgressionSystemSet,
+};
+
+#[derive(Deserialize, Clone, PartialEq, Debug)]
+enum AchievementCondition {
+    CutGrass(u64),
+    Slashes(u64),
+    ItemsBought(u64),
+    /// Own the given number of plants of the flora with the given id.
+    OwnFlora(String, u16),
+    ReachCap(u64),
+    Rebirths(u32),
+    MenuAction(MenuAction),
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct AchievementData {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    condition: AchievementCondition,
+}
+
+/// The part of the save that tracks achievements.
+#[derive(Serialize, Deserialize, Clone, Default, PartialEq, Debug)]
+pub struct Achievements {
+    /// Unlock timestamps by achievement id.
+    pub unlocked: HashMap<String, u64>,
+}
+
+#[derive(Event)]
+pub struct AchievementUnlocked {
+    /// Index into `MapData::achievement_data`.
+    pub index: usize,
+}
+
+impl AchievementData {
+    /// The current and the required value, `None` if the achievement can't be tracked (menu
+    /// actions).
+    pub fn progress(&self, core: &ProgressionCore, map_data: &MapData) -> Option<(u64, u64)> {
+        let statistics = &core.statistics;
+        match &self.condition {
+            AchievementCondition::CutGrass(n) => Some((statistics.grass_cut, *n)),
+            AchievementCondition::Slashes(n) => Some((statistics.slashes, *n)),
+            AchievementCondition::ItemsBought(n) => Some((statistics.items_bought, *n)),
+            AchievementCondition::OwnFlora(id, n) => {
+                let count = map_data
+                    .flora_registry()
+                    .get(id)
+                    .map(|flora| core.flora_count(flora))
+                    .unwrap_or_default();
+                Some((count as u64, *n as u64))
+            }
+            AchievementCondition::ReachCap(n) => Some((statistics.times_capped, *n)),
+            AchievementCondition::Rebirths(n) => Some((core.prestige.rebirths as u64, *n as u64)),
+            AchievementCondition::MenuAction(_) => None,
+        }
+    }
+
+    fn is_met(
+        &self,
+        core: &ProgressionCore,
+        map_data: &MapData,
+        menu_actions: &[MenuAction],
+    ) -> bool {
+        match &self.condition {
+            AchievementCondition::MenuAction(action) => menu_actions.contains(action),
+            _ => self
+                .progress(core, map_data)
+                .is_some_and(|(current, required)| current >= required),
+        }
+    }
+}
+
+impl Achievements {
+    pub fn unlocked_at(&self, id: &str) -> Option<u64> {
+        self.unlocked.get(id).copied()
+    }
+}
+
+impl MapData {
+    pub fn achievement_data(&self) -> &[AchievementData] {
+        &self.achievement_data
+    }
+}
+
+pub fn build_achievement_data(raw: &str) -> Vec<AchievementData> {
+    match serde_json::from_str(raw) {
+        Ok(data) => data,
+        Err(err) => {
+            error!("failed to parse achievement data, {}", err);
+            Vec::new()
+        }
+    }
+}
+
+/// Unlock all achievements whose condition is met, returns the indices of the new unlocks.
+fn unlock_achievements(
+    core: &mut ProgressionCore,
+    map_data: &MapData,
+    menu_actions: &[MenuAction],
+    timestamp: u64,
+) -> Vec<usize> {
+    let mut unlocked = Vec::new();
+    for (index, achievement) in map_data.achievement_data().iter().enumerate() {
+        if core.achievements.unlocked.contains_key(&achievement.id)
+            || !achievement.is_met(core, map_data, menu_actions)
+        {
+            continue;
+        }
+
+        core.achievements
+            .unlocked
+            .insert(achievement.id.clone(), timestamp);
+        unlocked.push(index);
+    }
+    unlocked
+}
+
+fn check_achievements(
+    mut core: ResMut<ProgressionCore>,
+    map_data: Res<MapData>,
+    mut ev_menu_action: EventReader<MenuActionEvent>,
+    mut ev_achievement_unlocked: EventWriter<AchievementUnlocked>,
+) {
+    let menu_actions = ev_menu_action
+        .read()
+        .map(|ev| ev.action)
+        .collect::<Vec<MenuAction>>();
+
+    for index in unlock_achievements(&mut core, &map_data, &menu_actions, timestamp()) {
+        ev_achievement_unlocked.write(AchievementUnlocked { index });
+    }
+}
+
+pub struct MapAchievementsPlugin;
+
+impl Plugin for MapAchievementsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<AchievementUnlocked>().add_systems(
+            Update,
+            check_achievements
+                .after(ProgressionSystemSet)
+                .after(StatisticsSystemSet)
+                .run_if(resource_exists::<ProgressionCore>.and(resource_exists::<MapData>)),
+        );
+    }
+}
+
+#[test]
+fn validate_achievement_json_data() {
+    use crate::assets::ACHIEVEMENT_DATA_CORE;
+
+    let data: Vec<AchievementData> = serde_json::from_str(ACHIEVEMENT_DATA_CORE).unwrap();
+    assert!(!data.is_empty());
+
+    let map_data = MapData::default();
+
+    for (i, achievement) in data.iter().enumerate() {
+        assert!(
+            data[..i].iter().all(|other| other.id != achievement.id),
+            "duplicate achievement id: {}",
+            achievement.id
+        );
+        if let AchievementCondition::OwnFlora(id, _) = &achievement.condition {
+            assert!(
+                map_data.flora_registry().get(id).is_some(),
+                "unknown flora: {}",
+                id
+            );
+        }
+    }
+}
+
+#[test]
+fn validate_achievement_unlocks() {
+    let mut map_data = MapData::default();
+    map_data.achievement_data = build_achievement_data(
+        r#"[
+            {"id": "a", "name": "A", "description": "", "condition": {"CutGrass": 10}},
+            {"id": "b", "name": "B", "description": "", "condition": {"OwnFlora": ["corn", 2]}},
+            {"id": "c", "name": "C", "description": "", "condition": {"MenuAction": "Discord"}}
+        ]"#,
+    );
+    let mut core = ProgressionCore::default();
+
+    core.statistics.grass_cut = 9;
+    assert!(unlock_achievements(&mut core, &map_data, &[], 1).is_empty());
+
+    core.statistics.grass_cut = 10;
+    let corn = map_data.flora_registry().get("corn").unwrap();
+    core.add_flora(corn);
+    core.add_flora(corn);
+    assert_eq!(
+        unlock_achievements(&mut core, &map_data, &[], 2),
+        vec![0, 1]
+    );
+    assert_eq!(core.achievements.unlocked_at("a"), Some(2));
+
+    // Already unlocked achievements are never unlocked twice.
+    assert_eq!(
+        unlock_achievements(&mut core, &map_data, &[MenuAction::Discord], 3),
+        vec![2]
+    );
+    assert_eq!(core.achievements.unlocked_at("a"), Some(2));
+    assert_eq!(core.achievements.unlocked_at("c"), Some(3));
+}
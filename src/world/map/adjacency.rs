@@ -0,0 +1,205 @@
+//! Adjacency bonuses.
+//!
+//! Every planted flora gets a bonus to its pps for each orthogonal neighbour that matches one of
+//! the rules in `adjacency.json`. The bonus of a single plant is the sum of all matching rules over
+//! all its neighbours, e.g. a Corn with two neighbouring Pumpkins gets +40%.
+
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::world::BigNumber;
+
+#[cfg(test)]
+use super::MAP_SIZE;
+use super::{compute_flora_pps, Flora, MapData, ModifierTarget, ProgressionCore};
+
+/// Flora are matched by their id.
+#[derive(Deserialize, Clone, PartialEq, Debug)]
+enum FloraMatch {
+    Any,
+    Flora(String),
+}
+
+#[derive(Deserialize, Clone, PartialEq, Debug)]
+enum NeighbourMatch {
+    Any,
+    /// The neighbour is of the same flora as the plant itself.
+    Same,
+    Flora(String),
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct AdjacencyRule {
+    flora: FloraMatch,
+    neighbour: NeighbourMatch,
+    /// Relative bonus per matching neighbour, `0.2` is +20%.
+    bonus: f64,
+}
+
+impl AdjacencyRule {
+    fn applies(&self, flora: &str, neighbour: &str) -> bool {
+        let flora_matches = match &self.flora {
+            FloraMatch::Any => true,
+            FloraMatch::Flora(id) => id == flora,
+        };
+        let neighbour_matches = match &self.neighbour {
+            NeighbourMatch::Any => true,
+            NeighbourMatch::Same => neighbour == flora,
+            NeighbourMatch::Flora(id) => id == neighbour,
+        };
+        flora_matches && neighbour_matches
+    }
+}
+
+pub fn build_adjacency_rules(raw: &str) -> Vec<AdjacencyRule> {
+    match serde_json::from_str(raw) {
+        Ok(rules) => rules,
+        Err(err) => {
+            error!("failed to parse adjacency rules, {}", err);
+            Vec::new()
+        }
+    }
+}
+
+impl MapData {
+    pub fn flora_at(&self, x: usize, y: usize) -> Option<Flora> {
+        self.flora_registry
+            .by_index(self.grid_index(x, y)? as usize)
+    }
+
+    /// The flora around the footprint of an object of the given size at the given cell. Flora
+    /// that touch the footprint with more than one cell are only counted once.
+    pub(super) fn neighbour_floras(&self, x: usize, y: usize, size: (usize, usize)) -> Vec<Flora> {
+        let (x_size, y_size) = size;
+        let below_and_above = (x..x + x_size)
+            .flat_map(|nx| [(Some(nx), y.checked_sub(1)), (Some(nx), Some(y + y_size))]);
+        let left_and_right = (y..y + y_size)
+            .flat_map(|ny| [(x.checked_sub(1), Some(ny)), (Some(x + x_size), Some(ny))]);
+
+        let mut anchors = Vec::new();
+        for anchor in below_and_above
+            .chain(left_and_right)
+            .filter_map(|(nx, ny)| self.anchor_of(nx?, ny?))
+        {
+            if !anchors.contains(&anchor) {
+                anchors.push(anchor);
+            }
+        }
+        anchors
+            .into_iter()
+            .filter_map(|(ax, ay)| self.flora_at(ax, ay))
+            .collect()
+    }
+
+    /// The bonus `flora` gets from a single `neighbour`.
+    fn rule_bonus(&self, flora: Flora, neighbour: Flora) -> f64 {
+        let (flora, neighbour) = (
+            self.flora_registry.id(flora),
+            self.flora_registry.id(neighbour),
+        );
+        self.adjacency_rules
+            .iter()
+            .filter(|rule| rule.applies(flora, neighbour))
+            .map(|rule| rule.bonus)
+            .sum()
+    }
+
+    /// The bonus the given flora gets (or would get) at the given cell, including the bonus of
+    /// the structures around it.
+    pub fn adjacency_bonus(&self, x: usize, y: usize, flora: Flora) -> f64 {
+        let size = self.flora_data(flora).size_on_grid();
+        let neighbour_bonus: f64 = self
+            .neighbour_floras(x, y, size)
+            .into_iter()
+            .map(|neighbour| self.rule_bonus(flora, neighbour))
+            .sum();
+        neighbour_bonus + self.structure_bonus(x, y, size)
+    }
+
+    /// The summed up bonuses of all planted flora, indexed by the flora index.
+    pub fn adjacency_bonuses(&self) -> Vec<f64> {
+        let mut bonuses = vec![0.0; self.flora_registry.len()];
+        for ((x, y), flora) in self.placed_flora() {
+            bonuses[flora.index()] += self.adjacency_bonus(x, y, flora);
+        }
+        bonuses
+    }
+}
+
+/// How much the pps would increase when placing the given flora at the given cell, including the
+/// bonuses it gives to its neighbours.
+pub fn placement_pps(
+    core: &ProgressionCore,
+    map_data: &MapData,
+    x: usize,
+    y: usize,
+    flora: Flora,
+) -> BigNumber {
+    let own_bonus = map_data.adjacency_bonus(x, y, flora);
+    let mut pps = compute_flora_pps(core, map_data, flora) * (1.0 + own_bonus).into();
+
+    let size = map_data.flora_data(flora).size_on_grid();
+    for neighbour in map_data.neighbour_floras(x, y, size) {
+        let bonus = map_data.rule_bonus(neighbour, flora);
+        if bonus > 0.0 {
+            pps += compute_flora_pps(core, map_data, neighbour) * bonus.into();
+        }
+    }
+    core.apply_modifiers(map_data, ModifierTarget::Pps, pps)
+}
+
+#[test]
+fn validate_adjacency_json_data() {
+    use crate::assets::ADJACENCY_DATA_CORE;
+
+    let rules: Vec<AdjacencyRule> = serde_json::from_str(ADJACENCY_DATA_CORE).unwrap();
+    assert!(!rules.is_empty());
+    assert!(rules.iter().all(|rule| rule.bonus > 0.0));
+
+    // Every referenced flora must exist.
+    let map_data = MapData::default();
+    for rule in &rules {
+        if let FloraMatch::Flora(id) = &rule.flora {
+            assert!(map_data.flora_registry().get(id).is_some(), "{}", id);
+        }
+        if let NeighbourMatch::Flora(id) = &rule.neighbour {
+            assert!(map_data.flora_registry().get(id).is_some(), "{}", id);
+        }
+    }
+}
+
+#[test]
+fn validate_adjacency_bonuses() {
+    let mut map_data = MapData::default();
+    map_data.adjacency_rules = build_adjacency_rules(
+        r#"[
+            {"flora": {"Flora": "corn"}, "neighbour": {"Flora": "pumpkin"}, "bonus": 0.25},
+            {"flora": "Any", "neighbour": "Same", "bonus": 0.5}
+        ]"#,
+    );
+
+    let corn = map_data.flora_registry().get("corn").unwrap();
+    let pumpkin = map_data.flora_registry().get("pumpkin").unwrap();
+
+    map_data.set_grid_index(1, 1, corn.cell_value());
+    map_data.set_grid_index(0, 1, pumpkin.cell_value());
+    map_data.set_grid_index(1, 0, pumpkin.cell_value());
+    map_data.set_grid_index(2, 1, corn.cell_value());
+    // Diagonal to the first Corn, so only the second one profits.
+    map_data.set_grid_index(2, 2, pumpkin.cell_value());
+
+    assert_eq!(map_data.adjacency_bonus(1, 1, corn), 1.0);
+    assert_eq!(map_data.adjacency_bonus(2, 1, corn), 0.75);
+    assert_eq!(map_data.adjacency_bonus(0, 1, pumpkin), 0.0);
+
+    let bonuses = map_data.adjacency_bonuses();
+    assert_eq!(bonuses[corn.index()], 1.75);
+    assert_eq!(bonuses[pumpkin.index()], 0.0);
+
+    // Cells at the border of the grid must not panic.
+    assert_eq!(map_data.adjacency_bonus(0, 0, pumpkin), 1.0);
+    assert_eq!(
+        map_data.adjacency_bonus(MAP_SIZE - 1, MAP_SIZE - 1, pumpkin),
+        0.0
+    );
+}
@@ -1,3 +1,5 @@
+use std::collections::BTreeSet;
+
 use bevy::{color::palettes::css::PINK, prelude::*};
 
 use crate::{
@@ -5,40 +7,62 @@ use crate::{
         collisions::{ColliderColor, StaticCollider, WORLD_COLLISION_GROUPS},
         TILE_SIZE,
     },
-    GameAssets, GameState,
+    GameAssets,
+};
+
+use super::{
+    land::{PLOTS_PER_SIDE, PLOT_SIZE},
+    MapData, ZLevel, MAP_SIZE,
 };
 
-use super::{MapData, ZLevel, MAP_SIZE};
+/// Blocks off the owned land.
+#[derive(Component)]
+struct LandBorder;
 
-fn spawn_map_border(mut commands: Commands) {
-    let size = MAP_SIZE as f32 * TILE_SIZE;
+/// Covers a plot that isn't owned yet.
+#[derive(Component)]
+struct UnownedPlotShade;
 
-    let x = size;
-    let y = size;
+fn spawn_plot_border(commands: &mut Commands, map_data: &MapData, plot: (usize, usize)) {
+    let (x, y) = plot;
+    let size = PLOT_SIZE as f32 * TILE_SIZE;
     let thickness = TILE_SIZE / 4.0;
+    let offset =
+        map_data.grid_indices_to_pos(x * PLOT_SIZE, y * PLOT_SIZE) - Vec2::ONE * TILE_SIZE * 0.5;
 
-    let offset = Vec2::NEG_ONE * size * 0.5 - Vec2::ONE * TILE_SIZE * 0.5;
+    let owned = |nx: Option<usize>, ny: Option<usize>| {
+        nx.zip(ny).is_some_and(|plot| map_data.owns_plot(plot))
+    };
 
-    for (translation, cuboid) in [
+    for (neighbour_owned, translation, cuboid) in [
         (
-            Vec2::new(-thickness, y / 2.0),
-            Vec2::new(thickness, y / 2.0),
+            owned(x.checked_sub(1), Some(y)),
+            Vec2::new(-thickness, size / 2.0),
+            Vec2::new(thickness, size / 2.0),
         ),
         (
-            Vec2::new(x / 2.0, -thickness),
-            Vec2::new(x / 2.0, thickness),
+            owned(Some(x), y.checked_sub(1)),
+            Vec2::new(size / 2.0, -thickness),
+            Vec2::new(size / 2.0, thickness),
         ),
         (
-            Vec2::new(x + thickness, y / 2.0),
-            Vec2::new(thickness, y / 2.0),
+            owned(Some(x + 1), Some(y)),
+            Vec2::new(size + thickness, size / 2.0),
+            Vec2::new(thickness, size / 2.0),
         ),
         (
-            Vec2::new(x / 2.0, y + thickness),
-            Vec2::new(x / 2.0, thickness),
+            owned(Some(x), Some(y + 1)),
+            Vec2::new(size / 2.0, size + thickness),
+            Vec2::new(size / 2.0, thickness),
         ),
     ] {
+        if neighbour_owned {
+            continue;
+        }
+
         debug_assert!(cuboid.x > 0.0 && cuboid.y > 0.0);
         commands.spawn((
+            LandBorder,
             WORLD_COLLISION_GROUPS,
             Transform::from_translation((translation + offset).extend(0.0)),
             StaticCollider::new(cuboid.x, cuboid.y),
@@ -47,15 +71,49 @@ fn spawn_map_border(mut commands: Commands) {
     }
 }
 
-fn spawn_dark_area(commands: &mut Commands, pos: Vec2, scale: Vec3) {
-    commands.spawn((
-        Transform::from_translation(pos.extend(ZLevel::Floor.value() + 1.0)).with_scale(scale),
-        Sprite {
-            image: Handle::<Image>::default(),
-            color: Color::BLACK.with_alpha(0.3).into(),
-            ..default()
-        },
-    ));
+/// Rebuild the border and the shading of the unowned plots whenever the owned land changed.
+fn sync_land(
+    mut commands: Commands,
+    map_data: Res<MapData>,
+    q_borders: Query<Entity, Or<(With<LandBorder>, With<UnownedPlotShade>)>>,
+    mut spawned_plots: Local<Option<BTreeSet<(usize, usize)>>>,
+) {
+    if spawned_plots.as_ref() == Some(&map_data.plots) {
+        return;
+    }
+    *spawned_plots = Some(map_data.plots.clone());
+
+    for entity in &q_borders {
+        commands.entity(entity).despawn();
+    }
+
+    let size = PLOT_SIZE as f32 * TILE_SIZE;
+    let center_offset = Vec2::ONE * 0.5 * (PLOT_SIZE - 1) as f32 * TILE_SIZE;
+    for x in 0..PLOTS_PER_SIDE {
+        for y in 0..PLOTS_PER_SIDE {
+            if map_data.owns_plot((x, y)) {
+                spawn_plot_border(&mut commands, &map_data, (x, y));
+                continue;
+            }
+
+            let pos = map_data.grid_indices_to_pos(x * PLOT_SIZE, y * PLOT_SIZE) + center_offset;
+            let entity = spawn_dark_area(&mut commands, pos, Vec3::new(size, size, 1.0));
+            commands.entity(entity).insert(UnownedPlotShade);
+        }
+    }
+}
+
+fn spawn_dark_area(commands: &mut Commands, pos: Vec2, scale: Vec3) -> Entity {
+    commands
+        .spawn((
+            Transform::from_translation(pos.extend(ZLevel::Floor.value() + 1.0)).with_scale(scale),
+            Sprite {
+                image: Handle::<Image>::default(),
+                color: Color::BLACK.with_alpha(0.3),
+                ..default()
+            },
+        ))
+        .id()
 }
 
 fn spawn_dark_areas(mut commands: Commands, map_data: Res<MapData>) {
@@ -90,12 +148,14 @@ pub struct MapBorderPlugin;
 
 impl Plugin for MapBorderPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(OnExit(GameState::AssetLoading), spawn_map_border)
-            .add_systems(
-                Update,
+        app.add_systems(
+            Update,
+            (
                 spawn_dark_areas.run_if(
                     resource_exists::<GameAssets>.and(resource_exists::<MapData>.and(run_once)),
                 ),
-            );
+                sync_land.run_if(resource_exists_and_changed::<MapData>),
+            ),
+        );
     }
 }
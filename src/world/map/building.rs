@@ -1,17 +1,120 @@
-use bevy::{color::palettes::css::RED, prelude::*};
+use bevy::{
+    color::palettes::css::{LIME, RED},
+    prelude::*,
+    text::FontSmoothing,
+};
 use bevy_trickfilm::prelude::*;
 
-use crate::{player::GamingInput, ui::ItemPressed, BachelorBuild, GameAssets};
+use crate::{
+    player::GamingInput,
+    ui::{ItemPressed, StructurePressed},
+    world::{utils::format_money_string, TILE_SIZE},
+    BachelorBuild, GameAssets,
+};
 
-use super::{Flora, MapData, ProgressionCore, ProgressionSystemSet, ZLevel};
+use super::{
+    adjacency, footprint::size_offset, Flora, MapData, ProgressionCore, ProgressionSystemSet,
+    Structure, ZLevel,
+};
+
+const BONUS_TEXT_OFFSET: Vec3 = Vec3::new(0.0, 24.0, 1.0);
 
 #[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
 pub struct BuildingSystemSet;
 
+/// Anything that follows the cursor over the grid (blueprints, the demolish tool, ...). There is
+/// only ever one of them, cancelling despawns it instead of opening the menu.
+#[derive(Component)]
+pub struct CursorTool;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum BlueprintItem {
+    Flora(Flora),
+    Structure(Structure),
+}
+
 #[derive(Component)]
 pub struct Blueprint {
-    pub item: Flora,
+    pub item: BlueprintItem,
     pub fits_at_pos: bool,
+    /// Where the flora was picked up if this isn't a new purchase but a move, see `relocate`.
+    pub moving_from: Option<Vec2>,
+}
+
+impl BlueprintItem {
+    fn size_on_grid(&self, map_data: &MapData) -> (usize, usize) {
+        match self {
+            Self::Flora(flora) => map_data.flora_data(*flora).size_on_grid(),
+            Self::Structure(structure) => map_data.structure_data(*structure).size_on_grid(),
+        }
+    }
+}
+
+/// Shows how much pps (or points cap) the blueprint would add at its current position.
+#[derive(Component)]
+struct BlueprintBonusText;
+
+/// The animated selectors around the cells under the cursor, one for every cell of the given
+/// size so that they match the footprint.
+pub(super) fn spawn_cursor_selector(
+    commands: &mut Commands,
+    assets: &GameAssets,
+    root: Entity,
+    size: (usize, usize),
+) {
+    let (x_size, y_size) = size;
+    for x in 0..x_size {
+        for y in 0..y_size {
+            let mut animator = AnimationPlayer2D::default();
+            animator
+                .play(assets.building_selector_animation.clone())
+                .repeat();
+
+            commands.spawn((
+                ChildOf(root),
+                animator,
+                Transform::from_translation(Vec3::new(x as f32, y as f32, 0.0) * TILE_SIZE),
+                Sprite::from_atlas_image(
+                    assets.building_selector.clone(),
+                    assets.building_selector_layout.clone().into(),
+                ),
+            ));
+        }
+    }
+}
+
+pub(super) fn spawn_blueprint(
+    commands: &mut Commands,
+    assets: &GameAssets,
+    size: (usize, usize),
+    blueprint: Blueprint,
+) {
+    let root = commands
+        .spawn((
+            CursorTool,
+            blueprint,
+            Visibility::Inherited,
+            Transform::from_xyz(0.0, 0.0, ZLevel::TopUi.value()),
+        ))
+        .id();
+
+    spawn_cursor_selector(commands, assets, root, size);
+
+    // Centered above the footprint.
+    let text_offset = Vec3::new(size_offset(size).x, (size.1 - 1) as f32 * TILE_SIZE, 0.0);
+    commands.spawn((
+        ChildOf(root),
+        BlueprintBonusText,
+        Text2d::default(),
+        TextFont {
+            font: assets.pixel_font.clone(),
+            font_size: 80.0,
+            font_smoothing: FontSmoothing::None,
+            ..default()
+        },
+        TextColor(Color::WHITE),
+        Transform::from_translation(BONUS_TEXT_OFFSET + text_offset).with_scale(Vec3::splat(0.1)),
+    ));
 }
 
 fn spawn_blueprint_item(
@@ -31,30 +134,16 @@ fn spawn_blueprint_item(
             continue;
         }
 
-        let root = commands
-            .spawn((
-                Blueprint {
-                    item: ev.flora,
-                    fits_at_pos: false,
-                },
-                Visibility::Inherited,
-                Transform::from_xyz(0.0, 0.0, ZLevel::TopUi.value()),
-            ))
-            .id();
-
-        let mut animator = AnimationPlayer2D::default();
-        animator
-            .play(assets.building_selector_animation.clone())
-            .repeat();
-
-        commands.spawn((
-            ChildOf(root),
-            animator,
-            Sprite::from_atlas_image(
-                assets.building_selector.clone(),
-                assets.building_selector_layout.clone().into(),
-            ),
-        ));
+        spawn_blueprint(
+            &mut commands,
+            &assets,
+            map_data.flora_data(ev.flora).size_on_grid(),
+            Blueprint {
+                item: BlueprintItem::Flora(ev.flora),
+                fits_at_pos: false,
+                moving_from: None,
+            },
+        );
     }
 }
 
@@ -63,38 +152,44 @@ fn move_blueprint(
     map_data: Res<MapData>,
     mut q_blueprint: Query<(&mut Transform, &mut Blueprint)>,
 ) {
-    let (x, y) = map_data.pos_to_grid_indices(gaming_input.mouse_world_coords);
-    let pos = map_data.grid_indices_to_pos(x, y);
-
     let Ok((mut transform, mut blueprint)) = q_blueprint.single_mut() else {
         return;
     };
 
+    // Keep the cursor in the middle of the footprint. Off the map it still snaps to the cells, it
+    // just never fits there.
+    let size = blueprint.item.size_on_grid(&map_data);
+    let cell = map_data.pos_to_cell(gaming_input.mouse_world_coords - size_offset(size));
+    let pos = map_data.cell_to_pos(cell);
+
     transform.translation.x = pos.x;
     transform.translation.y = pos.y;
 
-    blueprint.fits_at_pos = map_data.fits_at_pos(
-        transform.translation.xy(),
-        map_data.flora_data(blueprint.item.index()).size_on_grid(),
-    );
+    blueprint.fits_at_pos = match blueprint.moving_from {
+        Some(from) => map_data
+            .pos_to_grid_indices(from)
+            .zip(map_data.pos_to_grid_indices(pos))
+            .is_some_and(|(from, to)| map_data.can_move_flora(from, to)),
+        None => map_data.fits_at_pos(pos, size),
+    };
 }
 
-fn despawn_blueprint(mut commands: Commands, q_blueprint: Query<Entity, With<Blueprint>>) {
-    for entity in &q_blueprint {
+fn despawn_cursor_tools(mut commands: Commands, q_cursor_tools: Query<Entity, With<CursorTool>>) {
+    for entity in &q_cursor_tools {
         commands.entity(entity).despawn();
     }
 }
 
-fn despawn_blueprint_on_player_input(
+fn despawn_cursor_tools_on_player_input(
     mut commands: Commands,
     gaming_input: Res<GamingInput>,
-    q_blueprint: Query<Entity, With<Blueprint>>,
+    q_cursor_tools: Query<Entity, With<CursorTool>>,
 ) {
     if !gaming_input.cancel {
         return;
     }
 
-    for entity in &q_blueprint {
+    for entity in &q_cursor_tools {
         commands.entity(entity).despawn();
     }
 }
@@ -108,7 +203,16 @@ fn despawn_blueprint_if_not_affordable(
     debug_assert!(q_blueprints.iter().count() <= 1);
 
     for (entity, blueprint) in &q_blueprints {
-        if !core.is_affordable(&map_data, &blueprint.item) {
+        // Moving a plant is free.
+        if blueprint.moving_from.is_some() {
+            continue;
+        }
+
+        let affordable = match blueprint.item {
+            BlueprintItem::Flora(flora) => core.is_affordable(&map_data, &flora),
+            BlueprintItem::Structure(structure) => core.can_place_structure(&map_data, structure),
+        };
+        if !affordable {
             commands.entity(entity).despawn();
         }
     }
@@ -137,6 +241,58 @@ fn update_blueprint_color(
     }
 }
 
+fn update_blueprint_bonus_text(
+    core: Res<ProgressionCore>,
+    map_data: Res<MapData>,
+    q_blueprint: Query<(&Transform, &Blueprint)>,
+    mut q_texts: Query<(&mut Text2d, &mut TextColor, &mut Visibility), With<BlueprintBonusText>>,
+) {
+    let Ok((transform, blueprint)) = q_blueprint.single() else {
+        return;
+    };
+
+    let Some((x, y)) = map_data
+        .pos_to_grid_indices(transform.translation.xy())
+        .filter(|_| blueprint.fits_at_pos)
+    else {
+        for (_, _, mut visibility) in &mut q_texts {
+            *visibility = Visibility::Hidden;
+        }
+        return;
+    };
+
+    let (bonus_text, bonus_color) = match blueprint.item {
+        BlueprintItem::Flora(flora) => {
+            let bonus = map_data.adjacency_bonus(x, y, flora);
+            let pps = adjacency::placement_pps(&core, &map_data, x, y, flora);
+            if bonus > 0.0 {
+                (
+                    format!(
+                        "+{}/s (+{}%)",
+                        format_money_string(pps),
+                        (bonus * 100.0).round()
+                    ),
+                    LIME.into(),
+                )
+            } else {
+                (format!("+{}/s", format_money_string(pps)), Color::WHITE)
+            }
+        }
+        BlueprintItem::Structure(structure) => {
+            let capacity = core.structure_capacity_of(&map_data, structure);
+            (
+                format!("+{} cap", format_money_string(capacity)),
+                Color::WHITE,
+            )
+        }
+    };
+
+    for (mut text, mut color, mut visibility) in &mut q_texts {
+        *visibility = Visibility::Inherited;
+        (text.0, color.0) = (bonus_text.clone(), bonus_color);
+    }
+}
+
 pub struct MapBuildingPlugin;
 
 impl Plugin for MapBuildingPlugin {
@@ -144,14 +300,17 @@ impl Plugin for MapBuildingPlugin {
         app.add_systems(
             Update,
             (
-                despawn_blueprint.run_if(on_event::<ItemPressed>),
-                despawn_blueprint_on_player_input,
+                despawn_cursor_tools
+                    .run_if(on_event::<ItemPressed>.or(on_event::<StructurePressed>)),
+                despawn_cursor_tools_on_player_input,
                 spawn_blueprint_item
                     .run_if(resource_exists::<GameAssets>.and(resource_exists::<BachelorBuild>)),
                 move_blueprint.run_if(resource_exists::<MapData>),
                 despawn_blueprint_if_not_affordable
                     .run_if(resource_exists::<ProgressionCore>.and(resource_exists::<MapData>)),
                 update_blueprint_color,
+                update_blueprint_bonus_text
+                    .run_if(resource_exists::<ProgressionCore>.and(resource_exists::<MapData>)),
             )
                 .chain()
                 .in_set(BuildingSystemSet)
@@ -0,0 +1,205 @@
+//! Chunked storage of the grid.
+//!
+//! The grid is split into square chunks which only exist once they are loaded, so the garden isn't
+//! bound to a fixed size. Cells use signed coordinates and cells of chunks that aren't loaded
+//! simply have no value. Every change stamps its chunk, whatever only cares about the changes
+//! (saving, spawning the grass) remembers the last stamp it has seen, see `changed_since`.
+
+use std::{
+    collections::HashMap,
+    fmt,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use bevy::prelude::*;
+
+use super::EMPTY_CELL_VALUE;
+
+/// The width and height of a chunk in cells.
+pub const CHUNK_SIZE: i32 = 10;
+const CHUNK_CELLS: usize = (CHUNK_SIZE * CHUNK_SIZE) as usize;
+
+/// The stamps are shared by all grids, that way a fresh grid (e.g. after a reset) is newer than
+/// anything that was seen before.
+static NEXT_STAMP: AtomicU64 = AtomicU64::new(1);
+
+fn next_stamp() -> u64 {
+    NEXT_STAMP.fetch_add(1, Ordering::Relaxed)
+}
+
+#[derive(Clone)]
+struct Chunk {
+    cells: [u16; CHUNK_CELLS],
+    /// When the chunk last changed.
+    stamp: u64,
+}
+
+#[derive(Clone, Default)]
+pub struct ChunkedGrid {
+    chunks: HashMap<IVec2, Chunk>,
+}
+
+/// The chunk that contains the given cell.
+pub fn chunk_of(cell: IVec2) -> IVec2 {
+    cell.div_euclid(IVec2::splat(CHUNK_SIZE))
+}
+
+fn index_in_chunk(cell: IVec2) -> usize {
+    let local = cell.rem_euclid(IVec2::splat(CHUNK_SIZE));
+    (local.x * CHUNK_SIZE + local.y) as usize
+}
+
+fn cell_in_chunk(chunk: IVec2, index: usize) -> IVec2 {
+    let index = index as i32;
+    chunk * CHUNK_SIZE + IVec2::new(index / CHUNK_SIZE, index % CHUNK_SIZE)
+}
+
+impl ChunkedGrid {
+    /// Load the chunk with all of its cells set to the given value. Chunks that are already loaded
+    /// are left as they are.
+    pub fn load_chunk(&mut self, chunk: IVec2, value: u16) {
+        self.chunks.entry(chunk).or_insert_with(|| Chunk {
+            cells: [value; CHUNK_CELLS],
+            stamp: next_stamp(),
+        });
+    }
+
+    pub fn is_chunk_loaded(&self, chunk: IVec2) -> bool {
+        self.chunks.contains_key(&chunk)
+    }
+
+    pub fn is_loaded(&self, cell: IVec2) -> bool {
+        self.is_chunk_loaded(chunk_of(cell))
+    }
+
+    /// `None` if the chunk of the cell isn't loaded.
+    pub fn get(&self, cell: IVec2) -> Option<u16> {
+        let chunk = self.chunks.get(&chunk_of(cell))?;
+        Some(chunk.cells[index_in_chunk(cell)])
+    }
+
+    /// Returns whether the chunk of the cell is loaded, cells of other chunks can't be set. Only
+    /// actual changes stamp the chunk.
+    pub fn set(&mut self, cell: IVec2, value: u16) -> bool {
+        let Some(chunk) = self.chunks.get_mut(&chunk_of(cell)) else {
+            return false;
+        };
+
+        let current = &mut chunk.cells[index_in_chunk(cell)];
+        if *current != value {
+            *current = value;
+            chunk.stamp = next_stamp();
+        }
+        true
+    }
+
+    /// Stamp the chunk of the cell without changing it, for data that is kept next to the grid
+    /// (e.g. the growth ages or the owned land).
+    pub fn touch(&mut self, cell: IVec2) {
+        if let Some(chunk) = self.chunks.get_mut(&chunk_of(cell)) {
+            chunk.stamp = next_stamp();
+        }
+    }
+
+    /// The loaded chunks, sorted so that everything built from them is deterministic.
+    pub fn chunks(&self) -> Vec<IVec2> {
+        let mut chunks: Vec<IVec2> = self.chunks.keys().copied().collect();
+        chunks.sort_by_key(|chunk| (chunk.x, chunk.y));
+        chunks
+    }
+
+    /// All cells of the chunk with their values, nothing if the chunk isn't loaded.
+    pub fn cells_in_chunk(&self, chunk: IVec2) -> impl Iterator<Item = (IVec2, u16)> + '_ {
+        self.chunks.get(&chunk).into_iter().flat_map(move |data| {
+            data.cells
+                .iter()
+                .enumerate()
+                .map(move |(index, value)| (cell_in_chunk(chunk, index), *value))
+        })
+    }
+
+    /// All cells that aren't empty ground, chunk by chunk.
+    pub fn occupied(&self) -> impl Iterator<Item = (IVec2, u16)> + '_ {
+        self.chunks()
+            .into_iter()
+            .flat_map(|chunk| self.cells_in_chunk(chunk))
+            .filter(|(_, value)| *value != EMPTY_CELL_VALUE)
+    }
+
+    /// The stamp of the last change, pass it to `changed_since` later on.
+    pub fn latest_stamp(&self) -> u64 {
+        self.chunks
+            .values()
+            .map(|chunk| chunk.stamp)
+            .max()
+            .unwrap_or_default()
+    }
+
+    /// The chunks that changed (or got loaded) after the given stamp.
+    pub fn changed_since(&self, stamp: u64) -> Vec<IVec2> {
+        self.chunks()
+            .into_iter()
+            .filter(|chunk| self.chunks[chunk].stamp > stamp)
+            .collect()
+    }
+}
+
+/// Two grids are equal if they have the same cells, no matter when they changed.
+impl PartialEq for ChunkedGrid {
+    fn eq(&self, other: &Self) -> bool {
+        self.chunks.len() == other.chunks.len()
+            && self.chunks.iter().all(|(key, chunk)| {
+                other
+                    .chunks
+                    .get(key)
+                    .is_some_and(|other| other.cells == chunk.cells)
+            })
+    }
+}
+
+impl fmt::Debug for ChunkedGrid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map()
+            .entries(
+                self.occupied()
+                    .map(|(cell, value)| ((cell.x, cell.y), value)),
+            )
+            .finish()
+    }
+}
+
+#[test]
+fn validate_chunked_grid() {
+    let mut grid = ChunkedGrid::default();
+    grid.load_chunk(IVec2::new(-1, 0), EMPTY_CELL_VALUE);
+    grid.load_chunk(IVec2::ZERO, EMPTY_CELL_VALUE);
+
+    assert_eq!(chunk_of(IVec2::new(-1, 0)), IVec2::new(-1, 0));
+    assert_eq!(chunk_of(IVec2::new(CHUNK_SIZE, 0)), IVec2::new(1, 0));
+    assert_eq!(grid.get(IVec2::new(-CHUNK_SIZE, 3)), Some(EMPTY_CELL_VALUE));
+    // Neither aliases to the closest loaded cell.
+    assert_eq!(grid.get(IVec2::new(CHUNK_SIZE, 0)), None);
+    assert_eq!(grid.get(IVec2::new(0, -1)), None);
+    assert!(!grid.set(IVec2::new(0, CHUNK_SIZE), 1));
+
+    let stamp = grid.latest_stamp();
+    assert!(grid.changed_since(stamp).is_empty());
+    assert!(grid.set(IVec2::new(-1, 2), 7));
+    assert!(grid.set(IVec2::new(3, 4), 8));
+    assert_eq!(grid.get(IVec2::new(-1, 2)), Some(7));
+    assert_eq!(
+        grid.changed_since(stamp),
+        vec![IVec2::new(-1, 0), IVec2::ZERO]
+    );
+    assert_eq!(
+        grid.occupied().collect::<Vec<_>>(),
+        vec![(IVec2::new(-1, 2), 7), (IVec2::new(3, 4), 8)]
+    );
+
+    // Setting the same value again isn't a change.
+    let stamp = grid.latest_stamp();
+    assert!(grid.set(IVec2::new(3, 4), 8));
+    assert!(grid.changed_since(stamp).is_empty());
+    grid.touch(IVec2::new(3, 4));
+    assert_eq!(grid.changed_since(stamp), vec![IVec2::ZERO]);
+}
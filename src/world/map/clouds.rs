@@ -7,7 +7,7 @@ use bevy::{
 
 use crate::{assets::CLOUDS_SHADER, world::MainCamera, EffectAssets, GameAssets, GameState};
 
-use super::ZLevel;
+use super::{Daylight, ZLevel};
 
 const CLOUDS_NOISE_WIDTH: f32 = 1024.0;
 const CLOUDS_NOISE_HEIGHT: f32 = 512.0;
@@ -36,6 +36,9 @@ struct CloudsMaterial {
     quaternary: Handle<Image>,
     #[uniform(8)]
     texel_size: Vec4,
+    /// The color of the cloud shadows, darker at night, see `Daylight`.
+    #[uniform(9)]
+    shadow: Vec4,
 }
 
 impl Cloud {
@@ -79,6 +82,7 @@ fn spawn_clouds(
             0.0,
             0.0,
         ),
+        shadow: Vec4::new(0.0, 0.0, 0.0, Daylight::default().cloud_alpha),
     }));
 
     for cloud in [
@@ -87,7 +91,7 @@ fn spawn_clouds(
         Cloud::BottomLeft,
         Cloud::TopLeft,
     ] {
-        commands.spawn((cloud, transform.clone(), mesh.clone(), mat.clone()));
+        commands.spawn((cloud, transform, mesh.clone(), mat.clone()));
     }
 }
 
@@ -111,6 +115,12 @@ fn reposition_clouds(
     }
 }
 
+fn darken_clouds(daylight: Res<Daylight>, mut materials: ResMut<Assets<CloudsMaterial>>) {
+    for (_, material) in materials.iter_mut() {
+        material.shadow.w = daylight.cloud_alpha;
+    }
+}
+
 #[cfg(debug_assertions)]
 fn validate_const_match(assets: Res<GameAssets>, images: Res<Assets<Image>>) {
     let primary_image = images.get(&assets.primary_clouds_noise_texture).unwrap();
@@ -129,7 +139,13 @@ impl Plugin for CloudsPlugin {
     fn build(&self, app: &mut App) {
         app.add_plugins(Material2dPlugin::<CloudsMaterial>::default())
             .add_systems(OnExit(GameState::AssetLoading), spawn_clouds)
-            .add_systems(Update, reposition_clouds);
+            .add_systems(
+                Update,
+                (
+                    reposition_clouds,
+                    darken_clouds.run_if(resource_changed::<Daylight>),
+                ),
+            );
 
         #[cfg(debug_assertions)]
         app.add_systems(OnExit(GameState::AssetLoading), validate_const_match);
@@ -0,0 +1,251 @@
+//! Cost curves of the flora.
+//!
+//! Each flora picks one of the models in `flora.json`, e.g.
+//!
+//! `"cost": { "model": "exponential", "base": 1, "growth": 1.1 }`
+//!
+//! `n` is always the number of plants that are already owned, so the first plant costs `cost(0)`.
+//! All costs are rounded to whole points. Both the store and the `simulate` command go through
+//! this, so the balancing in the simulation matches the game.
+
+use serde::Deserialize;
+
+use crate::world::BigNumber;
+
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+#[serde(tag = "model", rename_all = "snake_case")]
+pub enum CostCurve {
+    /// `base * growth^n`.
+    Exponential { base: BigNumber, growth: f64 },
+    /// `base + factor * n^power`.
+    Polynomial {
+        base: BigNumber,
+        factor: f64,
+        power: f64,
+    },
+    /// Hand picked prices for the first plants, after that the last price keeps growing by
+    /// `growth` per plant.
+    Piecewise { prices: Vec<BigNumber>, growth: f64 },
+    /// Exponential with `growth` up to `soft_cap` plants, every plant after that additionally
+    /// grows by `growth_after_cap`.
+    SoftCap {
+        base: BigNumber,
+        growth: f64,
+        soft_cap: u32,
+        growth_after_cap: f64,
+    },
+}
+
+impl Default for CostCurve {
+    fn default() -> Self {
+        Self::Exponential {
+            base: 1u64.into(),
+            growth: 1.0,
+        }
+    }
+}
+
+impl CostCurve {
+    /// Catch the curves that would make plants free or cheaper the more you own.
+    pub fn validate(&self) -> Option<&'static str> {
+        match self {
+            CostCurve::Exponential { base, growth } => {
+                if base.is_zero() {
+                    return Some("cost base must not be zero");
+                }
+                if *growth < 1.0 {
+                    return Some("cost growth must be at least 1");
+                }
+            }
+            CostCurve::Polynomial {
+                base,
+                factor,
+                power,
+            } => {
+                if base.is_zero() {
+                    return Some("cost base must not be zero");
+                }
+                if *factor < 0.0 || *power < 0.0 {
+                    return Some("cost factor and power must not be negative");
+                }
+            }
+            CostCurve::Piecewise { prices, growth } => {
+                if prices.is_empty() || prices.iter().any(|price| price.is_zero()) {
+                    return Some("cost prices must not be empty or zero");
+                }
+                if prices.windows(2).any(|prices| prices[1] < prices[0]) {
+                    return Some("cost prices must not decrease");
+                }
+                if *growth < 1.0 {
+                    return Some("cost growth must be at least 1");
+                }
+            }
+            CostCurve::SoftCap {
+                base,
+                growth,
+                growth_after_cap,
+                ..
+            } => {
+                if base.is_zero() {
+                    return Some("cost base must not be zero");
+                }
+                if *growth < 1.0 || *growth_after_cap < 1.0 {
+                    return Some("cost growth must be at least 1");
+                }
+            }
+        }
+        None
+    }
+
+    /// The cost of the next plant when `n` plants are already owned.
+    pub fn cost(&self, n: u32) -> BigNumber {
+        let cost = match self {
+            CostCurve::Exponential { base, growth } => *base * BigNumber::from(*growth).powi(n),
+            CostCurve::Polynomial {
+                base,
+                factor,
+                power,
+            } => *base + BigNumber::from(n).powf(*power) * BigNumber::from(*factor),
+            CostCurve::Piecewise { prices, growth } => match prices.get(n as usize) {
+                Some(price) => *price,
+                None => {
+                    let last = prices.last().copied().unwrap_or(BigNumber::ZERO);
+                    let extra = n + 1 - prices.len() as u32;
+                    last * BigNumber::from(*growth).powi(extra)
+                }
+            },
+            CostCurve::SoftCap {
+                base,
+                growth,
+                soft_cap,
+                growth_after_cap,
+            } => {
+                let over_cap = n.saturating_sub(*soft_cap);
+                *base
+                    * BigNumber::from(*growth).powi(n)
+                    * BigNumber::from(*growth_after_cap).powi(over_cap)
+            }
+        };
+        cost.round()
+    }
+
+    /// The total cost of buying `amount` plants at once when `n` are already owned.
+    ///
+    /// Exponential curves use the closed form of the geometric series, which is only rounded once
+    /// and can therefore differ from buying one after another by less than a point per plant. All
+    /// other curves sum up the single costs, which is O(amount).
+    pub fn bulk_cost(&self, n: u32, amount: u32) -> BigNumber {
+        match self {
+            CostCurve::Exponential { base, growth } if amount > 1 && *growth > 1.0 => {
+                let growth_n = BigNumber::from(*growth).powi(n);
+                let series = (BigNumber::from(*growth).powi(amount) - BigNumber::from(1u64))
+                    * BigNumber::from(1.0 / (growth - 1.0));
+                (*base * growth_n * series).round()
+            }
+            _ => {
+                (n..n.saturating_add(amount)).fold(BigNumber::ZERO, |total, n| total + self.cost(n))
+            }
+        }
+    }
+}
+
+#[test]
+fn validate_cost_curves() {
+    let exponential = CostCurve::Exponential {
+        base: 10u64.into(),
+        growth: 1.15,
+    };
+    assert_eq!(exponential.cost(0), BigNumber::from(10u64));
+    // 10 * 1.15^2 = 13.225, the old curve floored the growth first and charged 10.
+    assert_eq!(exponential.cost(2), BigNumber::from(13u64));
+    // 10 * 1.15^3 = 15.209
+    assert_eq!(exponential.cost(3), BigNumber::from(15u64));
+
+    let polynomial = CostCurve::Polynomial {
+        base: 5u64.into(),
+        factor: 2.0,
+        power: 2.0,
+    };
+    assert_eq!(polynomial.cost(0), BigNumber::from(5u64));
+    assert_eq!(polynomial.cost(3), BigNumber::from(23u64));
+
+    let piecewise = CostCurve::Piecewise {
+        prices: vec![1u64.into(), 5u64.into(), 20u64.into()],
+        growth: 2.0,
+    };
+    assert_eq!(piecewise.cost(1), BigNumber::from(5u64));
+    assert_eq!(piecewise.cost(2), BigNumber::from(20u64));
+    assert_eq!(piecewise.cost(3), BigNumber::from(40u64));
+    assert_eq!(piecewise.cost(4), BigNumber::from(80u64));
+
+    let soft_cap = CostCurve::SoftCap {
+        base: 1u64.into(),
+        growth: 2.0,
+        soft_cap: 2,
+        growth_after_cap: 3.0,
+    };
+    assert_eq!(soft_cap.cost(2), BigNumber::from(4u64));
+    assert_eq!(soft_cap.cost(3), BigNumber::from(24u64));
+    assert_eq!(soft_cap.cost(4), BigNumber::from(144u64));
+}
+
+#[test]
+fn validate_bulk_cost_matches_single_purchases() {
+    let single = |curve: &CostCurve, n: u32, amount: u32| {
+        (n..n + amount).fold(BigNumber::ZERO, |total, n| total + curve.cost(n))
+    };
+
+    let soft_cap = CostCurve::SoftCap {
+        base: 3u64.into(),
+        growth: 1.3,
+        soft_cap: 5,
+        growth_after_cap: 1.5,
+    };
+    assert_eq!(soft_cap.bulk_cost(4, 10), single(&soft_cap, 4, 10));
+    assert_eq!(soft_cap.bulk_cost(4, 1), soft_cap.cost(4));
+    assert!(soft_cap.bulk_cost(4, 0).is_zero());
+
+    // Whole costs don't round, so the closed form matches exactly.
+    let doubling = CostCurve::Exponential {
+        base: 3u64.into(),
+        growth: 2.0,
+    };
+    assert_eq!(doubling.bulk_cost(4, 10), single(&doubling, 4, 10));
+    assert_eq!(doubling.bulk_cost(4, 1), doubling.cost(4));
+
+    // Otherwise it's off by less than a point per plant.
+    let exponential = CostCurve::Exponential {
+        base: 10u64.into(),
+        growth: 1.15,
+    };
+    for (n, amount) in [(0, 10), (7, 25), (40, 100)] {
+        let bulk = exponential.bulk_cost(n, amount).to_f64();
+        let single = single(&exponential, n, amount).to_f64();
+        assert!(
+            (bulk - single).abs() <= amount as f64,
+            "{} {}",
+            bulk,
+            single
+        );
+    }
+
+    let flat = CostCurve::Exponential {
+        base: 7u64.into(),
+        growth: 1.0,
+    };
+    assert_eq!(flat.bulk_cost(3, 10), BigNumber::from(70u64));
+}
+
+#[test]
+fn validate_cost_curve_json() {
+    let curve: CostCurve =
+        serde_json::from_str(r#"{"model": "piecewise", "prices": [1, 2, "3e20"], "growth": 1.5}"#)
+            .unwrap();
+    assert_eq!(curve.validate(), None);
+    assert_eq!(curve.cost(2), BigNumber::new(3.0, 20));
+
+    let curve: CostCurve =
+        serde_json::from_str(r#"{"model": "exponential", "base": 0, "growth": 1.5}"#).unwrap();
+    assert!(curve.validate().is_some());
+    assert!(serde_json::from_str::<CostCurve>(r#"{"model": "linear", "base": 1}"#).is_err());
+}
@@ -0,0 +1,267 @@
+//! The day/night cycle.
+//!
+//! A day runs through the phases of `day_night.json` in order, one in-game day takes the sum of
+//! their durations. The time of day is saved in the core and keeps going while the game is
+//! closed, see `offline`. Every phase tints the scene and the cloud shadows (the transition to the
+//! next phase is blended) and can have modifiers just like upgrades, e.g. corn doesn't produce at
+//! night.
+//!
+//! Sprites that should follow the tint get the `Daylit` component, the grass and the clouds have
+//! their own materials which pick up `Daylight` themselves.
+
+use std::time::Duration;
+
+use bevy::{prelude::*, time::common_conditions::on_timer};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    upgrades::{Modifier, ModifierTarget},
+    MapData, ProgressionCore, ProgressionSystemSet,
+};
+
+#[cfg(test)]
+use super::{compute_current_pps, offline::apply_offline_progression, WorldRng};
+
+/// The seconds at the end of a phase in which the tint blends over to the next phase.
+const TRANSITION_SECONDS: u64 = 60;
+const DEFAULT_CLOUD_ALPHA: f32 = 0.3;
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct PhaseModifier {
+    pub target: ModifierTarget,
+    pub modifier: Modifier,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct DayPhaseData {
+    pub name: String,
+    /// Seconds the phase lasts.
+    pub duration: u64,
+    /// Multiplied onto the colors of the scene.
+    tint: [f32; 3],
+    /// How dark the shadows of the clouds are.
+    cloud_alpha: f32,
+    #[serde(default)]
+    pub modifiers: Vec<PhaseModifier>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Default)]
+pub struct DayNight {
+    /// The in-game days that passed in this garden.
+    pub day: u64,
+    /// Seconds into the current day.
+    seconds: u64,
+}
+
+/// Marks sprites that get tinted with the time of day.
+#[derive(Component)]
+pub struct Daylit;
+
+/// How the scene looks at the current time of day.
+#[derive(Resource, Clone, Copy, PartialEq, Debug)]
+pub struct Daylight {
+    pub tint: Color,
+    pub cloud_alpha: f32,
+}
+
+impl Default for Daylight {
+    fn default() -> Self {
+        Self {
+            tint: Color::WHITE,
+            cloud_alpha: DEFAULT_CLOUD_ALPHA,
+        }
+    }
+}
+
+pub fn build_day_phase_data(raw: &str) -> Vec<DayPhaseData> {
+    match serde_json::from_str(raw) {
+        Ok(data) => data,
+        Err(err) => {
+            error!("failed to parse day phase data, {}", err);
+            Vec::new()
+        }
+    }
+}
+
+fn day_length(phases: &[DayPhaseData]) -> u64 {
+    phases.iter().map(|phase| phase.duration).sum()
+}
+
+impl MapData {
+    pub fn day_phase_data(&self) -> &[DayPhaseData] {
+        &self.day_phase_data
+    }
+}
+
+impl DayNight {
+    /// Let the given seconds pass, starting new days on the way.
+    pub(super) fn advance(&mut self, phases: &[DayPhaseData], seconds: u64) {
+        let length = day_length(phases);
+        if length == 0 {
+            return;
+        }
+
+        let seconds = self.seconds + seconds;
+        self.day += seconds / length;
+        self.seconds = seconds % length;
+    }
+
+    /// The index of the current phase and the seconds until it ends.
+    fn phase_index(&self, phases: &[DayPhaseData]) -> Option<(usize, u64)> {
+        let length = day_length(phases);
+        if length == 0 {
+            return None;
+        }
+
+        // The day length only changes with the data, but never index out of it.
+        let seconds = self.seconds % length;
+        let mut end = 0;
+        phases.iter().enumerate().find_map(|(index, phase)| {
+            end += phase.duration;
+            (seconds < end).then(|| (index, end - seconds))
+        })
+    }
+
+    /// The current phase, `None` if there are no phases at all.
+    pub fn phase<'a>(&self, phases: &'a [DayPhaseData]) -> Option<&'a DayPhaseData> {
+        self.phase_index(phases).map(|(index, _)| &phases[index])
+    }
+
+    /// Seconds until the current phase ends.
+    pub fn seconds_left_in_phase(&self, phases: &[DayPhaseData]) -> Option<u64> {
+        self.phase_index(phases).map(|(_, remaining)| remaining)
+    }
+
+    pub fn daylight(&self, phases: &[DayPhaseData]) -> Daylight {
+        let Some((index, remaining)) = self.phase_index(phases) else {
+            return Daylight::default();
+        };
+
+        let current = &phases[index];
+        let next = &phases[(index + 1) % phases.len()];
+        let t = 1.0 - remaining.min(TRANSITION_SECONDS) as f32 / TRANSITION_SECONDS as f32;
+
+        let tint = Vec3::from(current.tint).lerp(Vec3::from(next.tint), t);
+        Daylight {
+            tint: Color::srgb(tint.x, tint.y, tint.z),
+            cloud_alpha: current.cloud_alpha.lerp(next.cloud_alpha, t),
+        }
+    }
+}
+
+impl ProgressionCore {
+    pub fn day_phase<'a>(&self, map_data: &'a MapData) -> Option<&'a DayPhaseData> {
+        self.day_night.phase(map_data.day_phase_data())
+    }
+}
+
+fn advance_day_night(mut core: ResMut<ProgressionCore>, map_data: Res<MapData>) {
+    core.day_night.advance(map_data.day_phase_data(), 1);
+}
+
+fn update_daylight(
+    core: Res<ProgressionCore>,
+    map_data: Res<MapData>,
+    mut daylight: ResMut<Daylight>,
+) {
+    daylight.set_if_neq(core.day_night.daylight(map_data.day_phase_data()));
+}
+
+fn tint_daylit_sprites(daylight: Res<Daylight>, mut q_sprites: Query<(Ref<Daylit>, &mut Sprite)>) {
+    for (daylit, mut sprite) in &mut q_sprites {
+        if daylight.is_changed() || daylit.is_added() {
+            sprite.color = daylight.tint;
+        }
+    }
+}
+
+pub struct MapDayNightPlugin;
+
+impl Plugin for MapDayNightPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Daylight>()
+            .add_systems(
+                Update,
+                (
+                    advance_day_night
+                        .run_if(on_timer(Duration::from_secs(1)))
+                        .in_set(ProgressionSystemSet),
+                    update_daylight,
+                )
+                    .chain()
+                    .run_if(resource_exists::<ProgressionCore>.and(resource_exists::<MapData>)),
+            )
+            .add_systems(Update, tint_daylit_sprites.after(update_daylight));
+    }
+}
+
+#[test]
+fn validate_day_night_json_data() {
+    use crate::assets::DAY_NIGHT_DATA_CORE;
+
+    let data: Vec<DayPhaseData> = serde_json::from_str(DAY_NIGHT_DATA_CORE).unwrap();
+    assert!(!data.is_empty());
+    for (i, phase) in data.iter().enumerate() {
+        assert!(data[..i].iter().all(|other| other.name != phase.name));
+        assert!(phase.duration > 0, "{}", phase.name);
+    }
+}
+
+#[test]
+fn validate_day_night_cycle() {
+    let map_data = MapData::default();
+    let phases = map_data.day_phase_data();
+    let mut day_night = DayNight::default();
+
+    assert_eq!(day_night.phase(phases).unwrap().name, phases[0].name);
+    assert_eq!(
+        day_night.seconds_left_in_phase(phases),
+        Some(phases[0].duration)
+    );
+
+    day_night.advance(phases, phases[0].duration);
+    assert_eq!(day_night.phase(phases).unwrap().name, phases[1].name);
+
+    // The tint only blends at the end of a phase.
+    let tint = day_night.daylight(phases).tint;
+    day_night.advance(phases, phases[1].duration - TRANSITION_SECONDS);
+    assert_eq!(day_night.daylight(phases).tint, tint);
+    day_night.advance(phases, TRANSITION_SECONDS / 2);
+    assert_ne!(day_night.daylight(phases).tint, tint);
+
+    let length = day_length(phases);
+    let mut day_night = DayNight::default();
+    day_night.advance(phases, length * 2 + 1);
+    assert_eq!(day_night.day, 2);
+    assert_eq!(day_night.phase(phases).unwrap().name, phases[0].name);
+
+    // Without phases there is no time of day at all.
+    day_night.advance(&[], 100);
+    assert_eq!(day_night.phase(&[]).map(|phase| &phase.name), None);
+    assert_eq!(day_night.daylight(&[]), Daylight::default());
+}
+
+#[test]
+fn validate_day_night_modifies_production() {
+    let mut core = ProgressionCore::default();
+    let mut map_data = MapData::default();
+    let corn = map_data.flora_registry().get("corn").unwrap();
+    core.add_flora(corn);
+    core.points_cap = 1_000_000u64.into();
+    let day_pps = compute_current_pps(&core, &map_data);
+    assert!(!day_pps.is_zero());
+
+    // Corn sleeps at night, right after the night the next day starts with the dawn.
+    let phases = map_data.day_phase_data().to_vec();
+    let night_start = day_length(&phases) - phases.last().unwrap().duration;
+    core.day_night.advance(&phases, night_start);
+    assert_eq!(core.day_phase(&map_data).unwrap().name, "Night");
+    assert!(compute_current_pps(&core, &map_data).is_zero());
+
+    let night = phases.last().unwrap().duration;
+    let report =
+        apply_offline_progression(&mut core, &mut map_data, &mut WorldRng::new(0), night + 60);
+    assert_eq!(report.points, day_pps * 60u64.into());
+    assert_eq!(core.day_night.day, 1);
+    assert_eq!(core.day_phase(&map_data).unwrap().name, "Dawn");
+}
@@ -28,18 +28,20 @@ fn spawn_grid_debug_visuals(
         return;
     }
 
-    let (player_x, player_y) = map_data.pos_to_grid_indices(player_transform.translation.xy());
+    let Some((player_x, player_y)) =
+        map_data.pos_to_grid_indices(player_transform.translation.xy())
+    else {
+        return;
+    };
 
     for i in 0..DEBUG_GRID_SIZE {
         for j in 0..DEBUG_GRID_SIZE {
             let x = i + player_x.max(DEBUG_GRID_SIZE / 2) - DEBUG_GRID_SIZE / 2;
             let y = j + player_y.max(DEBUG_GRID_SIZE / 2) - DEBUG_GRID_SIZE / 2;
 
-            if !map_data.indices_in_grid(x, y) {
+            let Some(index) = map_data.grid_index(x, y) else {
                 continue;
-            }
-
-            let index = map_data.grid_index(x, y);
+            };
             let (text, color) = if index == EMPTY_CELL_VALUE {
                 ("E".to_string(), Color::WHITE.with_alpha(0.5))
             } else if index == TALL_GRASS_CELL_VALUE {
@@ -103,5 +105,5 @@ impl Plugin for MapDebugPlugin {
 
 #[test]
 fn validate_debug_grid_size_is_halvable() {
-    assert!(DEBUG_GRID_SIZE % 2 == 0)
+    assert!(DEBUG_GRID_SIZE.is_multiple_of(2))
 }
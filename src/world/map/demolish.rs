@@ -0,0 +1,274 @@
+//! Demolishing placed flora.
+//!
+//! The demolish tool is toggled with its own input and follows the cursor just like the blueprint
+//! of a bought item. Demolishing a plant clears its cells (they become empty ground, just like cut
+//! grass) and gives back a fraction of what the last plant of that flora cost.
+
+use bevy::{color::palettes::css::RED, prelude::*, text::FontSmoothing};
+
+use crate::{
+    player::{GamingInput, Player},
+    world::{utils::format_money_string, BigNumber},
+    BachelorBuild, GameAssets,
+};
+
+use super::{
+    building::{spawn_cursor_selector, CursorTool},
+    BuildingSystemSet, Flora, MapData, ProgressionCore, ProgressionSystemSet, ZLevel,
+};
+
+#[cfg(test)]
+use super::EMPTY_CELL_VALUE;
+
+/// Used for flora that don't set their own `refund_fraction` in `flora.json`.
+pub const DEFAULT_REFUND_FRACTION: f32 = 0.5;
+
+const REFUND_TEXT_OFFSET: Vec3 = Vec3::new(0.0, 24.0, 1.0);
+
+#[derive(Component, Default)]
+struct DemolishTool {
+    /// The flora under the cursor, if any.
+    target: Option<Flora>,
+}
+
+#[derive(Component)]
+struct DemolishRefundText;
+
+#[derive(Event)]
+pub struct FloraDemolished {
+    /// Position of the anchor cell the flora was placed at.
+    pub pos: Vec2,
+}
+
+impl ProgressionCore {
+    /// What demolishing a plant of the flora gives back, a fraction of what the last one cost.
+    pub fn flora_refund(&self, map_data: &MapData, flora: Flora) -> BigNumber {
+        let count = self.flora_count(flora);
+        if count == 0 {
+            return BigNumber::ZERO;
+        }
+
+        let flora_data = map_data.flora_data(flora);
+        let last_cost = flora_data.cost(count as u32 - 1);
+        (last_cost * BigNumber::from(flora_data.refund_fraction() as f64)).floor()
+    }
+
+    fn remove_flora(&mut self, flora: Flora) {
+        if let Some(count) = self.flora.get_mut(flora.index()) {
+            *count = count.saturating_sub(1);
+        }
+    }
+
+    /// Remove a plant of the flora and refund it, the refund can't go over the cap.
+    fn demolish_flora(&mut self, map_data: &MapData, flora: Flora) -> BigNumber {
+        let refund = self.flora_refund(map_data, flora);
+        self.points = (self.points + refund).min(self.points_cap);
+        self.remove_flora(flora);
+        refund
+    }
+}
+
+impl MapData {
+    /// Clear the cells of the flora that covers the given cell, returns that flora.
+    pub(super) fn remove_flora_at(&mut self, x: usize, y: usize) -> Option<Flora> {
+        let (anchor, flora) = self.clear_flora(x, y)?;
+        self.growth_ages.remove(&anchor);
+        Some(flora)
+    }
+}
+
+fn toggle_demolish_tool(
+    mut commands: Commands,
+    assets: Res<GameAssets>,
+    gaming_input: Res<GamingInput>,
+    bachelor_build: Res<BachelorBuild>,
+    q_tools: Query<Entity, With<DemolishTool>>,
+    q_cursor_tools: Query<Entity, With<CursorTool>>,
+) {
+    if !gaming_input.demolish || !bachelor_build.with_building {
+        return;
+    }
+
+    if !q_tools.is_empty() {
+        for entity in &q_tools {
+            commands.entity(entity).despawn();
+        }
+        return;
+    }
+
+    for entity in &q_cursor_tools {
+        commands.entity(entity).despawn();
+    }
+
+    let root = commands
+        .spawn((
+            CursorTool,
+            DemolishTool::default(),
+            Visibility::Inherited,
+            Transform::from_xyz(0.0, 0.0, ZLevel::TopUi.value()),
+        ))
+        .id();
+
+    spawn_cursor_selector(&mut commands, &assets, root, (1, 1));
+
+    commands.spawn((
+        ChildOf(root),
+        DemolishRefundText,
+        Text2d::default(),
+        TextFont {
+            font: assets.pixel_font.clone(),
+            font_size: 80.0,
+            font_smoothing: FontSmoothing::None,
+            ..default()
+        },
+        TextColor(Color::WHITE),
+        Transform::from_translation(REFUND_TEXT_OFFSET).with_scale(Vec3::splat(0.1)),
+    ));
+}
+
+fn move_demolish_tool(
+    gaming_input: Res<GamingInput>,
+    map_data: Res<MapData>,
+    mut q_tool: Query<(&mut Transform, &mut DemolishTool)>,
+) {
+    let Ok((mut transform, mut tool)) = q_tool.single_mut() else {
+        return;
+    };
+
+    let cell = map_data.pos_to_cell(gaming_input.mouse_world_coords);
+    let pos = map_data.cell_to_pos(cell);
+
+    transform.translation.x = pos.x;
+    transform.translation.y = pos.y;
+    tool.target = map_data
+        .pos_to_grid_indices(pos)
+        .and_then(|(x, y)| map_data.flora_at(x, y));
+}
+
+fn update_demolish_tool_visuals(
+    core: Res<ProgressionCore>,
+    map_data: Res<MapData>,
+    q_tool: Query<(&Children, &DemolishTool)>,
+    mut q_sprites: Query<&mut Sprite>,
+    mut q_texts: Query<(&mut Text2d, &mut Visibility), With<DemolishRefundText>>,
+) {
+    let Ok((children, tool)) = q_tool.single() else {
+        return;
+    };
+
+    for child in children {
+        if let Ok(mut sprite) = q_sprites.get_mut(*child) {
+            sprite.color = if tool.target.is_some() {
+                RED.into()
+            } else {
+                Color::WHITE.with_alpha(0.5)
+            };
+        }
+
+        let Ok((mut text, mut visibility)) = q_texts.get_mut(*child) else {
+            continue;
+        };
+        match tool.target {
+            Some(flora) => {
+                *visibility = Visibility::Inherited;
+                text.0 = format!(
+                    "+{}",
+                    format_money_string(core.flora_refund(&map_data, flora))
+                );
+            }
+            None => *visibility = Visibility::Hidden,
+        }
+    }
+}
+
+fn demolish_flora_on_confirm(
+    mut core: ResMut<ProgressionCore>,
+    mut map_data: ResMut<MapData>,
+    gaming_input: Res<GamingInput>,
+    q_player: Query<&Player>,
+    q_tool: Query<&Transform, With<DemolishTool>>,
+    mut ev_flora_demolished: EventWriter<FloraDemolished>,
+) {
+    if !gaming_input.confirm {
+        return;
+    }
+
+    let Ok(player) = q_player.single() else {
+        return;
+    };
+    if player.is_over_ui {
+        return;
+    }
+
+    let Ok(transform) = q_tool.single() else {
+        return;
+    };
+
+    let Some((x, y)) = map_data.pos_to_grid_indices(transform.translation.xy()) else {
+        return;
+    };
+    let Some((anchor_x, anchor_y)) = map_data.anchor_of(x, y) else {
+        return;
+    };
+    let Some(flora) = map_data.remove_flora_at(x, y) else {
+        return;
+    };
+
+    core.demolish_flora(&map_data, flora);
+    ev_flora_demolished.write(FloraDemolished {
+        pos: map_data.grid_indices_to_pos(anchor_x, anchor_y),
+    });
+}
+
+pub struct MapDemolishPlugin;
+
+impl Plugin for MapDemolishPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<FloraDemolished>().add_systems(
+            Update,
+            (
+                toggle_demolish_tool
+                    .run_if(resource_exists::<GameAssets>.and(resource_exists::<BachelorBuild>)),
+                move_demolish_tool.run_if(resource_exists::<MapData>),
+                demolish_flora_on_confirm
+                    .run_if(resource_exists::<ProgressionCore>.and(resource_exists::<MapData>)),
+                update_demolish_tool_visuals
+                    .run_if(resource_exists::<ProgressionCore>.and(resource_exists::<MapData>)),
+            )
+                .chain()
+                .in_set(BuildingSystemSet)
+                .after(ProgressionSystemSet),
+        );
+    }
+}
+
+#[test]
+fn validate_demolish_refunds_last_price() {
+    let mut map_data = MapData::default();
+    let mut core = ProgressionCore::default();
+    core.points_cap = 1_000_000u64.into();
+    let pumpkin = map_data.flora_registry().get("pumpkin").unwrap();
+    assert!(core.flora_refund(&map_data, pumpkin).is_zero());
+
+    for _ in 0..10 {
+        core.add_flora(pumpkin);
+    }
+    map_data.set_grid_index(2, 3, pumpkin.cell_value());
+    map_data.growth_ages.insert((2, 3), 30);
+
+    let flora_data = map_data.flora_data(pumpkin);
+    let expected =
+        (flora_data.cost(9) * BigNumber::from(flora_data.refund_fraction() as f64)).floor();
+    assert!(!expected.is_zero());
+
+    assert_eq!(map_data.remove_flora_at(2, 3), Some(pumpkin));
+    assert_eq!(map_data.grid_index(2, 3), Some(EMPTY_CELL_VALUE));
+    assert!(map_data.growth_ages.is_empty());
+    assert_eq!(map_data.remove_flora_at(2, 3), None);
+
+    assert_eq!(core.demolish_flora(&map_data, pumpkin), expected);
+    assert_eq!(core.points, expected);
+    assert_eq!(core.flora_count(pumpkin), 9);
+    // Buying it back costs exactly what the demolished one did.
+    assert_eq!(core.flora_cost(&map_data, pumpkin), flora_data.cost(9));
+}
@@ -0,0 +1,385 @@
+//! Save codes.
+//!
+//! A save code is the entire save slot (core, map data and telemetry) packed into a single line
+//! of text, so that gardens can be moved between the native and the wasm build (which store their
+//! saves in completely different places) and attached to bug reports.
+//!
+//! The format is `ig<version>.<payload>.<checksum>`, where the payload is the deflated slot json
+//! encoded as url safe base64 and the checksum is the truncated sha256 of `<version>.<payload>`.
+//! The checksum only catches codes that got mangled on the way, it's not meant to keep anyone from
+//! editing their garden.
+
+use std::io::{Read, Write};
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use bevy::prelude::*;
+use flate2::{read::DeflateDecoder, write::DeflateEncoder, Compression};
+use sha2::{Digest, Sha256};
+
+#[cfg(not(target_arch = "wasm32"))]
+use crate::assets::{SAVE_CODE_EXPORT_FILE, SAVE_CODE_IMPORT_FILE};
+use crate::ui::{MenuAction, MenuActionEvent};
+
+#[cfg(not(target_arch = "wasm32"))]
+use super::slots;
+use super::{
+    package_save_data,
+    save::{self, SaveError, SerializedChunks},
+    ActiveSlot, FloraRegistry, GameTelemetryManager, MapData, ProgressionCore, SaveSlot, SaveSlots,
+};
+
+const SAVE_CODE_PREFIX: &str = "ig";
+const SAVE_CODE_SEPARATOR: char = '.';
+const SAVE_CODE_VERSION: u32 = 1;
+const SAVE_CODE_CHECKSUM_LENGTH: usize = 16;
+/// Upper bound for the inflated payload, a real save is way smaller than this. Protects us from
+/// codes that inflate to absurd sizes.
+const MAX_DECODED_SAVE_SIZE: u64 = 8 * 1024 * 1024;
+
+/// Sent after a save code was exported or imported from the menu.
+#[derive(Event)]
+pub struct SaveCodeResult {
+    pub action: MenuAction,
+    pub success: bool,
+}
+
+fn checksum(version: u32, payload: &str) -> String {
+    let digest = Sha256::digest(format!("{}{}{}", version, SAVE_CODE_SEPARATOR, payload));
+    let mut checksum = hex::encode(digest);
+    checksum.truncate(SAVE_CODE_CHECKSUM_LENGTH);
+    checksum
+}
+
+pub fn encode_save_code(slot: &SaveSlot) -> String {
+    let mut encoder = DeflateEncoder::new(Vec::new(), Compression::best());
+    let compressed = encoder
+        .write_all(slot.to_string().as_bytes())
+        .and_then(|_| encoder.finish())
+        .unwrap_or_default();
+
+    let payload = URL_SAFE_NO_PAD.encode(compressed);
+    format!(
+        "{}{}{}{}{}{}",
+        SAVE_CODE_PREFIX,
+        SAVE_CODE_VERSION,
+        SAVE_CODE_SEPARATOR,
+        payload,
+        SAVE_CODE_SEPARATOR,
+        checksum(SAVE_CODE_VERSION, &payload)
+    )
+}
+
+/// Decode and validate the given save code. The code is only accepted if the core and the map data
+/// inside of it can actually be loaded.
+pub fn decode_save_code(code: &str) -> Result<SaveSlot, SaveError> {
+    // Codes get pasted from all kinds of places, line breaks included.
+    let code: String = code.chars().filter(|c| !c.is_whitespace()).collect();
+    let malformed = |msg: &str| SaveError::Malformed(msg.to_string());
+
+    let rest = code
+        .strip_prefix(SAVE_CODE_PREFIX)
+        .ok_or_else(|| malformed("save code has an invalid prefix"))?;
+    let mut parts = rest.split(SAVE_CODE_SEPARATOR);
+    let (Some(version), Some(payload), Some(code_checksum), None) =
+        (parts.next(), parts.next(), parts.next(), parts.next())
+    else {
+        return Err(malformed("save code doesn't consist of three parts"));
+    };
+
+    let version = version
+        .parse::<u32>()
+        .map_err(|_| malformed("save code version is not a number"))?;
+    if version > SAVE_CODE_VERSION {
+        return Err(SaveError::UnsupportedVersion(version));
+    }
+    if checksum(version, payload) != code_checksum {
+        return Err(SaveError::ChecksumMismatch);
+    }
+
+    let compressed = URL_SAFE_NO_PAD
+        .decode(payload)
+        .map_err(|err| SaveError::Malformed(err.to_string()))?;
+    let mut json = String::new();
+    DeflateDecoder::new(compressed.as_slice())
+        .take(MAX_DECODED_SAVE_SIZE)
+        .read_to_string(&mut json)
+        .map_err(|err| SaveError::Malformed(err.to_string()))?;
+
+    let slot = SaveSlot::from_str(&json).ok_or_else(|| malformed("save code is not a slot"))?;
+    // Only the ids matter for validating, so the embedded registry is good enough.
+    let flora_registry = FloraRegistry::default();
+    save::deserialize_progression_core(&slot.core, &flora_registry)?;
+    save::deserialize_map_data(&slot.map_data, &flora_registry)?;
+    Ok(slot)
+}
+
+/// Import the given code into a new slot, the current slots are never touched.
+pub fn import_save_code(slots: &mut SaveSlots, code: &str) -> Result<u64, SaveError> {
+    let slot = decode_save_code(code)?;
+    Ok(slots.add_slot(slot))
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn write_exported_code(code: &str) -> bool {
+    if let Err(err) = std::fs::write(SAVE_CODE_EXPORT_FILE, code) {
+        error!(
+            "failed to write save code to: {}, {}",
+            SAVE_CODE_EXPORT_FILE, err
+        );
+        return false;
+    }
+    info!("exported save code to: {}", SAVE_CODE_EXPORT_FILE);
+    true
+}
+
+#[cfg(target_arch = "wasm32")]
+fn write_exported_code(code: &str) -> bool {
+    let Some(window) = web_sys::window() else {
+        error!("failed to get browser window (for exporting save code)");
+        return false;
+    };
+    window
+        .prompt_with_message_and_default("Copy your save code:", code)
+        .is_ok()
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn read_code_to_import() -> Option<String> {
+    match std::fs::read_to_string(SAVE_CODE_IMPORT_FILE) {
+        Ok(code) => Some(code),
+        Err(err) => {
+            error!(
+                "failed to read save code from: {}, {}",
+                SAVE_CODE_IMPORT_FILE, err
+            );
+            None
+        }
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+fn read_code_to_import() -> Option<String> {
+    web_sys::window()?
+        .prompt_with_message("Paste your save code:")
+        .ok()
+        .flatten()
+}
+
+fn export_save_code(
+    core: Res<ProgressionCore>,
+    map_data: Res<MapData>,
+    telemetry: Res<GameTelemetryManager>,
+    active_slot: Res<ActiveSlot>,
+    mut serialized_chunks: Local<SerializedChunks>,
+    mut ev_menu_action: EventReader<MenuActionEvent>,
+    mut ev_save_code_result: EventWriter<SaveCodeResult>,
+) {
+    if !ev_menu_action
+        .read()
+        .any(|ev| ev.action == MenuAction::ExportSave)
+    {
+        return;
+    }
+
+    let slot = package_save_data(
+        &active_slot,
+        &core,
+        &map_data,
+        &telemetry,
+        &mut serialized_chunks,
+    );
+    let success = write_exported_code(&encode_save_code(&slot));
+    ev_save_code_result.write(SaveCodeResult {
+        action: MenuAction::ExportSave,
+        success,
+    });
+}
+
+fn import_save_code_from_menu(
+    mut slots: ResMut<SaveSlots>,
+    mut ev_menu_action: EventReader<MenuActionEvent>,
+    mut ev_save_code_result: EventWriter<SaveCodeResult>,
+) {
+    if !ev_menu_action
+        .read()
+        .any(|ev| ev.action == MenuAction::ImportSave)
+    {
+        return;
+    }
+
+    let Some(code) = read_code_to_import() else {
+        return;
+    };
+
+    let success = match import_save_code(&mut slots, &code) {
+        Ok(id) => {
+            info!("imported save code into slot: {}", id);
+            true
+        }
+        Err(err) => {
+            error!("refusing to import save code, {}", err);
+            false
+        }
+    };
+    ev_save_code_result.write(SaveCodeResult {
+        action: MenuAction::ImportSave,
+        success,
+    });
+}
+
+/// Handle `export-save [slot name]` and `import-save <code or file>` from the command line.
+/// Returns `None` if the arguments didn't contain any of the two, the error is meant for the user.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn run_save_code_command(args: &[String]) -> Option<Result<(), String>> {
+    let index = args
+        .iter()
+        .position(|s| s == "export-save" || s == "import-save")?;
+    let argument = args.get(index + 1);
+    let mut slots = slots::read_save_slots();
+
+    if args[index] == "export-save" {
+        let entry = match argument {
+            Some(name) => slots.entries.iter().find(|e| &e.name == name),
+            None => slots.entries.last(),
+        };
+        let Some(slot) = entry.and_then(|e| slots::read_slot(e.id)) else {
+            return Some(Err("no matching save slot found".to_string()));
+        };
+        println!("{}", encode_save_code(&slot));
+        return Some(Ok(()));
+    }
+
+    Some(import_save_code_from_argument(&mut slots, argument))
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn import_save_code_from_argument(
+    slots: &mut SaveSlots,
+    argument: Option<&String>,
+) -> Result<(), String> {
+    let argument =
+        argument.ok_or("usage: import-save <code or file containing the code>".to_string())?;
+    // Anything that doesn't look like a code has to be a file.
+    let code = match std::fs::read_to_string(argument) {
+        Ok(code) => code,
+        Err(_) if argument.starts_with(SAVE_CODE_PREFIX) => argument.clone(),
+        Err(err) => {
+            return Err(format!(
+                "failed to read save code from: {}, {}",
+                argument, err
+            ))
+        }
+    };
+
+    let id = import_save_code(slots, &code)
+        .map_err(|err| format!("refusing to import save code, {}", err))?;
+    if slots::read_slot(id).is_none() {
+        return Err(format!("failed to write the imported save slot: {}", id));
+    }
+    println!("imported save code into slot: {}", id);
+    Ok(())
+}
+
+pub struct MapExportPlugin;
+
+impl Plugin for MapExportPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<SaveCodeResult>().add_systems(
+            Update,
+            (
+                export_save_code.run_if(
+                    resource_exists::<ProgressionCore>
+                        .and(resource_exists::<MapData>)
+                        .and(resource_exists::<ActiveSlot>),
+                ),
+                import_save_code_from_menu.run_if(resource_exists::<SaveSlots>),
+            ),
+        );
+    }
+}
+
+#[cfg(test)]
+fn test_slot() -> SaveSlot {
+    let mut core = ProgressionCore::default();
+    core.points = 4242u64.into();
+    let mut map_data = MapData::default();
+    map_data.set_grid_index(1, 2, 0);
+
+    SaveSlot {
+        name: "Test".to_string(),
+        core: save::serialize_progression_core(&core, map_data.flora_registry()),
+        map_data: save::serialize_map_data(&map_data),
+        telemetry: String::new(),
+    }
+}
+
+#[test]
+fn validate_save_code_round_trip() {
+    let slot = test_slot();
+    let code = encode_save_code(&slot);
+    assert!(!code.contains(char::is_whitespace));
+
+    // Line breaks from copy pasting are fine.
+    let (head, tail) = code.split_at(code.len() / 2);
+    let loaded = decode_save_code(&format!("{}\n{}\n", head, tail)).unwrap();
+    assert_eq!(loaded.to_string(), slot.to_string());
+}
+
+#[test]
+fn validate_tampered_save_codes_are_rejected() {
+    let code = encode_save_code(&test_slot());
+    let (version, rest) = code.split_once(SAVE_CODE_SEPARATOR).unwrap();
+    let (payload, code_checksum) = rest.split_once(SAVE_CODE_SEPARATOR).unwrap();
+
+    let mut tampered_payload = payload.to_string();
+    let last = tampered_payload.pop().unwrap();
+    tampered_payload.push(if last == 'A' { 'B' } else { 'A' });
+    let tampered = format!("{}.{}.{}", version, tampered_payload, code_checksum);
+    assert_eq!(
+        decode_save_code(&tampered).err(),
+        Some(SaveError::ChecksumMismatch)
+    );
+
+    let truncated = &code[..code.len() - 1];
+    assert_eq!(
+        decode_save_code(truncated).err(),
+        Some(SaveError::ChecksumMismatch)
+    );
+}
+
+#[test]
+fn validate_malformed_save_codes_are_rejected() {
+    assert!(decode_save_code("").is_err());
+    assert!(decode_save_code("banana").is_err());
+    assert!(decode_save_code("ig1.abc").is_err());
+    assert!(decode_save_code("ig1.a.b.c").is_err());
+
+    // Correct checksum, but the payload isn't a save.
+    let payload = URL_SAFE_NO_PAD.encode("not deflated");
+    let code = format!("ig1.{}.{}", payload, checksum(1, &payload));
+    assert!(matches!(
+        decode_save_code(&code),
+        Err(SaveError::Malformed(_))
+    ));
+
+    let future = format!("ig{}.a.b", SAVE_CODE_VERSION + 1);
+    assert_eq!(
+        decode_save_code(&future).err(),
+        Some(SaveError::UnsupportedVersion(SAVE_CODE_VERSION + 1))
+    );
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[test]
+fn validate_save_code_command_errors() {
+    let arg = |s: &str| Some(s.to_string());
+
+    assert_eq!(run_save_code_command(&["idle-garden".to_string()]), None);
+
+    // None of these get far enough to write a slot.
+    let mut slots = SaveSlots::default();
+    assert!(import_save_code_from_argument(&mut slots, None).is_err());
+    assert!(import_save_code_from_argument(&mut slots, arg("missing/code.txt").as_ref()).is_err());
+    assert!(import_save_code_from_argument(&mut slots, arg("ig1.abc.def").as_ref()).is_err());
+    assert!(slots.entries.is_empty());
+}
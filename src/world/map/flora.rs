@@ -1,41 +1,116 @@
+use std::fmt;
+
 use bevy::{
+    asset::{io::Reader, AssetLoadFailedEvent, AssetLoader, LoadContext},
     prelude::*,
     render::render_resource::{AsBindGroup, ShaderRef},
     sprite::{AlphaMode2d, Material2d, Material2dPlugin},
 };
 
 use serde::Deserialize;
-use strum::FromRepr;
 
 use crate::{
-    assets::FLORA_SHADER,
+    assets::{FLORA_DATA_CORE, FLORA_SHADER},
     ui::{MenuAction, MenuActionEvent},
-    world::{camera::YSort, collisions::WORLD_COLLISION_GROUPS, TILE_SIZE},
+    world::{
+        camera::YSort,
+        collisions::{StaticSensorAABB, CROP_COLLISION_GROUPS, WORLD_COLLISION_GROUPS},
+        BigNumber,
+    },
     BachelorBuild, EffectAssets, GameAssets,
 };
 
-use super::{ItemBought, MapData, EMPTY_CELL_VALUE, MAP_SIZE, TALL_GRASS_CELL_VALUE};
+use super::{
+    cost::CostCurve,
+    demolish::{FloraDemolished, DEFAULT_REFUND_FRACTION},
+    footprint::size_offset,
+    growth::{Crop, GrowthData},
+    relocate::FloraMoved,
+    save::{deserialize_progression_core, serialize_progression_core},
+    structures::STRUCTURE_ID_PREFIX,
+    Daylit, ItemBought, MapData, PrestigeAction, PrestigeActionEvent, ProgressionCore,
+    EMPTY_CELL_ID, TALL_GRASS_CELL_ID, TALL_GRASS_CELL_VALUE,
+};
+
+#[cfg(test)]
+use super::EMPTY_CELL_VALUE;
 
 #[derive(Deserialize, Clone, Default)]
 pub struct FloraData {
-    base_cost: u32,
-    pub cost_growth_factor: f32,
-    pub pps: u32,
+    cost: CostCurve,
+    /// The fraction of the last price that is given back when demolishing a plant.
+    #[serde(default = "default_refund_fraction")]
+    refund_fraction: f32,
+    pub pps: BigNumber,
     ysort: f32,
     size_on_grid: (usize, usize),
+    /// Only flora with growth stages can be harvested.
+    pub growth: Option<GrowthData>,
+}
+
+/// An extra sprite that is drawn on top of the main sprite of a flora (e.g. the corn crops).
+#[derive(Deserialize, Clone)]
+struct SpriteLayer {
+    sprite: String,
+    #[serde(default)]
+    offset: Vec2,
+    ysort: f32,
+    #[serde(skip)]
+    image: Handle<Image>,
 }
 
-#[derive(Clone, Copy, Deserialize, Hash, Eq, PartialEq, Default, FromRepr, Debug)]
-pub enum Flora {
-    #[default]
-    Potatoe,
-    Raddish,
-    Carrot,
-    Corn,
-    Pumpkin,
-    Onion,
+/// A single entry of `flora.json`.
+#[derive(Deserialize, Clone)]
+struct FloraDefinition {
+    /// Stable id, this is what ends up in the save data.
+    id: String,
+    name: String,
+    sprite: String,
+    /// Shown in the store, flora without an icon use their sprite instead.
+    icon: Option<String>,
+    #[serde(default)]
+    layers: Vec<SpriteLayer>,
+    #[serde(flatten)]
+    data: FloraData,
+    #[serde(skip)]
+    image: Handle<Image>,
+    #[serde(skip)]
+    icon_image: Handle<Image>,
 }
 
+/// Index into the `FloraRegistry`. Only the ids are stable, never save this directly.
+#[derive(Clone, Copy, Hash, Eq, PartialEq, Default, Debug)]
+pub struct Flora(u16);
+
+/// All flora, in the order they show up in the store.
+///
+/// Loaded from `flora.json` through the asset server, which also loads all the sprites. The
+/// `Default` is parsed from the embedded data and has no sprites, which is all the simulation
+/// (and the tests) need.
+#[derive(Asset, TypePath, Clone)]
+pub struct FloraRegistry {
+    definitions: Vec<FloraDefinition>,
+}
+
+#[derive(Debug)]
+pub enum FloraRegistryError {
+    Io(std::io::Error),
+    Json(serde_json::Error),
+    DuplicateId(String),
+    ReservedId(String),
+    /// The data of the flora with the given id doesn't make sense.
+    InvalidData(String, &'static str),
+}
+
+/// Present while `flora.json` failed to load, the game keeps running with the last valid data.
+#[derive(Resource)]
+pub struct FloraRegistryLoadError {
+    pub message: String,
+}
+
+#[derive(Default)]
+struct FloraRegistryLoader;
+
 /// This is used as an Event, but because Events are a little more boiler plate I opted to use just
 /// a resource. We insert this when we spawn the flora, only after that do we want to spawn the
 /// grass (and only at places where there is no flora already).
@@ -43,7 +118,9 @@ pub enum Flora {
 pub struct InitialFloraSpawned;
 
 #[derive(Component)]
-struct FloraMarker;
+struct FloraMarker {
+    pos: Vec2,
+}
 
 #[derive(Asset, TypePath, AsBindGroup, Debug, Clone)]
 pub struct FloraMaterial {
@@ -54,44 +131,213 @@ pub struct FloraMaterial {
     pub texture: Option<Handle<Image>>,
 }
 
+fn default_refund_fraction() -> f32 {
+    DEFAULT_REFUND_FRACTION
+}
+
 impl Flora {
-    pub fn from_index(index: usize) -> Option<Self> {
-        let maybe_flora = Self::from_repr(index);
+    pub fn index(&self) -> usize {
+        self.0 as usize
+    }
+
+    /// The value of the cells in the grid that are occupied by this flora.
+    pub fn cell_value(&self) -> u16 {
+        self.0
+    }
+}
 
-        if maybe_flora.is_none() {
-            error!("failed to create Flora from index, probably backwards incompatibility.");
+impl fmt::Display for FloraRegistryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FloraRegistryError::Io(err) => write!(f, "failed to read flora data: {}", err),
+            FloraRegistryError::Json(err) => write!(f, "failed to parse flora data: {}", err),
+            FloraRegistryError::DuplicateId(id) => write!(f, "duplicate flora id: {}", id),
+            FloraRegistryError::ReservedId(id) => write!(f, "flora id is reserved: {}", id),
+            FloraRegistryError::InvalidData(id, reason) => {
+                write!(f, "invalid data of flora {}: {}", id, reason)
+            }
+        }
+    }
+}
+
+impl std::error::Error for FloraRegistryError {}
+
+impl Default for FloraRegistry {
+    fn default() -> Self {
+        Self::from_json(FLORA_DATA_CORE.as_bytes()).unwrap_or_else(|err| {
+            error!("{}", err);
+            Self {
+                definitions: Vec::new(),
+            }
+        })
+    }
+}
+
+impl FloraRegistry {
+    pub fn from_json(raw: &[u8]) -> Result<Self, FloraRegistryError> {
+        let definitions: Vec<FloraDefinition> =
+            serde_json::from_slice(raw).map_err(FloraRegistryError::Json)?;
+
+        for (i, definition) in definitions.iter().enumerate() {
+            if definition.id == TALL_GRASS_CELL_ID
+                || definition.id == EMPTY_CELL_ID
+                || definition.id.starts_with(STRUCTURE_ID_PREFIX)
+            {
+                return Err(FloraRegistryError::ReservedId(definition.id.clone()));
+            }
+            if definitions[..i]
+                .iter()
+                .any(|other| other.id == definition.id)
+            {
+                return Err(FloraRegistryError::DuplicateId(definition.id.clone()));
+            }
+            if let Some(reason) = definition.data.validate() {
+                return Err(FloraRegistryError::InvalidData(
+                    definition.id.clone(),
+                    reason,
+                ));
+            }
         }
-        maybe_flora
+
+        // The values at the end of the range are reserved for the special cells.
+        debug_assert!(definitions.len() < TALL_GRASS_CELL_VALUE as usize);
+        Ok(Self { definitions })
     }
 
-    fn last() -> Self {
-        Flora::Onion
+    pub fn len(&self) -> usize {
+        self.definitions.len()
     }
 
-    pub fn len() -> usize {
-        Flora::last().index() + 1
+    pub fn iter(&self) -> impl Iterator<Item = Flora> + use<> {
+        (0..self.definitions.len() as u16).map(Flora)
     }
 
-    pub fn index(&self) -> usize {
-        *self as usize
+    /// `None` if the value isn't the index of any flora (e.g. a tall grass cell).
+    pub fn by_index(&self, index: usize) -> Option<Flora> {
+        (index < self.definitions.len()).then_some(Flora(index as u16))
+    }
+
+    /// Look up a flora by its id.
+    pub fn get(&self, id: &str) -> Option<Flora> {
+        self.definitions
+            .iter()
+            .position(|definition| definition.id == id)
+            .map(|index| Flora(index as u16))
     }
 
-    pub fn image(&self, assets: &GameAssets) -> Handle<Image> {
-        assets.flora_images[self.index()].clone()
+    fn definition(&self, flora: Flora) -> Option<&FloraDefinition> {
+        let definition = self.definitions.get(flora.index());
+        if definition.is_none() {
+            error!(
+                "attempted to get flora definition from index out of range: {}, must never happen!",
+                flora.index()
+            );
+        }
+        definition
     }
 
-    pub fn icon(&self, assets: &GameAssets) -> Handle<Image> {
-        assets.flora_icons[self.index()].clone()
+    pub fn id(&self, flora: Flora) -> &str {
+        self.definition(flora)
+            .map(|definition| definition.id.as_str())
+            .unwrap_or_default()
+    }
+
+    pub fn name(&self, flora: Flora) -> &str {
+        self.definition(flora)
+            .map(|definition| definition.name.as_str())
+            .unwrap_or_default()
+    }
+
+    pub fn data(&self, flora: Flora) -> FloraData {
+        self.definition(flora)
+            .map(|definition| definition.data.clone())
+            .unwrap_or_default()
+    }
+
+    pub fn image(&self, flora: Flora) -> Handle<Image> {
+        self.definition(flora)
+            .map(|definition| definition.image.clone())
+            .unwrap_or_default()
+    }
+
+    pub fn icon(&self, flora: Flora) -> Handle<Image> {
+        self.definition(flora)
+            .map(|definition| definition.icon_image.clone())
+            .unwrap_or_default()
+    }
+
+    fn layers(&self, flora: Flora) -> &[SpriteLayer] {
+        self.definition(flora)
+            .map(|definition| definition.layers.as_slice())
+            .unwrap_or_default()
+    }
+}
+
+impl AssetLoader for FloraRegistryLoader {
+    type Asset = FloraRegistry;
+    type Settings = ();
+    type Error = FloraRegistryError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader
+            .read_to_end(&mut bytes)
+            .await
+            .map_err(FloraRegistryError::Io)?;
+
+        let mut registry = FloraRegistry::from_json(&bytes)?;
+        for definition in &mut registry.definitions {
+            definition.image = load_context.load(&definition.sprite);
+            definition.icon_image = match &definition.icon {
+                Some(icon) => load_context.load(icon),
+                None => definition.image.clone(),
+            };
+            for layer in &mut definition.layers {
+                layer.image = load_context.load(&layer.sprite);
+            }
+        }
+        Ok(registry)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["json"]
     }
 }
 
 impl FloraData {
+    /// Catch the mistakes that would otherwise only show up as weird behaviour in game.
+    fn validate(&self) -> Option<&'static str> {
+        let (x, y) = self.size_on_grid;
+        if x == 0 || y == 0 {
+            return Some("size_on_grid must not be zero");
+        }
+        if !(0.0..=1.0).contains(&self.refund_fraction) {
+            return Some("refund_fraction must be between 0 and 1");
+        }
+        self.cost.validate()
+    }
+
     fn ysort(&self) -> f32 {
         self.ysort
     }
 
-    pub fn cost(&self, count: usize) -> u32 {
-        self.base_cost * (self.cost_growth_factor.powi(count as i32)).floor() as u32
+    /// The cost of the next plant when `count` are already owned.
+    pub fn cost(&self, count: u32) -> BigNumber {
+        self.cost.cost(count)
+    }
+
+    /// The total cost of the next `amount` plants when `count` are already owned.
+    pub fn bulk_cost(&self, count: u32, amount: u32) -> BigNumber {
+        self.cost.bulk_cost(count, amount)
+    }
+
+    pub fn refund_fraction(&self) -> f32 {
+        self.refund_fraction
     }
 
     pub fn size_on_grid(&self) -> (usize, usize) {
@@ -101,17 +347,11 @@ impl FloraData {
         (x, y)
     }
 
-    /// Offset that will center the item based on its grid size.
+    /// Offset from the anchor cell to the center of the footprint.
     /// Has nothing to do with the `gfx_offset`, they are two separate things.
     /// Altough they are often very similar.
-    #[allow(dead_code)]
-    fn size_offset(&self) -> Vec2 {
-        let (x, y) = self.size_on_grid;
-
-        debug_assert!(x > 0);
-        debug_assert!(y > 0);
-
-        0.5 * TILE_SIZE * Vec2::new((x - 1) as f32, (y - 1) as f32)
+    pub fn size_offset(&self) -> Vec2 {
+        size_offset(self.size_on_grid)
     }
 }
 
@@ -127,58 +367,62 @@ impl Material2d for FloraMaterial {
 
 fn spawn_flora(
     commands: &mut Commands,
-    assets: &GameAssets,
     _effects: &EffectAssets,
     _materials: &mut Assets<FloraMaterial>,
     _images: &Assets<Image>,
     pos: Vec2,
-    flora: &Flora,
-    data: &FloraData,
+    flora: Flora,
+    registry: &FloraRegistry,
 ) {
-    let ysort = data.ysort();
+    let data = registry.data(flora);
+    // The marker (and crop) keep the position of the anchor, the visuals are centered on the
+    // footprint.
+    let center = pos + data.size_offset();
+    let (x_size, y_size) = data.size_on_grid();
 
     let root = commands
         .spawn((
-            FloraMarker,
+            FloraMarker { pos },
             Transform::default(),
             Visibility::Inherited,
             WORLD_COLLISION_GROUPS,
         ))
         .id();
 
-    let image_handle = flora.image(assets);
-
     commands.spawn((
         ChildOf(root),
-        YSort(ysort),
-        Transform::from_translation(pos.extend(0.0)),
+        YSort(data.ysort()),
+        Transform::from_translation(center.extend(0.0)),
         Sprite {
-            image: image_handle.clone(),
+            image: registry.image(flora),
             ..default()
         },
+        Daylit,
     ));
 
-    if flora == &Flora::Corn {
-        debug_assert!(ysort < 0.0);
+    for layer in registry.layers(flora) {
         commands.spawn((
             ChildOf(root),
-            Transform::from_translation((pos + Vec2::new(0.0, 16.0)).extend(0.0)),
-            YSort(10.0),
-            Sprite::from_image(assets.corn_crop_left.clone()),
+            Transform::from_translation((center + layer.offset).extend(0.0)),
+            YSort(layer.ysort),
+            Sprite::from_image(layer.image.clone()),
+            Daylit,
         ));
+    }
 
+    if data.growth.is_some() {
+        commands.entity(root).insert(Crop { pos });
         commands.spawn((
             ChildOf(root),
-            Transform::from_translation((pos + Vec2::new(0.0, 16.0)).extend(0.0)),
-            YSort(22.0),
-            Sprite::from_image(assets.corn_crop_right.clone()),
+            Transform::from_translation(center.extend(0.0)),
+            StaticSensorAABB::new(8.0 * x_size as f32, 8.0 * y_size as f32),
+            CROP_COLLISION_GROUPS,
         ));
     }
 }
 
 fn spawn_flora_on_item_bought(
     mut commands: Commands,
-    assets: Res<GameAssets>,
     effects: Res<EffectAssets>,
     mut materials: ResMut<Assets<FloraMaterial>>,
     images: Res<Assets<Image>>,
@@ -191,70 +435,204 @@ fn spawn_flora_on_item_bought(
     }
 
     for ev in ev_item_bought.read() {
-        let flora_data = map_data.flora_data(ev.item.index());
         let pos = ev.pos;
+        let Some((x, y)) = map_data.pos_to_grid_indices(pos) else {
+            error!("bought flora off the map, must never happen!");
+            continue;
+        };
 
-        map_data.set_map_data_value_at_pos(pos, flora_data.size_on_grid(), ev.item.index() as u16);
+        map_data.place_flora(x, y, ev.item);
         spawn_flora(
             &mut commands,
-            &assets,
             &effects,
             &mut materials,
             &images,
             pos,
-            &ev.item,
-            &flora_data,
+            ev.item,
+            map_data.flora_registry(),
         );
     }
 }
 
 fn spawn_flora_on_map_data_insertion(
     mut commands: Commands,
-    assets: Res<GameAssets>,
     effects: Res<EffectAssets>,
     mut materials: ResMut<Assets<FloraMaterial>>,
     images: Res<Assets<Image>>,
     map_data: Res<MapData>,
 ) {
     commands.insert_resource(InitialFloraSpawned);
-    for x in 0..MAP_SIZE {
-        for y in 0..MAP_SIZE {
-            if map_data.grid_index(x, y) == EMPTY_CELL_VALUE
-                || map_data.grid_index(x, y) == TALL_GRASS_CELL_VALUE
-            {
-                continue;
-            }
+    for ((x, y), flora) in map_data.placed_flora() {
+        spawn_flora(
+            &mut commands,
+            &effects,
+            &mut materials,
+            &images,
+            map_data.grid_indices_to_pos(x, y),
+            flora,
+            map_data.flora_registry(),
+        );
+    }
+}
 
-            let pos = map_data.grid_indices_to_pos(x, y);
+/// The registry from the asset server, which (unlike the embedded default) comes with sprites.
+pub fn loaded_flora_registry(
+    assets: &GameAssets,
+    registries: &Assets<FloraRegistry>,
+) -> FloraRegistry {
+    match registries.get(&assets.flora_registry) {
+        Some(registry) => registry.clone(),
+        None => {
+            error!("flora registry is not loaded, must be loaded at this point");
+            FloraRegistry::default()
+        }
+    }
+}
 
-            let Some(flora) = Flora::from_index(map_data.grid_index(x, y).into()) else {
-                continue;
-            };
-            let flora_data = map_data.flora_data(flora.index());
-
-            spawn_flora(
-                &mut commands,
-                &assets,
-                &effects,
-                &mut materials,
-                &images,
-                pos,
-                &flora,
-                &flora_data,
+/// Pick up changes to `flora.json` while the game is running, see `assets::hot_reload`.
+///
+/// Costs, pps and affordability are all computed from the `MapData` on the fly, so swapping in
+/// the new registry is enough for those. The placed flora are spawned again, their sprites, sizes
+/// or even their ids might have changed.
+fn reload_flora_registry(
+    mut commands: Commands,
+    assets: Res<GameAssets>,
+    effects: Res<EffectAssets>,
+    mut materials: ResMut<Assets<FloraMaterial>>,
+    images: Res<Assets<Image>>,
+    flora_registries: Res<Assets<FloraRegistry>>,
+    mut core: ResMut<ProgressionCore>,
+    mut map_data: ResMut<MapData>,
+    q_floras: Query<Entity, With<FloraMarker>>,
+    mut ev_asset: EventReader<AssetEvent<FloraRegistry>>,
+) {
+    if !ev_asset
+        .read()
+        .any(|ev| ev.is_modified(&assets.flora_registry))
+    {
+        return;
+    }
+
+    let Some(flora_registry) = flora_registries.get(&assets.flora_registry) else {
+        return;
+    };
+
+    // The save format stores the flora by id, going through it puts the counts of added, removed
+    // or reordered flora in the right place.
+    let raw_core = serialize_progression_core(&core, map_data.flora_registry());
+    match deserialize_progression_core(&raw_core, flora_registry) {
+        Ok(reloaded_core) => *core = reloaded_core,
+        Err(err) => {
+            error!(
+                "failed to remap progression core to reloaded flora data, {}",
+                err
             );
+            return;
         }
     }
+    map_data.set_flora_registry(flora_registry.clone());
+
+    for entity in &q_floras {
+        commands.entity(entity).despawn();
+    }
+    for ((x, y), flora) in map_data.placed_flora() {
+        spawn_flora(
+            &mut commands,
+            &effects,
+            &mut materials,
+            &images,
+            map_data.grid_indices_to_pos(x, y),
+            flora,
+            map_data.flora_registry(),
+        );
+    }
+    info!("reloaded flora data");
+}
+
+fn track_flora_registry_load_errors(
+    mut commands: Commands,
+    mut ev_asset: EventReader<AssetEvent<FloraRegistry>>,
+    mut ev_load_failed: EventReader<AssetLoadFailedEvent<FloraRegistry>>,
+) {
+    if ev_asset.read().any(|ev| {
+        matches!(
+            ev,
+            AssetEvent::LoadedWithDependencies { .. } | AssetEvent::Modified { .. }
+        )
+    }) {
+        commands.remove_resource::<FloraRegistryLoadError>();
+    }
+
+    if let Some(ev) = ev_load_failed.read().last() {
+        error!("{}", ev.error);
+        commands.insert_resource(FloraRegistryLoadError {
+            message: ev.error.to_string(),
+        });
+    }
+}
+
+fn despawn_flora_on_demolished(
+    mut commands: Commands,
+    q_floras: Query<(Entity, &FloraMarker)>,
+    mut ev_flora_demolished: EventReader<FloraDemolished>,
+) {
+    for ev in ev_flora_demolished.read() {
+        for (entity, marker) in &q_floras {
+            if marker.pos == ev.pos {
+                commands.entity(entity).despawn();
+            }
+        }
+    }
+}
+
+fn respawn_flora_on_moved(
+    mut commands: Commands,
+    effects: Res<EffectAssets>,
+    mut materials: ResMut<Assets<FloraMaterial>>,
+    images: Res<Assets<Image>>,
+    map_data: Res<MapData>,
+    q_floras: Query<(Entity, &FloraMarker)>,
+    mut ev_flora_moved: EventReader<FloraMoved>,
+) {
+    for ev in ev_flora_moved.read() {
+        for (entity, marker) in &q_floras {
+            if marker.pos == ev.from {
+                commands.entity(entity).despawn();
+            }
+        }
+
+        let Some(flora) = map_data
+            .pos_to_grid_indices(ev.to)
+            .and_then(|(x, y)| map_data.flora_at(x, y))
+        else {
+            error!("no flora at the cell it was moved to, must never happen!");
+            continue;
+        };
+        spawn_flora(
+            &mut commands,
+            &effects,
+            &mut materials,
+            &images,
+            ev.to,
+            flora,
+            map_data.flora_registry(),
+        );
+    }
 }
 
 fn despawn_flora_on_reset(
     mut commands: Commands,
     q_floras: Query<Entity, With<FloraMarker>>,
     mut ev_menu_action: EventReader<MenuActionEvent>,
+    mut ev_prestige_action: EventReader<PrestigeActionEvent>,
 ) {
-    if !ev_menu_action
+    let reset = ev_menu_action
         .read()
-        .any(|ev| ev.action == MenuAction::Reset)
-    {
+        .any(|ev| ev.action == MenuAction::Reset);
+    let rebirth = ev_prestige_action
+        .read()
+        .any(|ev| ev.action == PrestigeAction::Rebirth);
+    if !reset && !rebirth {
         return;
     }
 
@@ -268,21 +646,141 @@ pub struct MapFloraPlugin;
 impl Plugin for MapFloraPlugin {
     fn build(&self, app: &mut App) {
         app.add_plugins(Material2dPlugin::<FloraMaterial>::default())
+            .init_asset::<FloraRegistry>()
+            .init_asset_loader::<FloraRegistryLoader>()
             .add_systems(
                 Update,
                 (
                     spawn_flora_on_item_bought.run_if(
-                        resource_exists::<GameAssets>
+                        resource_exists::<EffectAssets>
                             .and(resource_exists::<MapData>.and(resource_exists::<BachelorBuild>)),
                     ),
                     spawn_flora_on_map_data_insertion.run_if(
-                        resource_exists::<GameAssets>
-                            .and(resource_exists::<EffectAssets>)
+                        resource_exists::<EffectAssets>
                             .and(resource_exists::<MapData>)
                             .and(run_once),
                     ),
                     despawn_flora_on_reset,
+                    despawn_flora_on_demolished,
+                    respawn_flora_on_moved
+                        .run_if(resource_exists::<EffectAssets>.and(resource_exists::<MapData>)),
+                    reload_flora_registry.run_if(
+                        resource_exists::<GameAssets>
+                            .and(resource_exists::<EffectAssets>)
+                            .and(resource_exists::<ProgressionCore>)
+                            .and(resource_exists::<MapData>),
+                    ),
+                    track_flora_registry_load_errors,
                 ),
             );
     }
 }
+
+#[test]
+fn validate_flora_json_data() {
+    let registry = FloraRegistry::from_json(FLORA_DATA_CORE.as_bytes()).unwrap();
+    assert!(registry.len() > 0);
+
+    for flora in registry.iter() {
+        assert_eq!(registry.get(registry.id(flora)), Some(flora));
+        assert!(!registry.name(flora).is_empty());
+        let (x, y) = registry.data(flora).size_on_grid();
+        assert!(x > 0 && y > 0);
+    }
+}
+
+#[test]
+fn validate_flora_registry_ids() {
+    let sunflower = r#"{
+        "id": "sunflower", "name": "Sunflower", "sprite": "map/sunflower.png",
+        "cost": {"model": "exponential", "base": 1, "growth": 1.1},
+        "pps": 1, "ysort": -300, "size_on_grid": [1, 1]
+    }"#;
+
+    let registry = FloraRegistry::from_json(format!("[{}]", sunflower).as_bytes()).unwrap();
+    let flora = registry.get("sunflower").unwrap();
+    assert_eq!(registry.name(flora), "Sunflower");
+    assert!(registry.layers(flora).is_empty());
+    assert_eq!(registry.get("corn"), None);
+    assert_eq!(registry.by_index(1), None);
+
+    assert!(matches!(
+        FloraRegistry::from_json(format!("[{}, {}]", sunflower, sunflower).as_bytes()),
+        Err(FloraRegistryError::DuplicateId(_))
+    ));
+    assert!(matches!(
+        FloraRegistry::from_json(
+            format!(
+                "[{}]",
+                sunflower.replace(r#""sunflower""#, r#""tall_grass""#)
+            )
+            .as_bytes()
+        ),
+        Err(FloraRegistryError::ReservedId(_))
+    ));
+    assert!(matches!(
+        FloraRegistry::from_json(
+            format!("[{}]", sunflower.replace(r#""sunflower""#, r#""empty""#)).as_bytes()
+        ),
+        Err(FloraRegistryError::ReservedId(_))
+    ));
+}
+
+#[test]
+fn validate_flora_registry_rejects_invalid_data() {
+    let flora = |size: &str, growth: f32| {
+        format!(
+            r#"[{{
+                "id": "sunflower", "name": "Sunflower", "sprite": "map/sunflower.png",
+                "cost": {{"model": "exponential", "base": 1, "growth": {}}},
+                "pps": 1, "ysort": -300, "size_on_grid": {}
+            }}]"#,
+            growth, size
+        )
+    };
+
+    assert!(FloraRegistry::from_json(flora("[1, 1]", 1.0).as_bytes()).is_ok());
+    assert!(matches!(
+        FloraRegistry::from_json(flora("[0, 1]", 1.1).as_bytes()),
+        Err(FloraRegistryError::InvalidData(_, _))
+    ));
+    assert!(matches!(
+        FloraRegistry::from_json(flora("[1, 1]", 0.9).as_bytes()),
+        Err(FloraRegistryError::InvalidData(_, _))
+    ));
+    assert!(matches!(
+        FloraRegistry::from_json(b"[{\"id\": \"sunflower\"}]"),
+        Err(FloraRegistryError::Json(_))
+    ));
+}
+
+#[test]
+fn validate_reloaded_flora_registry_remaps_cells() {
+    let mut map_data = MapData::default();
+    let corn = map_data.flora_registry().get("corn").unwrap();
+    let onion = map_data.flora_registry().get("onion").unwrap();
+    map_data.set_grid_index(0, 0, corn.cell_value());
+    map_data.set_grid_index(1, 0, onion.cell_value());
+    map_data.set_grid_index(2, 0, EMPTY_CELL_VALUE);
+    map_data.growth_ages.insert((1, 0), 10);
+
+    // Reverse the order and drop the onion.
+    let mut definitions: serde_json::Value = serde_json::from_str(FLORA_DATA_CORE).unwrap();
+    let definitions = definitions.as_array_mut().unwrap();
+    definitions.reverse();
+    definitions.retain(|definition| definition["id"] != "onion");
+    let reloaded = FloraRegistry::from_json(
+        serde_json::Value::from(definitions.clone())
+            .to_string()
+            .as_bytes(),
+    )
+    .unwrap();
+
+    map_data.set_flora_registry(reloaded);
+    let corn = map_data.flora_registry().get("corn").unwrap();
+    assert_eq!(map_data.flora_at(0, 0), Some(corn));
+    assert_eq!(map_data.grid_index(1, 0), Some(EMPTY_CELL_VALUE));
+    assert!(map_data.growth_ages.is_empty());
+    assert_eq!(map_data.grid_index(2, 0), Some(EMPTY_CELL_VALUE));
+    assert_eq!(map_data.grid_index(3, 0), Some(TALL_GRASS_CELL_VALUE));
+}
@@ -0,0 +1,200 @@
+//! Flora that cover more than a single cell.
+//!
+//! Every placed flora has an anchor, the bottom left cell of its footprint. The grid stores the
+//! flora in all cells of the footprint (so checking whether a cell is free stays trivial), the
+//! `anchors` of the `MapData` point the other cells back to their anchor. Everything that works on
+//! whole plants (growth, saving, spawning the sprites, ...) goes through the anchor. Structures
+//! use the same anchors, see `structures`.
+
+use bevy::prelude::*;
+
+use crate::world::TILE_SIZE;
+
+use super::{cell_to_indices, Flora, MapData, EMPTY_CELL_VALUE, STRUCTURE_CELL_VALUE};
+
+#[cfg(test)]
+use super::{FloraRegistry, MAP_SIZE, TALL_GRASS_CELL_VALUE};
+
+/// The cells covered by an object of the given size anchored at the given cell.
+fn footprint(x: usize, y: usize, size: (usize, usize)) -> impl Iterator<Item = (usize, usize)> {
+    let (x_size, y_size) = size;
+    (x..x + x_size).flat_map(move |x| (y..y + y_size).map(move |y| (x, y)))
+}
+
+/// Offset from the anchor cell to the center of a footprint of the given size.
+pub fn size_offset(size: (usize, usize)) -> Vec2 {
+    let (x, y) = size;
+
+    debug_assert!(x > 0);
+    debug_assert!(y > 0);
+
+    0.5 * TILE_SIZE * Vec2::new((x - 1) as f32, (y - 1) as f32)
+}
+
+impl MapData {
+    /// The anchor of the flora (or structure) that covers the given cell.
+    pub fn anchor_of(&self, x: usize, y: usize) -> Option<(usize, usize)> {
+        if self.flora_at(x, y).is_none() && self.grid_index(x, y) != Some(STRUCTURE_CELL_VALUE) {
+            return None;
+        }
+        Some(self.anchors.get(&(x, y)).copied().unwrap_or((x, y)))
+    }
+
+    /// All placed flora with their anchors, every plant shows up exactly once.
+    pub fn placed_flora(&self) -> impl Iterator<Item = ((usize, usize), Flora)> + '_ {
+        self.grid
+            .occupied()
+            .filter_map(|(cell, value)| {
+                Some((
+                    cell_to_indices(cell)?,
+                    self.flora_registry.by_index(value as usize)?,
+                ))
+            })
+            .filter(|(cell, _)| !self.anchors.contains_key(cell))
+    }
+
+    /// Fill the footprint with the value, the other cells point back to the anchor.
+    pub(super) fn fill_footprint(&mut self, x: usize, y: usize, size: (usize, usize), value: u16) {
+        for cell in footprint(x, y, size) {
+            if !self.indices_in_grid(cell.0, cell.1) {
+                continue;
+            }
+
+            self.set_grid_index(cell.0, cell.1, value);
+            if cell != (x, y) {
+                self.anchors.insert(cell, (x, y));
+            }
+        }
+    }
+
+    /// Place the flora with its anchor at the given cell, the footprint must be free.
+    pub(super) fn place_flora(&mut self, x: usize, y: usize, flora: Flora) {
+        let size = self.flora_data(flora).size_on_grid();
+        debug_assert!(
+            self.fits_at_empty_or_grass_position(x, y, size.0, size.1),
+            "flora: {}, (x, y): {}, {}, grid: {:?}",
+            self.flora_registry.id(flora),
+            x,
+            y,
+            self.grid_index(x, y)
+        );
+
+        self.fill_footprint(x, y, size, flora.cell_value());
+    }
+
+    /// Clear all cells of the flora that covers the given cell. Returns the anchor and the flora.
+    ///
+    /// The growth age is kept around, it's up to the caller what should happen with the crop.
+    pub(super) fn clear_flora(&mut self, x: usize, y: usize) -> Option<((usize, usize), Flora)> {
+        let flora = self.flora_at(x, y)?;
+        let anchor = self.anchor_of(x, y)?;
+        let size = self.flora_data(flora).size_on_grid();
+
+        for cell in footprint(anchor.0, anchor.1, size) {
+            if self.anchor_of(cell.0, cell.1) != Some(anchor) {
+                continue;
+            }
+
+            self.set_grid_index(cell.0, cell.1, EMPTY_CELL_VALUE);
+            self.anchors.remove(&cell);
+        }
+        Some((anchor, flora))
+    }
+}
+
+#[cfg(test)]
+fn map_data_with_big_flora() -> MapData {
+    let registry = FloraRegistry::from_json(
+        br#"[
+            {
+                "id": "corn", "name": "Corn", "sprite": "map/corn.png",
+                "cost": {"model": "exponential", "base": 1, "growth": 1.1},
+                "pps": 1, "ysort": -300, "size_on_grid": [1, 1]
+            },
+            {
+                "id": "oak", "name": "Oak", "sprite": "map/oak.png",
+                "cost": {"model": "exponential", "base": 1, "growth": 1.1},
+                "pps": 1, "ysort": -300, "size_on_grid": [2, 3]
+            }
+        ]"#,
+    )
+    .unwrap();
+    MapData::with_flora_registry(registry)
+}
+
+#[test]
+fn validate_multi_tile_flora_placement() {
+    let mut map_data = map_data_with_big_flora();
+    let corn = map_data.flora_registry().get("corn").unwrap();
+    let oak = map_data.flora_registry().get("oak").unwrap();
+
+    assert!(map_data.fits_at_empty_or_grass_position(4, 4, 2, 3));
+    // The footprint would stick out of the grid.
+    assert!(!map_data.fits_at_empty_or_grass_position(MAP_SIZE - 1, 4, 2, 3));
+
+    map_data.place_flora(4, 4, oak);
+    map_data.place_flora(6, 5, corn);
+    for (x, y) in footprint(4, 4, (2, 3)) {
+        assert_eq!(map_data.flora_at(x, y), Some(oak));
+        assert_eq!(map_data.anchor_of(x, y), Some((4, 4)));
+    }
+    assert_eq!(map_data.anchor_of(6, 5), Some((6, 5)));
+    assert_eq!(map_data.anchor_of(6, 4), None);
+    assert!(!map_data.fits_at_empty_or_grass_position(3, 3, 2, 3));
+    assert_eq!(
+        map_data.placed_flora().collect::<Vec<_>>(),
+        vec![((4, 4), oak), ((6, 5), corn)]
+    );
+
+    // A 1x2 plant at (6, 4) touches two cells of the oak, it's still only a single neighbour.
+    map_data.place_flora(7, 5, corn);
+    assert_eq!(map_data.neighbour_floras(6, 4, (1, 2)), vec![oak, corn]);
+
+    assert_eq!(map_data.clear_flora(5, 6), Some(((4, 4), oak)));
+    for (x, y) in footprint(4, 4, (2, 3)) {
+        assert_eq!(map_data.grid_index(x, y), Some(EMPTY_CELL_VALUE));
+    }
+    assert!(map_data.anchors.is_empty());
+    assert_eq!(map_data.grid_index(4, 7), Some(TALL_GRASS_CELL_VALUE));
+}
+
+#[test]
+fn validate_multi_tile_flora_round_trip() {
+    let mut map_data = map_data_with_big_flora();
+    let corn = map_data.flora_registry().get("corn").unwrap();
+    let oak = map_data.flora_registry().get("oak").unwrap();
+    map_data.place_flora(2, 3, oak);
+    map_data.place_flora(4, 3, corn);
+    map_data.growth_ages.insert((2, 3), 7);
+
+    let raw = map_data.to_string();
+    assert!(raw.contains("2,3:oak:7;"));
+    // Only the anchor is stored.
+    assert!(!raw.contains(";2,4:"));
+    assert!(!raw.contains(";3,3:"));
+
+    let loaded = MapData::from_str(&raw, map_data.flora_registry().clone()).unwrap();
+    assert_eq!(loaded.grid, map_data.grid);
+    assert_eq!(loaded.anchors, map_data.anchors);
+    assert_eq!(loaded.growth_ages, map_data.growth_ages);
+    assert_eq!(loaded.to_string(), raw);
+
+    // Saves used to store every cell of the footprint.
+    let legacy = raw.replace(
+        "2,3:oak:7;",
+        "2,3:oak:7;2,4:oak;2,5:oak;3,3:oak;3,4:oak;3,5:oak;",
+    );
+    let loaded = MapData::from_str(&legacy, map_data.flora_registry().clone()).unwrap();
+    assert_eq!(loaded.grid, map_data.grid);
+    assert_eq!(loaded.anchors, map_data.anchors);
+
+    // Overlapping flora and footprints that stick out of the grid are malformed.
+    assert!(MapData::from_str("#2,3:oak;3,4:corn#", map_data.flora_registry().clone()).is_err());
+    assert!(MapData::from_str(
+        "#2,3:oak;2,5:tall_grass#",
+        map_data.flora_registry().clone()
+    )
+    .is_err());
+    let edge = format!("#{},0:oak#", MAP_SIZE - 1);
+    assert!(MapData::from_str(&edge, map_data.flora_registry().clone()).is_err());
+}
@@ -1,3 +1,5 @@
+use std::collections::HashSet;
+
 use bevy::{
     color::palettes::css::RED,
     prelude::*,
@@ -8,7 +10,7 @@ use bevy::{
     sprite::{AlphaMode2d, Material2d, Material2dPlugin},
     text::FontSmoothing,
 };
-use rand::{thread_rng, Rng};
+use rand::Rng;
 
 use crate::{
     assets::{GRASS_SHADER, HALF_HEIGHT_GRASS_TIMESTAMPS_IMAGE},
@@ -16,7 +18,6 @@ use crate::{
     ui::{MenuAction, MenuActionEvent},
     world::{
         collisions::{IntersectionEvent, StaticSensorAABB, GRASS_COLLISION_GROUPS},
-        utils::format_money_string_raw,
         DynamicCollider, Velocity, YSort, ZLevel, SLASH_COLLISION_GROUPS, TILE_SIZE,
     },
     BachelorBuild, EffectAssets, GameState,
@@ -25,15 +26,15 @@ use crate::{
 use crate::GameAssets;
 
 use super::{
-    flora::InitialFloraSpawned, ItemBought, MapData, ProgressionCore, ProgressionSystemSet,
-    MAP_SIZE, TALL_GRASS_CELL_VALUE,
+    cell_to_indices, flora::InitialFloraSpawned, BuildingSystemSet, Daylight, Daylit, ItemBought,
+    MapData, PrestigeAction, PrestigeActionEvent, ProgressionCore, ProgressionSystemSet, RngStream,
+    WorldRng, MAP_SIZE, TALL_GRASS_CELL_VALUE,
 };
 
 // Should match the exp damp time scale used in the grass shader.
 // The sine time will only be reset when the exp damp is at zero,
 // in other words when the grass is not moving through player shake.
 const TIME_TILL_SINE_RESET: f32 = 1.5;
-const OFFLINE_PROGRESSION_NUMBER_POP_UP_OFFSET: Vec2 = Vec2::new(0.0, 20.0);
 const HALFED_TILE_SIZE: f32 = TILE_SIZE * 0.5;
 
 const QUAD_MAX_SHIFT_OFFSET: f32 = 3.0;
@@ -51,9 +52,26 @@ const SUB_QUAD_OFFSETS: [Vec2; 4] = [
 ];
 
 #[derive(Component)]
-struct TallGrass;
+struct TallGrass {
+    cell: IVec2,
+    index: u32,
+}
+/// Hands out the indices of the blades of grass (see `spawn_tall_grass`), the indices of
+/// despawned blades are used again so that regrowing grass doesn't run out of them.
+#[derive(Resource, Default)]
+struct GrassIndices {
+    next: u32,
+    free: Vec<u32>,
+}
+/// The cells that have their blades of grass and the stamp of the last synced change, see
+/// `sync_grass`.
+#[derive(Resource, Default)]
+struct GrassCells {
+    stamp: u64,
+    cells: HashSet<IVec2>,
+}
 #[derive(Component)]
-struct NumberPopUp {
+pub(super) struct NumberPopUp {
     move_speed: f32,
     timer: Timer,
 }
@@ -66,6 +84,9 @@ pub struct CutTallGrass {
 
 #[derive(Asset, TypePath, AsBindGroup, Debug, Clone)]
 pub struct GrassMaterial {
+    /// The linear color of the `Daylight` tint.
+    #[uniform(0)]
+    pub tint: Vec4,
     #[texture(1)]
     #[sampler(2)]
     pub texture: Option<Handle<Image>>,
@@ -98,6 +119,19 @@ impl NumberPopUp {
     }
 }
 
+impl GrassIndices {
+    fn take(&mut self) -> u32 {
+        self.free.pop().unwrap_or_else(|| {
+            self.next += 1;
+            self.next - 1
+        })
+    }
+
+    fn release(&mut self, index: u32) {
+        self.free.push(index);
+    }
+}
+
 impl Material2d for GrassMaterial {
     fn vertex_shader() -> ShaderRef {
         GRASS_SHADER.into()
@@ -118,6 +152,7 @@ fn spawn_tall_grass(
     effects: &EffectAssets,
     images: &Assets<Image>,
     pos: Vec2,
+    cell: IVec2,
     index: u32,
 ) {
     let image_handle = assets.grass.clone();
@@ -136,7 +171,7 @@ fn spawn_tall_grass(
     let image_size = Vec2::new(image.width() as f32, image.height() as f32);
 
     commands.spawn((
-        TallGrass,
+        TallGrass { cell, index },
         YSort(0.0),
         Transform::from_translation(pos.extend(0.0)).with_scale(image_size.extend(1.0)),
         Mesh2d(effects.rect_mesh.clone()),
@@ -147,73 +182,139 @@ fn spawn_tall_grass(
     ));
 }
 
-fn spawn_grass(
+/// Grows the blades of grass on the given cell, the same ones for the same world seed.
+fn spawn_grass_at_cell(
+    commands: &mut Commands,
+    assets: &GameAssets,
+    effects: &EffectAssets,
+    images: &Assets<Image>,
+    world_rng: &WorldRng,
+    center_pos: Vec2,
+    cell: IVec2,
+    grass_indices: &mut GrassIndices,
+) {
+    let mut rng = world_rng.for_cell(RngStream::Grass, cell);
+    let mut threshold = 0.35;
+    for offset in QUAD_OFFSETS {
+        for sub_offset in SUB_QUAD_OFFSETS {
+            let threshold_check = rng.random_range(0.0..1.0);
+
+            if threshold_check > threshold {
+                threshold += 0.3;
+                continue;
+            }
+
+            let random_shift = Vec2::new(rng.random_range(-1.0..1.0), rng.random_range(-1.0..1.0));
+            let pos = center_pos + offset + sub_offset + random_shift * QUAD_MAX_SHIFT_OFFSET;
+            spawn_tall_grass(
+                commands,
+                assets,
+                effects,
+                images,
+                pos,
+                cell,
+                grass_indices.take(),
+            );
+        }
+    }
+}
+
+/// Grow the grass on the owned tall grass cells of all chunks that changed since the last sync.
+/// Blades that were left standing on a cell that regrows are replaced.
+fn sync_grass(
     mut commands: Commands,
     assets: Res<GameAssets>,
     effects: Res<EffectAssets>,
     images: Res<Assets<Image>>,
     map_data: Res<MapData>,
+    world_rng: Res<WorldRng>,
+    mut grass_indices: ResMut<GrassIndices>,
+    mut grass_cells: ResMut<GrassCells>,
+    q_grass: Query<(Entity, &TallGrass)>,
 ) {
-    let size = (MAP_SIZE / 2) as i32;
-    let mut rng = thread_rng();
-    let mut index = 0;
-
-    for i in -size..size {
-        for j in -size..size {
-            let center_pos = Vec2::new(i as f32 * TILE_SIZE, j as f32 * TILE_SIZE);
-
-            let (x, y) = map_data.pos_to_grid_indices(center_pos);
-            if map_data.grid_index(x, y) != TALL_GRASS_CELL_VALUE {
+    let mut grown = HashSet::new();
+    let stamp = map_data.grid.latest_stamp();
+    for chunk in map_data.grid.changed_since(grass_cells.stamp) {
+        for (cell, value) in map_data.grid.cells_in_chunk(chunk) {
+            let owned = cell_to_indices(cell).is_some_and(|(x, y)| map_data.owns_cell(x, y));
+            if !owned || value != TALL_GRASS_CELL_VALUE {
+                grass_cells.cells.remove(&cell);
                 continue;
             }
-
-            let mut threshold = 0.35;
-            for offset in QUAD_OFFSETS {
-                for sub_offset in SUB_QUAD_OFFSETS {
-                    let threshold_check = rng.gen_range(0.0..1.0);
-
-                    if threshold_check > threshold {
-                        threshold += 0.3;
-                        continue;
-                    }
-
-                    let random_shift =
-                        Vec2::new(rng.gen_range(-1.0..1.0), rng.gen_range(-1.0..1.0));
-                    let pos =
-                        center_pos + offset + sub_offset + random_shift * QUAD_MAX_SHIFT_OFFSET;
-                    spawn_tall_grass(&mut commands, &assets, &effects, &images, pos, index);
-                    index += 1;
-                }
+            if grass_cells.cells.insert(cell) {
+                grown.insert(cell);
             }
         }
     }
+    grass_cells.stamp = stamp;
+
+    if grown.is_empty() {
+        return;
+    }
+
+    for (entity, grass) in &q_grass {
+        if grown.contains(&grass.cell) {
+            grass_indices.release(grass.index);
+            commands.entity(entity).despawn();
+        }
+    }
+    for cell in grown {
+        spawn_grass_at_cell(
+            &mut commands,
+            &assets,
+            &effects,
+            &images,
+            &world_rng,
+            map_data.cell_to_pos(cell),
+            cell,
+            &mut grass_indices,
+        );
+    }
 }
 
-fn respawn_grass_on_reset(
+fn despawn_grass_on_reset(
     mut commands: Commands,
-    assets: Res<GameAssets>,
-    effects: Res<EffectAssets>,
-    images: Res<Assets<Image>>,
-    map_data: Res<MapData>,
+    mut grass_indices: ResMut<GrassIndices>,
+    mut grass_cells: ResMut<GrassCells>,
     q_grass: Query<Entity, With<TallGrass>>,
     mut ev_menu_action: EventReader<MenuActionEvent>,
+    mut ev_prestige_action: EventReader<PrestigeActionEvent>,
 ) {
-    if !ev_menu_action
+    let reset = ev_menu_action
         .read()
-        .any(|ev| ev.action == MenuAction::Reset)
-    {
+        .any(|ev| ev.action == MenuAction::Reset);
+    let rebirth = ev_prestige_action
+        .read()
+        .any(|ev| ev.action == PrestigeAction::Rebirth);
+    if !reset && !rebirth {
         return;
     }
 
     for entity in &q_grass {
         commands.entity(entity).despawn();
     }
-
-    spawn_grass(commands, assets, effects, images, map_data);
+    // The fresh map grows all of its grass on the next sync.
+    *grass_indices = GrassIndices::default();
+    *grass_cells = GrassCells::default();
 }
 
-fn despawn_tall_grass(mut commands: Commands, mut ev_cut_tall_grass: EventReader<CutTallGrass>) {
+fn despawn_tall_grass(
+    mut commands: Commands,
+    mut grass_indices: ResMut<GrassIndices>,
+    q_grass: Query<&TallGrass>,
+    mut ev_cut_tall_grass: EventReader<CutTallGrass>,
+) {
+    let mut cut = HashSet::new();
     for ev in ev_cut_tall_grass.read() {
+        // The same blade can be cut by more than one slash in a frame.
+        if !cut.insert(ev.entity) {
+            continue;
+        }
+        let Ok(grass) = q_grass.get(ev.entity) else {
+            continue;
+        };
+
+        grass_indices.release(grass.index);
         commands.entity(ev.entity).despawn();
     }
 }
@@ -246,7 +347,7 @@ fn trigger_cut_tall_grass_event(
     }
 }
 
-fn spawn_number_pop_up(
+pub(super) fn spawn_number_pop_up(
     commands: &mut Commands,
     assets: &GameAssets,
     pos: Vec2,
@@ -271,35 +372,6 @@ fn spawn_number_pop_up(
     ));
 }
 
-/// We spawn the offline progress number pop up in here because it's convenient.
-/// It's not clean at all, but I don't care, it's easy to do right now.
-fn spawn_offline_progress_number_pop_up(
-    mut commands: Commands,
-    assets: Res<GameAssets>,
-    mut core: ResMut<ProgressionCore>,
-    q_player: Query<&Transform, With<Player>>,
-) {
-    if core.offline_progression == 0 {
-        return;
-    }
-
-    let Ok(player_transform) = q_player.single() else {
-        return;
-    };
-
-    spawn_number_pop_up(
-        &mut commands,
-        &assets,
-        player_transform.translation.xy() + OFFLINE_PROGRESSION_NUMBER_POP_UP_OFFSET,
-        "+".to_string() + &format_money_string_raw(core.offline_progression),
-        Color::WHITE.with_alpha(1.0),
-        NumberPopUp::default(),
-        80.0,
-        0.0,
-    );
-    core.offline_progression = 0;
-}
-
 /// We spawn the item bought cost number pop up in here because it's convenient.
 /// It's not clean at all, but I don't care, it's easy to do right now.
 /// Same reason as for the offline progress.
@@ -425,10 +497,22 @@ fn set_grass_timestamps(
     materials.get_mut(&effects.grass_material);
 }
 
+fn tint_grass_material(
+    daylight: Res<Daylight>,
+    effects: Res<EffectAssets>,
+    mut materials: ResMut<Assets<GrassMaterial>>,
+) {
+    let Some(material) = materials.get_mut(&effects.grass_material) else {
+        return;
+    };
+    material.tint = LinearRgba::from(daylight.tint).to_vec4();
+}
+
 fn spawn_background_grass_tile(commands: &mut Commands, assets: &GameAssets, pos: Vec2) {
     commands.spawn((
         Transform::from_translation(pos.extend(ZLevel::Background.value())),
         Sprite::from_image(assets.grass_background_tile.clone()),
+        Daylit,
     ));
 }
 
@@ -447,6 +531,8 @@ pub struct MapGrassPlugin;
 impl Plugin for MapGrassPlugin {
     fn build(&self, app: &mut App) {
         app.add_plugins(Material2dPlugin::<GrassMaterial>::default())
+            .init_resource::<GrassIndices>()
+            .init_resource::<GrassCells>()
             .add_event::<CutTallGrass>()
             .add_systems(
                 OnExit(GameState::AssetLoading),
@@ -455,9 +541,16 @@ impl Plugin for MapGrassPlugin {
             .add_systems(
                 Update,
                 (
-                    spawn_grass.run_if(resource_exists::<InitialFloraSpawned>.and(run_once)),
-                    respawn_grass_on_reset.after(ProgressionSystemSet),
+                    despawn_grass_on_reset,
+                    sync_grass.run_if(
+                        resource_exists::<WorldRng>
+                            .and(resource_exists::<InitialFloraSpawned>)
+                            .and(resource_exists_and_changed::<MapData>),
+                    ),
                 )
+                    .chain()
+                    .after(ProgressionSystemSet)
+                    .after(BuildingSystemSet)
                     .run_if(
                         resource_exists::<GameAssets>
                             .and(resource_exists::<EffectAssets>)
@@ -469,9 +562,6 @@ impl Plugin for MapGrassPlugin {
                 (
                     trigger_cut_tall_grass_event,
                     despawn_tall_grass,
-                    spawn_offline_progress_number_pop_up.run_if(
-                        in_state(GameState::Gaming).and(resource_exists::<ProgressionCore>),
-                    ),
                     spawn_item_cost_number_pop_up_on_item_bought.run_if(
                         resource_exists::<GameAssets>
                             .and(resource_exists::<ProgressionCore>)
@@ -483,6 +573,11 @@ impl Plugin for MapGrassPlugin {
                     .chain()
                     .before(ProgressionSystemSet),
             )
+            .add_systems(
+                Update,
+                tint_grass_material
+                    .run_if(resource_exists::<EffectAssets>.and(resource_changed::<Daylight>)),
+            )
             .add_systems(
                 PostUpdate,
                 set_grass_timestamps.run_if(resource_exists::<EffectAssets>),
@@ -0,0 +1,295 @@
+//! Crop growth.
+//!
+//! Flora with a `growth` section in `flora.json` grows through a couple of stages over time. Once
+//! ripe it can be harvested with the scythe, which yields a burst of points and starts the cycle
+//! from the beginning. Growing crops still produce their normal pps, the harvest is on top.
+//!
+//! The age of every growing crop is stored per cell in the `MapData`, so it survives saving and
+//! keeps growing while the game is closed.
+
+use std::time::Duration;
+
+use bevy::{color::palettes::css::LIME, prelude::*, time::common_conditions::on_timer};
+use serde::Deserialize;
+
+use crate::{
+    world::{
+        collisions::{IntersectionEvent, CROP_COLLISION_GROUPS},
+        utils::format_money_string_raw,
+        BigNumber, SLASH_COLLISION_GROUPS,
+    },
+    GameAssets,
+};
+
+use super::{
+    compute_flora_pps, grass, indices_to_cell, Flora, MapData, ModifierTarget, ProgressionCore,
+    ProgressionSystemSet,
+};
+
+const STAGE_SCALES: [f32; 4] = [0.4, 0.6, 0.8, 1.0];
+
+#[derive(Deserialize, Clone, Copy, Debug)]
+pub struct GrowthData {
+    /// Time it takes to grow from one stage to the next.
+    stage_seconds: u64,
+    /// The harvest yields the pps of the crop for this many seconds.
+    harvest_seconds: u64,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum GrowthStage {
+    Seed,
+    Sprout,
+    Mature,
+    Ripe,
+}
+
+/// Marks the root entity of a flora that grows.
+#[derive(Component)]
+pub struct Crop {
+    pub pos: Vec2,
+}
+
+#[derive(Event)]
+struct HarvestCrop {
+    pos: Vec2,
+}
+
+#[derive(Event)]
+struct CropHarvested {
+    pos: Vec2,
+    points: BigNumber,
+}
+
+impl GrowthData {
+    fn ripe_age(self) -> u64 {
+        3 * self.stage_seconds
+    }
+
+    fn stage(self, age: u64) -> GrowthStage {
+        match age / self.stage_seconds.max(1) {
+            0 => GrowthStage::Seed,
+            1 => GrowthStage::Sprout,
+            2 => GrowthStage::Mature,
+            _ => GrowthStage::Ripe,
+        }
+    }
+}
+
+impl GrowthStage {
+    fn scale(self) -> f32 {
+        STAGE_SCALES[self as usize]
+    }
+}
+
+impl MapData {
+    fn growth_at(&self, x: usize, y: usize) -> Option<GrowthData> {
+        let flora = self.flora_at(x, y)?;
+        self.flora_data(flora).growth
+    }
+
+    /// `None` if there is no growing flora at the given cell.
+    pub fn growth_stage(&self, x: usize, y: usize) -> Option<GrowthStage> {
+        let growth = self.growth_at(x, y)?;
+        let anchor = self.anchor_of(x, y)?;
+        let age = self.growth_ages.get(&anchor).copied().unwrap_or_default();
+        Some(growth.stage(age))
+    }
+
+    /// Let all crops grow for the given time, crops stop aging once they are ripe.
+    pub fn advance_growth(&mut self, seconds: u64) {
+        let crops: Vec<_> = self
+            .placed_flora()
+            .filter_map(|(anchor, flora)| Some((anchor, self.flora_data(flora).growth?)))
+            .collect();
+
+        for (anchor, growth) in crops {
+            let age = self.growth_ages.entry(anchor).or_default();
+            let new_age = (*age + seconds).min(growth.ripe_age());
+            if new_age != *age {
+                *age = new_age;
+                self.grid.touch(indices_to_cell(anchor.0, anchor.1));
+            }
+        }
+    }
+
+    /// Returns the harvested flora if the crop at the given cell was ripe.
+    fn harvest(&mut self, x: usize, y: usize) -> Option<Flora> {
+        if self.growth_stage(x, y)? != GrowthStage::Ripe {
+            return None;
+        }
+
+        let anchor = self.anchor_of(x, y)?;
+        self.growth_ages.remove(&anchor);
+        self.grid.touch(indices_to_cell(anchor.0, anchor.1));
+        self.flora_at(x, y)
+    }
+}
+
+fn advance_crop_growth(mut map_data: ResMut<MapData>) {
+    map_data.advance_growth(1);
+}
+
+fn trigger_harvest_crop_event(
+    q_transforms: Query<&Transform>,
+    mut ev_intersection: EventReader<IntersectionEvent>,
+    mut ev_harvest_crop: EventWriter<HarvestCrop>,
+) {
+    for ev in ev_intersection.read() {
+        let (entity, other_group) = if ev.collision_groups.0 == CROP_COLLISION_GROUPS {
+            (ev.entities.0, ev.collision_groups.1)
+        } else if ev.collision_groups.1 == CROP_COLLISION_GROUPS {
+            (ev.entities.1, ev.collision_groups.0)
+        } else {
+            continue;
+        };
+
+        if other_group != SLASH_COLLISION_GROUPS {
+            continue;
+        }
+
+        let Ok(transform) = q_transforms.get(entity) else {
+            continue;
+        };
+        ev_harvest_crop.write(HarvestCrop {
+            pos: transform.translation.xy(),
+        });
+    }
+}
+
+fn harvest_crops(
+    mut core: ResMut<ProgressionCore>,
+    mut map_data: ResMut<MapData>,
+    mut ev_harvest_crop: EventReader<HarvestCrop>,
+    mut ev_crop_harvested: EventWriter<CropHarvested>,
+) {
+    for ev in ev_harvest_crop.read() {
+        let Some((x, y)) = map_data.pos_to_grid_indices(ev.pos) else {
+            continue;
+        };
+        let Some(flora) = map_data.harvest(x, y) else {
+            continue;
+        };
+        let Some(growth) = map_data.flora_data(flora).growth else {
+            continue;
+        };
+
+        let pps = compute_flora_pps(&core, &map_data, flora) * growth.harvest_seconds.into();
+        let points = core.apply_modifiers(&map_data, ModifierTarget::Pps, pps);
+        let points = core.add_points(points);
+        core.statistics.add_points_of_flora(flora, points);
+        ev_crop_harvested.write(CropHarvested {
+            pos: ev.pos,
+            points,
+        });
+    }
+}
+
+fn spawn_harvest_number_pop_ups(
+    mut commands: Commands,
+    assets: Res<GameAssets>,
+    mut ev_crop_harvested: EventReader<CropHarvested>,
+) {
+    for ev in ev_crop_harvested.read() {
+        grass::spawn_number_pop_up(
+            &mut commands,
+            &assets,
+            ev.pos,
+            "+".to_string() + &format_money_string_raw(ev.points),
+            LIME.into(),
+            grass::NumberPopUp::default(),
+            80.0,
+            0.0,
+        );
+    }
+}
+
+fn update_crop_visuals(
+    map_data: Res<MapData>,
+    q_crops: Query<(&Crop, &Children)>,
+    mut q_sprites: Query<&mut Transform, With<Sprite>>,
+) {
+    for (crop, children) in &q_crops {
+        let Some(stage) = map_data
+            .pos_to_grid_indices(crop.pos)
+            .and_then(|(x, y)| map_data.growth_stage(x, y))
+        else {
+            continue;
+        };
+
+        for child in children {
+            let Ok(mut transform) = q_sprites.get_mut(*child) else {
+                continue;
+            };
+            transform.scale = Vec3::splat(stage.scale());
+        }
+    }
+}
+
+pub struct MapGrowthPlugin;
+
+impl Plugin for MapGrowthPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<HarvestCrop>()
+            .add_event::<CropHarvested>()
+            .add_systems(
+                Update,
+                (
+                    advance_crop_growth.run_if(on_timer(Duration::from_secs(1))),
+                    harvest_crops,
+                )
+                    .chain()
+                    .in_set(ProgressionSystemSet)
+                    .run_if(resource_exists::<ProgressionCore>.and(resource_exists::<MapData>)),
+            )
+            .add_systems(
+                Update,
+                (
+                    trigger_harvest_crop_event.before(ProgressionSystemSet),
+                    spawn_harvest_number_pop_ups
+                        .after(ProgressionSystemSet)
+                        .run_if(resource_exists::<GameAssets>),
+                    update_crop_visuals.run_if(resource_exists::<MapData>),
+                ),
+            );
+    }
+}
+
+#[test]
+fn validate_growth_stages() {
+    let growth = GrowthData {
+        stage_seconds: 10,
+        harvest_seconds: 60,
+    };
+
+    assert_eq!(growth.stage(0), GrowthStage::Seed);
+    assert_eq!(growth.stage(9), GrowthStage::Seed);
+    assert_eq!(growth.stage(10), GrowthStage::Sprout);
+    assert_eq!(growth.stage(25), GrowthStage::Mature);
+    assert_eq!(growth.stage(30), GrowthStage::Ripe);
+    assert_eq!(growth.stage(u64::MAX), GrowthStage::Ripe);
+}
+
+#[test]
+fn validate_growth_and_harvest_cycle() {
+    let mut map_data = MapData::default();
+    let (flora, growth) = map_data
+        .flora_registry()
+        .iter()
+        .find_map(|flora| Some((flora, map_data.flora_data(flora).growth?)))
+        .expect("at least one flora must grow");
+
+    map_data.set_grid_index(4, 2, flora.cell_value());
+    assert_eq!(map_data.growth_stage(4, 2), Some(GrowthStage::Seed));
+    assert_eq!(map_data.growth_stage(5, 2), None);
+    assert_eq!(map_data.harvest(4, 2), None);
+
+    // Offline progression can skip way past the ripe age.
+    map_data.advance_growth(growth.ripe_age() * 100);
+    assert_eq!(map_data.growth_ages[&(4, 2)], growth.ripe_age());
+    assert_eq!(map_data.growth_stage(4, 2), Some(GrowthStage::Ripe));
+
+    assert_eq!(map_data.harvest(4, 2), Some(flora));
+    assert_eq!(map_data.growth_stage(4, 2), Some(GrowthStage::Seed));
+    assert_eq!(map_data.harvest(4, 2), None);
+}
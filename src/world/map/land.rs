@@ -0,0 +1,340 @@
+//! Buying land.
+//!
+//! The grid covers the biggest garden there can ever be, but only the plots that are owned can be
+//! used. A new garden starts with the plots in the middle, every plot that borders the owned land
+//! can be bought with points. The border, the dark overlay and the camera bounds follow the owned
+//! land whenever it changes.
+
+use std::{collections::BTreeSet, ops::Range};
+
+use bevy::{color::palettes::css::RED, math::bounding::Aabb2d, prelude::*, text::FontSmoothing};
+
+use crate::{
+    player::{GamingInput, Player},
+    world::{utils::format_money_string, BigNumber, TILE_SIZE},
+    BachelorBuild, GameAssets,
+};
+
+use super::{
+    building::{spawn_cursor_selector, CursorTool},
+    cost::CostCurve,
+    indices_to_cell, BuildingSystemSet, MapData, ProgressionCore, ProgressionSystemSet, ZLevel,
+    MAP_SIZE,
+};
+
+/// The width and height of a plot in cells.
+pub const PLOT_SIZE: usize = 5;
+pub const PLOTS_PER_SIDE: usize = MAP_SIZE / PLOT_SIZE;
+/// The plots (on both axes) that a new garden starts with.
+const STARTING_PLOTS: Range<usize> = 4..8;
+
+const PLOT_COST_TEXT_OFFSET: Vec3 = Vec3::new(0.0, 24.0, 1.0);
+
+#[derive(Component, Default)]
+struct LandTool {
+    /// The plot under the cursor, `None` off the map.
+    plot: Option<(usize, usize)>,
+}
+
+#[derive(Component)]
+struct PlotCostText;
+
+/// The plots of a square garden, e.g. the ones a new garden starts with.
+pub(super) fn square_plots(range: Range<usize>) -> BTreeSet<(usize, usize)> {
+    range
+        .clone()
+        .flat_map(|x| range.clone().map(move |y| (x, y)))
+        .collect()
+}
+
+pub(super) fn starting_plots() -> BTreeSet<(usize, usize)> {
+    square_plots(STARTING_PLOTS)
+}
+
+/// The cells of the given plot.
+pub(super) fn plot_cells(plot: (usize, usize)) -> impl Iterator<Item = (usize, usize)> {
+    let (x, y) = (plot.0 * PLOT_SIZE, plot.1 * PLOT_SIZE);
+    (x..x + PLOT_SIZE).flat_map(move |x| (y..y + PLOT_SIZE).map(move |y| (x, y)))
+}
+
+fn plot_cost_curve() -> CostCurve {
+    CostCurve::Polynomial {
+        base: 400u64.into(),
+        factor: 150.0,
+        power: 2.0,
+    }
+}
+
+impl MapData {
+    pub fn plot_at(&self, x: usize, y: usize) -> (usize, usize) {
+        (x / PLOT_SIZE, y / PLOT_SIZE)
+    }
+
+    pub fn owns_plot(&self, plot: (usize, usize)) -> bool {
+        self.plots.contains(&plot)
+    }
+
+    /// Whether the cell is part of the owned land, only those can be built on.
+    pub fn owns_cell(&self, x: usize, y: usize) -> bool {
+        self.indices_in_grid(x, y) && self.owns_plot(self.plot_at(x, y))
+    }
+
+    /// Only plots that border the owned land can be bought.
+    pub fn can_buy_plot(&self, plot: (usize, usize)) -> bool {
+        let (x, y) = plot;
+        if x >= PLOTS_PER_SIDE || y >= PLOTS_PER_SIDE || self.owns_plot(plot) {
+            return false;
+        }
+
+        [
+            (x.checked_sub(1), Some(y)),
+            (Some(x + 1), Some(y)),
+            (Some(x), y.checked_sub(1)),
+            (Some(x), Some(y + 1)),
+        ]
+        .into_iter()
+        .any(|(nx, ny)| nx.zip(ny).is_some_and(|plot| self.owns_plot(plot)))
+    }
+
+    /// How many plots were bought on top of the ones the garden started with.
+    fn bought_plots(&self) -> usize {
+        self.plots.len().saturating_sub(starting_plots().len())
+    }
+
+    /// The world space rectangle covering all of the owned land.
+    pub fn land_bounds(&self) -> Aabb2d {
+        let (mut min, mut max) = ((usize::MAX, usize::MAX), (0, 0));
+        for &(x, y) in &self.plots {
+            min = (min.0.min(x), min.1.min(y));
+            max = (max.0.max(x + 1), max.1.max(y + 1));
+        }
+        if self.plots.is_empty() {
+            min = (0, 0);
+        }
+
+        let half_tile = Vec2::splat(0.5 * TILE_SIZE);
+        Aabb2d {
+            min: self.grid_indices_to_pos(min.0 * PLOT_SIZE, min.1 * PLOT_SIZE) - half_tile,
+            max: self.grid_indices_to_pos(max.0 * PLOT_SIZE, max.1 * PLOT_SIZE) - half_tile,
+        }
+    }
+}
+
+impl ProgressionCore {
+    /// The cost of the next plot, it only depends on how many were bought already.
+    pub fn plot_cost(&self, map_data: &MapData) -> BigNumber {
+        plot_cost_curve().cost(map_data.bought_plots() as u32)
+    }
+
+    /// Returns whether the plot was bought.
+    fn buy_plot(&mut self, map_data: &mut MapData, plot: (usize, usize)) -> bool {
+        let cost = self.plot_cost(map_data);
+        if !map_data.can_buy_plot(plot) || self.points < cost {
+            return false;
+        }
+
+        self.points -= cost;
+        map_data.plots.insert(plot);
+        // The grass of the new land grows on the changed chunks.
+        for (x, y) in plot_cells(plot) {
+            map_data.grid.touch(indices_to_cell(x, y));
+        }
+        true
+    }
+}
+
+fn toggle_land_tool(
+    mut commands: Commands,
+    assets: Res<GameAssets>,
+    gaming_input: Res<GamingInput>,
+    bachelor_build: Res<BachelorBuild>,
+    q_tools: Query<Entity, With<LandTool>>,
+    q_cursor_tools: Query<Entity, With<CursorTool>>,
+) {
+    if !gaming_input.buy_land || !bachelor_build.with_building {
+        return;
+    }
+
+    let was_active = !q_tools.is_empty();
+    for entity in &q_cursor_tools {
+        commands.entity(entity).despawn();
+    }
+    if was_active {
+        return;
+    }
+
+    let root = commands
+        .spawn((
+            CursorTool,
+            LandTool::default(),
+            Visibility::Inherited,
+            Transform::from_xyz(0.0, 0.0, ZLevel::TopUi.value()),
+        ))
+        .id();
+
+    spawn_cursor_selector(&mut commands, &assets, root, (PLOT_SIZE, PLOT_SIZE));
+
+    let center = 0.5 * (PLOT_SIZE - 1) as f32 * TILE_SIZE;
+    commands.spawn((
+        ChildOf(root),
+        PlotCostText,
+        Text2d::default(),
+        TextFont {
+            font: assets.pixel_font.clone(),
+            font_size: 80.0,
+            font_smoothing: FontSmoothing::None,
+            ..default()
+        },
+        TextColor(Color::WHITE),
+        Transform::from_translation(PLOT_COST_TEXT_OFFSET + Vec3::new(center, center, 0.0))
+            .with_scale(Vec3::splat(0.1)),
+    ));
+}
+
+fn move_land_tool(
+    gaming_input: Res<GamingInput>,
+    map_data: Res<MapData>,
+    mut q_tool: Query<(&mut Transform, &mut LandTool)>,
+) {
+    let Ok((mut transform, mut tool)) = q_tool.single_mut() else {
+        return;
+    };
+
+    let cell = map_data.pos_to_cell(gaming_input.mouse_world_coords);
+    tool.plot = map_data
+        .pos_to_grid_indices(gaming_input.mouse_world_coords)
+        .map(|(x, y)| map_data.plot_at(x, y));
+    let pos =
+        map_data.cell_to_pos(cell.div_euclid(IVec2::splat(PLOT_SIZE as i32)) * PLOT_SIZE as i32);
+
+    transform.translation.x = pos.x;
+    transform.translation.y = pos.y;
+}
+
+fn update_land_tool_visuals(
+    core: Res<ProgressionCore>,
+    map_data: Res<MapData>,
+    q_tool: Query<(&Children, &LandTool)>,
+    mut q_sprites: Query<&mut Sprite>,
+    mut q_texts: Query<(&mut Text2d, &mut Visibility), With<PlotCostText>>,
+) {
+    let Ok((children, tool)) = q_tool.single() else {
+        return;
+    };
+
+    let cost = core.plot_cost(&map_data);
+    let can_buy = tool.plot.is_some_and(|plot| map_data.can_buy_plot(plot));
+
+    for child in children {
+        if let Ok(mut sprite) = q_sprites.get_mut(*child) {
+            sprite.color = if can_buy && core.points >= cost {
+                Color::WHITE
+            } else {
+                RED.into()
+            };
+        }
+
+        let Ok((mut text, mut visibility)) = q_texts.get_mut(*child) else {
+            continue;
+        };
+        if can_buy {
+            *visibility = Visibility::Inherited;
+            text.0 = format!("-{}", format_money_string(cost));
+        } else {
+            *visibility = Visibility::Hidden;
+        }
+    }
+}
+
+fn buy_plot_on_confirm(
+    mut core: ResMut<ProgressionCore>,
+    mut map_data: ResMut<MapData>,
+    gaming_input: Res<GamingInput>,
+    q_player: Query<&Player>,
+    q_tool: Query<&LandTool>,
+) {
+    if !gaming_input.confirm {
+        return;
+    }
+
+    let Ok(player) = q_player.single() else {
+        return;
+    };
+    if player.is_over_ui {
+        return;
+    }
+
+    let Some(plot) = q_tool.single().ok().and_then(|tool| tool.plot) else {
+        return;
+    };
+
+    core.buy_plot(&mut map_data, plot);
+}
+
+pub struct MapLandPlugin;
+
+impl Plugin for MapLandPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                toggle_land_tool
+                    .run_if(resource_exists::<GameAssets>.and(resource_exists::<BachelorBuild>)),
+                move_land_tool.run_if(resource_exists::<MapData>),
+                buy_plot_on_confirm
+                    .run_if(resource_exists::<ProgressionCore>.and(resource_exists::<MapData>)),
+                update_land_tool_visuals
+                    .run_if(resource_exists::<ProgressionCore>.and(resource_exists::<MapData>)),
+            )
+                .chain()
+                .in_set(BuildingSystemSet)
+                .after(ProgressionSystemSet),
+        );
+    }
+}
+
+#[test]
+fn validate_buying_plots() {
+    let mut map_data = MapData::default();
+    let mut core = ProgressionCore::default();
+    core.points_cap = 1_000_000u64.into();
+    core.points = 900u64.into();
+
+    // The middle of the map is owned, the corners are not.
+    let (center_x, center_y) = map_data.pos_to_grid_indices(Vec2::ZERO).unwrap();
+    assert!(map_data.owns_cell(center_x, center_y));
+    assert!(!map_data.owns_cell(0, 0));
+    assert!(!map_data.owns_cell(MAP_SIZE, 0));
+
+    let first = STARTING_PLOTS.start;
+    let outside = (first - 1, first);
+    // Not next to the owned land.
+    assert!(!map_data.can_buy_plot((first - 2, first)));
+    assert!(!map_data.can_buy_plot((first - 1, first - 1)));
+    assert!(!map_data.can_buy_plot((first, first)));
+    assert!(!map_data.can_buy_plot((PLOTS_PER_SIDE, first)));
+    assert!(map_data.can_buy_plot(outside));
+
+    let cost = core.plot_cost(&map_data);
+    assert_eq!(cost, BigNumber::from(400u64));
+    assert!(!core.buy_plot(&mut map_data, (first - 2, first)));
+    assert!(core.buy_plot(&mut map_data, outside));
+    assert_eq!(core.points, BigNumber::from(500u64));
+    assert!(plot_cells(outside).all(|(x, y)| map_data.owns_cell(x, y)));
+    assert!(map_data.can_buy_plot((first - 2, first)));
+    assert!(core.plot_cost(&map_data) > cost);
+
+    // Too expensive.
+    assert!(!core.buy_plot(&mut map_data, (first - 2, first)));
+    assert!(!map_data.owns_plot((first - 2, first)));
+
+    let bounds = map_data.land_bounds();
+    assert_eq!(
+        bounds.max.x - bounds.min.x,
+        ((STARTING_PLOTS.len() + 1) * PLOT_SIZE) as f32 * TILE_SIZE
+    );
+    assert_eq!(
+        bounds.max.y - bounds.min.y,
+        (STARTING_PLOTS.len() * PLOT_SIZE) as f32 * TILE_SIZE
+    );
+}
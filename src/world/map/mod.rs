@@ -1,21 +1,63 @@
+mod achievements;
+mod adjacency;
 mod border;
 mod building;
+mod chunks;
 mod clouds;
+mod cost;
+mod day_night;
 mod debug;
+mod demolish;
+mod export;
 mod flora;
+mod footprint;
 mod grass;
+mod growth;
+mod land;
+mod offline;
+mod prestige;
+mod random_events;
+mod regrowth;
+mod relocate;
+mod rng;
+mod save;
+#[cfg(not(target_arch = "wasm32"))]
+mod simulation;
+mod slots;
+mod statistics;
+mod structures;
 mod telemetry;
+mod upgrades;
 
-#[cfg(not(target_arch = "wasm32"))]
-use std::fs::{self, read_to_string};
-use std::{collections::HashMap, time::Duration};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    fmt,
+    time::Duration,
+};
 
 #[cfg(target_arch = "wasm32")]
 use wasm_bindgen::prelude::*;
 
-pub use building::{Blueprint, BuildingSystemSet};
-pub use flora::Flora;
+pub use achievements::{AchievementUnlocked, Achievements};
+pub use building::{Blueprint, BlueprintItem, BuildingSystemSet, CursorTool};
+pub use day_night::{Daylight, Daylit};
+#[cfg(not(target_arch = "wasm32"))]
+pub use export::run_save_code_command;
+pub use export::SaveCodeResult;
+pub use flora::{Flora, FloraRegistry, FloraRegistryLoadError};
 pub use grass::{CutTallGrass, GrassMaterial};
+pub use offline::OfflineReport;
+pub use prestige::{
+    can_rebirth, seeds_for_run, PrestigeAction, PrestigeActionEvent, PrestigeUpgrade,
+    PRESTIGE_POINTS_THRESHOLD,
+};
+pub use random_events::RandomEventStarted;
+pub use rng::{RngStream, WorldRng};
+#[cfg(not(target_arch = "wasm32"))]
+pub use simulation::run_simulation_command;
+pub use slots::{SaveSlots, SlotAction, SlotActionEvent, MAX_SLOT_NAME_LENGTH};
+pub use statistics::Statistics;
+pub use structures::{Structure, SILO_ID};
 pub use telemetry::GameTelemetryManager;
 
 #[cfg(not(target_arch = "wasm32"))]
@@ -25,56 +67,95 @@ use bevy::{
     time::common_conditions::{on_real_timer, on_timer},
 };
 
-#[cfg(not(target_arch = "wasm32"))]
-use crate::assets::{GAME_TELEMETRY_FILE, MAP_DATA_FILE, PROGRESSION_CORE_FILE};
-#[cfg(target_arch = "wasm32")]
-use crate::assets::{WASM_KEYS, WASM_MAP_DATA_KEY_STORAGE, WASM_PROGRESSION_CORE_KEY_STORAGE};
-
+use achievements::AchievementData;
+use adjacency::AdjacencyRule;
+use chunks::{ChunkedGrid, CHUNK_SIZE};
+use day_night::{DayNight, DayPhaseData};
 use flora::FloraData;
+use prestige::Prestige;
+use random_events::{RandomEventData, RandomEvents};
+use save::{SaveError, SerializedChunks};
 use serde::{Deserialize, Serialize};
+use slots::{ActiveSlot, SaveSlot};
+use structures::{StructureData, STRUCTURE_ID_PREFIX};
+use upgrades::{ModifierTarget, UpgradeData};
 
 use crate::{
-    assets::FLORA_DATA_CORE,
+    assets::{
+        ACHIEVEMENT_DATA_CORE, ADJACENCY_DATA_CORE, DAY_NIGHT_DATA_CORE, RANDOM_EVENT_DATA_CORE,
+        STRUCTURE_DATA_CORE, UPGRADE_DATA_CORE,
+    },
     player::{GamingInput, Player},
     ui::{ItemPressed, MenuAction, MenuActionEvent},
     BachelorBuild,
 };
 
-use super::TILE_SIZE;
+use super::{BigNumber, TILE_SIZE};
 
-pub const MAP_SIZE: usize = 30;
+/// The size of the biggest possible garden, only the bought land of it can be used, see `land`.
+pub const MAP_SIZE: usize = 60;
+const _: () = assert!(MAP_SIZE.is_multiple_of(CHUNK_SIZE as usize));
 const EMPTY_CELL_VALUE: u16 = u16::MAX;
+/// Covers the footprint of a structure, see `structures`.
+const STRUCTURE_CELL_VALUE: u16 = u16::MAX - 1;
 const TALL_GRASS_CELL_VALUE: u16 = u16::MAX - 2;
+/// Stands in for the tall grass in the saved map data, so it must never be used as a flora id.
+const TALL_GRASS_CELL_ID: &str = "tall_grass";
+/// Stands in for empty ground that is growing grass again, see `regrowth`. Just like
+/// `TALL_GRASS_CELL_ID` it must never be used as a flora id.
+const EMPTY_CELL_ID: &str = "empty";
+/// Separates the owned plots, the cells and the regrowth delays in the saved map data.
+const LAND_SEPARATOR: char = '#';
 
 const DEFAULT_POINTS_CAP: u64 = 800;
-const POINTS_CAP_INCEASE_PER_SILO: u64 = 300;
-pub const POINTS_CAP_COST_INCREASE_PER_SILO: u64 = 200;
 const AUTO_SAVE_TIME_INTERVAL: u64 = 60;
 
 const TALL_GRASS_POINTS: u64 = 1;
 
+/// Appended to the file name (or storage key) of save data that failed to load.
+const SAVE_BACKUP_SUFFIX: &str = ".backup";
+
 pub struct MapPlugin;
 
 impl Plugin for MapPlugin {
     fn build(&self, app: &mut App) {
         app.add_plugins((
+            achievements::MapAchievementsPlugin,
             debug::MapDebugPlugin,
             border::MapBorderPlugin,
-            building::MapBuildingPlugin,
+            (
+                building::MapBuildingPlugin,
+                demolish::MapDemolishPlugin,
+                land::MapLandPlugin,
+                relocate::MapRelocatePlugin,
+                structures::MapStructuresPlugin,
+            ),
             clouds::CloudsPlugin,
+            export::MapExportPlugin,
             flora::MapFloraPlugin,
             grass::MapGrassPlugin,
+            (
+                day_night::MapDayNightPlugin,
+                growth::MapGrowthPlugin,
+                random_events::MapRandomEventsPlugin,
+                regrowth::MapRegrowthPlugin,
+            ),
+            prestige::MapPrestigePlugin,
+            slots::MapSlotsPlugin,
+            statistics::MapStatisticsPlugin,
             telemetry::GameTelemetryPlugin,
+            upgrades::MapUpgradesPlugin,
         ))
         .add_event::<ItemBought>()
         .add_event::<AutoSave>()
-        .add_systems(Startup, (insert_progression_core, insert_map_data_resource))
         .add_systems(
             PreUpdate,
             (
+                rng::sync_world_rng.run_if(resource_exists::<ProgressionCore>),
                 add_offline_progression.run_if(
                     resource_exists::<ProgressionCore>
                         .and(resource_exists::<MapData>)
+                        .and(resource_exists::<WorldRng>)
                         .and(run_once),
                 ),
                 // We also check if MapData exists (even though we don't need it in the system
@@ -106,7 +187,7 @@ impl Plugin for MapPlugin {
                 increase_points_on_cut_tall_grass,
                 add_points.run_if(on_timer(Duration::from_secs(1))),
                 reset_game_state,
-                save_game_state.run_if(on_event::<AutoSave>),
+                save_game_state.run_if(on_event::<AutoSave>.and(resource_exists::<ActiveSlot>)),
             )
                 .chain()
                 .in_set(ProgressionSystemSet)
@@ -118,15 +199,20 @@ impl Plugin for MapPlugin {
             PostUpdate,
             save_game_state.after(exit_on_all_closed).run_if(
                 resource_exists::<MapData>
-                    .and(resource_exists::<ProgressionCore>.and(on_event::<AppExit>)),
+                    .and(resource_exists::<ProgressionCore>)
+                    .and(resource_exists::<ActiveSlot>)
+                    .and(on_event::<AppExit>),
             ),
         );
         #[cfg(target_arch = "wasm32")]
         app.add_systems(
             Update,
-            sync_state_to_js.run_if(resource_exists::<MapData>.and(
-                resource_exists::<ProgressionCore>.and(on_real_timer(Duration::from_secs(1))),
-            )),
+            sync_state_to_js.run_if(
+                resource_exists::<MapData>
+                    .and(resource_exists::<ProgressionCore>)
+                    .and(resource_exists::<ActiveSlot>)
+                    .and(on_real_timer(Duration::from_secs(1))),
+            ),
         );
     }
 }
@@ -137,27 +223,53 @@ pub struct ProgressionSystemSet;
 #[derive(Resource, Serialize, Deserialize, Clone)]
 pub struct ProgressionCore {
     previous_timestamp: u64,
-    /// We store the points generate while offline in here.
-    /// This is also used as a flag, if there was progress done offline,
-    /// meaning this value is >0, than we spawn a number pop up indicating that
-    /// and than reset this value back to 0.
-    ///
-    /// Don't use this value for anything else.
-    offline_progression: u64,
-    pub points: u64,
-    pub points_cap: u64,
-    pub pps: u32,
+    /// All randomness of the garden is derived from this, see `rng`.
+    pub world_seed: u64,
+    /// The points that can be spent right now, never above `points_cap`.
+    pub points: BigNumber,
+    /// All the points earned in this run, used to compute the seeds of a rebirth.
+    pub lifetime_points: BigNumber,
+    pub points_cap: BigNumber,
+    pub pps: BigNumber,
+    /// Owned plants, indexed by the flora index. Saved by the flora ids, see `save`.
     pub flora: Vec<u16>,
-    pub silos: u64,
+    /// Bought structures that aren't placed yet by their id, see `structures`.
+    pub unplaced_structures: BTreeMap<String, u32>,
     pub player: Vec2,
     pub music: bool,
     pub sound: bool,
+    pub prestige: Prestige,
+    pub random_events: RandomEvents,
+    pub day_night: DayNight,
+    /// Ids of the bought upgrades.
+    pub upgrades: Vec<String>,
+    pub achievements: Achievements,
+    pub statistics: Statistics,
 }
 
 #[derive(Resource)]
 pub struct MapData {
-    grid: Vec<[u16; MAP_SIZE]>,
-    flora_data: Vec<FloraData>,
+    /// The cells with signed coordinates, the cell of the grid indices `(x, y)` is `(x, y)`, see
+    /// `chunks`.
+    grid: ChunkedGrid,
+    flora_registry: FloraRegistry,
+    upgrade_data: Vec<UpgradeData>,
+    adjacency_rules: Vec<AdjacencyRule>,
+    achievement_data: Vec<AchievementData>,
+    structure_data: Vec<StructureData>,
+    random_event_data: Vec<RandomEventData>,
+    day_phase_data: Vec<DayPhaseData>,
+    /// Seconds the crop at the cell has been growing, see `growth`.
+    growth_ages: HashMap<(usize, usize), u64>,
+    /// The anchor of every cell that is covered by a flora without being its anchor, see
+    /// `footprint`.
+    anchors: HashMap<(usize, usize), (usize, usize)>,
+    /// The placed structures by their anchor, see `structures`.
+    structures: BTreeMap<(usize, usize), Structure>,
+    /// The owned plots of land, see `land`.
+    plots: BTreeSet<(usize, usize)>,
+    /// Seconds until the empty cell grows tall grass again, see `regrowth`.
+    regrowth: HashMap<(usize, usize), f64>,
 }
 
 pub enum ZLevel {
@@ -172,8 +284,11 @@ pub enum ZLevel {
 #[derive(Event)]
 pub struct ItemBought {
     pub pos: Vec2,
-    pub cost: u32,
+    /// The total cost of all bought plants.
+    pub cost: BigNumber,
     item: Flora,
+    /// Only the store buys more than one plant at once, see `ItemPressed`.
+    amount: u32,
 }
 
 #[derive(Event)]
@@ -183,156 +298,345 @@ impl Default for ProgressionCore {
     fn default() -> Self {
         Self {
             previous_timestamp: 0,
-            offline_progression: 0,
-            points: 0,
-            points_cap: DEFAULT_POINTS_CAP,
-            pps: 0,
-            flora: vec![0; Flora::len()],
-            silos: 0,
+            world_seed: 0,
+            points: BigNumber::ZERO,
+            lifetime_points: BigNumber::ZERO,
+            points_cap: DEFAULT_POINTS_CAP.into(),
+            pps: BigNumber::ZERO,
+            flora: Vec::new(),
+            unplaced_structures: BTreeMap::new(),
             player: Vec2::ZERO,
             music: true,
             sound: true,
+            prestige: Prestige::default(),
+            random_events: RandomEvents::default(),
+            day_night: DayNight::default(),
+            upgrades: Vec::new(),
+            achievements: Achievements::default(),
+            statistics: Statistics::default(),
         }
     }
 }
 
 impl ProgressionCore {
+    /// The core of a brand new garden, unlike `default` it gets a random world seed.
+    pub fn new_garden() -> Self {
+        Self {
+            world_seed: rand::random(),
+            ..default()
+        }
+    }
+
+    pub fn flora_count(&self, flora: Flora) -> u16 {
+        self.flora.get(flora.index()).copied().unwrap_or_default()
+    }
+
+    fn add_flora(&mut self, flora: Flora) {
+        if self.flora.len() <= flora.index() {
+            self.flora.resize(flora.index() + 1, 0);
+        }
+        self.flora[flora.index()] += 1;
+    }
+
+    /// The cost of the next plant of the given flora.
+    pub fn flora_cost(&self, map_data: &MapData, flora: Flora) -> BigNumber {
+        map_data
+            .flora_data(flora)
+            .cost(self.flora_count(flora).into())
+    }
+
+    /// The cost of the next `amount` plants of the given flora.
+    pub fn flora_bulk_cost(&self, map_data: &MapData, flora: Flora, amount: u32) -> BigNumber {
+        map_data
+            .flora_data(flora)
+            .bulk_cost(self.flora_count(flora).into(), amount)
+    }
+
     pub fn is_affordable(&self, map_data: &MapData, flora: &Flora) -> bool {
-        self.points
-            >= map_data
-                .flora_data(flora.index())
-                .cost(self.flora[flora.index()].into()) as u64
+        self.points >= self.flora_cost(map_data, *flora)
+    }
+
+    /// Buy `amount` plants of the flora at once. Returns whether they were affordable.
+    fn buy_flora(&mut self, map_data: &MapData, flora: Flora, amount: u32) -> bool {
+        let cost = self.flora_bulk_cost(map_data, flora, amount);
+        if self.points < cost {
+            return false;
+        }
+
+        self.points -= cost;
+        for _ in 0..amount {
+            self.add_flora(flora);
+        }
+        true
     }
 
-    fn update_points_cap(&mut self) {
-        self.points_cap = DEFAULT_POINTS_CAP + self.silos * POINTS_CAP_INCEASE_PER_SILO;
+    fn update_points_cap(&mut self, map_data: &MapData) {
+        let points_cap = BigNumber::from(DEFAULT_POINTS_CAP) + self.structure_capacity(map_data);
+        self.points_cap = self
+            .apply_modifiers(map_data, ModifierTarget::PointsCap, points_cap)
+            .floor();
+    }
+
+    /// Add the given points without going over the cap, returns how many were actually added.
+    fn add_points(&mut self, amount: BigNumber) -> BigNumber {
+        debug_assert!(self.points <= self.points_cap);
+        let points = (self.points + amount).min(self.points_cap);
+        let added = points - self.points;
+
+        self.points = points;
+        self.lifetime_points += added;
+        self.statistics.points_earned += added;
+        added
     }
 }
 
 impl Default for MapData {
     fn default() -> Self {
-        Self {
-            grid: vec![[TALL_GRASS_CELL_VALUE; MAP_SIZE]; MAP_SIZE],
-            flora_data: Self::build_flora_data(),
-        }
+        Self::with_flora_registry(FloraRegistry::default())
     }
 }
 
 impl MapData {
-    fn build_flora_data() -> Vec<FloraData> {
-        let map: HashMap<Flora, FloraData> =
-            serde_json::from_str(FLORA_DATA_CORE).unwrap_or_default();
-
-        debug_assert_eq!(map.len(), Flora::len());
-
-        let mut data = vec![FloraData::default(); Flora::len()];
-
-        for (key, value) in &map {
-            data[key.index()] = value.clone();
+    /// A fresh map, the registry is kept over resets so that the loaded sprites stay around.
+    fn with_flora_registry(flora_registry: FloraRegistry) -> Self {
+        let mut grid = ChunkedGrid::default();
+        let chunks_per_side = MAP_SIZE as i32 / CHUNK_SIZE;
+        for x in 0..chunks_per_side {
+            for y in 0..chunks_per_side {
+                grid.load_chunk(IVec2::new(x, y), TALL_GRASS_CELL_VALUE);
+            }
         }
 
-        data
+        Self {
+            grid,
+            flora_registry,
+            upgrade_data: upgrades::build_upgrade_data(UPGRADE_DATA_CORE),
+            adjacency_rules: adjacency::build_adjacency_rules(ADJACENCY_DATA_CORE),
+            achievement_data: achievements::build_achievement_data(ACHIEVEMENT_DATA_CORE),
+            structure_data: structures::build_structure_data(STRUCTURE_DATA_CORE),
+            random_event_data: random_events::build_random_event_data(RANDOM_EVENT_DATA_CORE),
+            day_phase_data: day_night::build_day_phase_data(DAY_NIGHT_DATA_CORE),
+            growth_ages: HashMap::new(),
+            anchors: HashMap::new(),
+            structures: BTreeMap::new(),
+            plots: land::starting_plots(),
+            regrowth: HashMap::new(),
+        }
     }
 
     /// String must be of form
     ///
-    /// usize,usize:u16;REPEAT
-    fn from_str(string: &str) -> Self {
-        let mut map_data = MapData::default();
+    /// usize,usize;REPEAT#usize,usize:id[:u64];REPEAT#usize,usize:u64;REPEAT
+    ///
+    /// where the first part are the owned plots, the second part the cells and the last part the
+    /// seconds until empty ground grows grass again. The id is either a flora id, a structure id
+    /// with the `STRUCTURE_ID_PREFIX`, `TALL_GRASS_CELL_ID` or `EMPTY_CELL_ID`. The optional last
+    /// value of a cell is the age of a growing crop. Cells that aren't stored are tall grass.
+    /// Flora and structures are only stored at their anchor, the rest of the footprint is filled
+    /// in from their size. Older saves stored every cell of the footprint, those cells are skipped.
+    ///
+    /// This only parses the current format, see `save::deserialize_map_data` for loading saves.
+    fn from_str(string: &str, flora_registry: FloraRegistry) -> Result<Self, SaveError> {
+        let mut map_data = MapData::with_flora_registry(flora_registry);
 
         if string.is_empty() {
-            return map_data;
+            return Ok(map_data);
         }
 
-        for raw_data_point in string.split(';').collect::<Vec<&str>>() {
-            let parts = raw_data_point.split(':').collect::<Vec<&str>>();
-            debug_assert_eq!(parts.len(), 2, "{:?}", string);
-
-            let xy = parts[0].split(',').collect::<Vec<&str>>();
+        let missing_separator =
+            || SaveError::Malformed(format!("missing '{}' in map data", LAND_SEPARATOR));
+        let (raw_plots, rest) = string
+            .split_once(LAND_SEPARATOR)
+            .ok_or_else(missing_separator)?;
+        let (raw_cells, raw_regrowth) = rest
+            .split_once(LAND_SEPARATOR)
+            .ok_or_else(missing_separator)?;
+
+        map_data.plots.clear();
+        for raw_plot in raw_plots.split(';').filter(|s| !s.is_empty()) {
+            let malformed = || SaveError::Malformed(format!("invalid plot: {}", raw_plot));
+
+            let (x, y) = raw_plot.split_once(',').ok_or_else(malformed)?;
+            let x = x.parse::<usize>().map_err(|_| malformed())?;
+            let y = y.parse::<usize>().map_err(|_| malformed())?;
+            if x >= land::PLOTS_PER_SIDE || y >= land::PLOTS_PER_SIDE {
+                return Err(malformed());
+            }
+            map_data.plots.insert((x, y));
+        }
 
-            debug_assert_eq!(xy.len(), 2, "{:?}", string);
+        for raw_data_point in raw_cells.split(';').filter(|s| !s.is_empty()) {
+            let malformed = || SaveError::Malformed(format!("invalid cell: {}", raw_data_point));
 
-            let Ok(x) = xy[0].parse::<usize>() else {
-                return MapData::default();
-            };
-            let Ok(y) = xy[1].parse::<usize>() else {
-                return MapData::default();
+            let (xy, value) = raw_data_point.split_once(':').ok_or_else(malformed)?;
+            let (x, y) = xy.split_once(',').ok_or_else(malformed)?;
+            let (value, age) = match value.split_once(':') {
+                Some((value, age)) => (value, Some(age)),
+                None => (value, None),
             };
 
-            let Ok(value) = parts[1].parse::<u16>() else {
-                return MapData::default();
-            };
+            let x = x.parse::<usize>().map_err(|_| malformed())?;
+            let y = y.parse::<usize>().map_err(|_| malformed())?;
 
-            if x >= map_data.grid.len() || y >= map_data.grid[0].len() {
-                return MapData::default();
+            if !map_data.indices_in_grid(x, y) {
+                return Err(malformed());
             }
 
-            map_data.grid[x][y] = value;
-        }
-
-        map_data
-    }
-
-    fn to_string(&self) -> String {
-        let mut string = String::new();
+            if value == TALL_GRASS_CELL_ID {
+                if map_data.flora_at(x, y).is_some() {
+                    return Err(malformed());
+                }
+                map_data.set_grid_index(x, y, TALL_GRASS_CELL_VALUE);
+            } else if value == EMPTY_CELL_ID {
+                if map_data.flora_at(x, y).is_some() {
+                    return Err(malformed());
+                }
+                if age.is_some() {
+                    return Err(malformed());
+                }
+                map_data.set_grid_index(x, y, EMPTY_CELL_VALUE);
+                continue;
+            } else if let Some(id) = value.strip_prefix(STRUCTURE_ID_PREFIX) {
+                let Some(structure) = map_data.structure_by_id(id) else {
+                    warn!(
+                        "dropping map data cell with unknown structure: {}",
+                        raw_data_point
+                    );
+                    continue;
+                };
 
-        for x in 0..MAP_SIZE {
-            for y in 0..MAP_SIZE {
-                if self.grid_index(x, y) == EMPTY_CELL_VALUE {
+                let (x_size, y_size) = map_data.structure_data(structure).size_on_grid();
+                if !map_data.fits_at_empty_or_grass_position(x, y, x_size, y_size) {
+                    return Err(malformed());
+                }
+                map_data.place_structure(x, y, structure);
+                continue;
+            } else if let Some(flora) = map_data.flora_registry.get(value) {
+                if map_data
+                    .anchor_of(x, y)
+                    .is_some_and(|anchor| anchor != (x, y))
+                    && map_data.flora_at(x, y) == Some(flora)
+                {
                     continue;
                 }
 
-                if !string.is_empty() {
-                    string.push(';');
+                let (x_size, y_size) = map_data.flora_data(flora).size_on_grid();
+                if !map_data.fits_at_empty_or_grass_position(x, y, x_size, y_size) {
+                    return Err(malformed());
                 }
+                map_data.place_flora(x, y, flora);
+            } else {
+                // The flora was removed from the data, the cell simply grows grass again.
+                warn!(
+                    "dropping map data cell with unknown flora: {}",
+                    raw_data_point
+                );
+                continue;
+            }
+
+            if let Some(age) = age {
+                let age = age.parse::<u64>().map_err(|_| malformed())?;
+                map_data.growth_ages.insert((x, y), age);
+            }
+        }
 
-                string.push_str(&format!("{},{}:{}", x, y, self.grid_index(x, y)));
+        for raw_regrowth in raw_regrowth.split(';').filter(|s| !s.is_empty()) {
+            let malformed = || SaveError::Malformed(format!("invalid regrowth: {}", raw_regrowth));
+
+            let (xy, remaining) = raw_regrowth.split_once(':').ok_or_else(malformed)?;
+            let (x, y) = xy.split_once(',').ok_or_else(malformed)?;
+            let x = x.parse::<usize>().map_err(|_| malformed())?;
+            let y = y.parse::<usize>().map_err(|_| malformed())?;
+            let remaining = remaining.parse::<f64>().map_err(|_| malformed())?;
+
+            if map_data.grid_index(x, y) != Some(EMPTY_CELL_VALUE) {
+                return Err(malformed());
             }
+            map_data.regrowth.insert((x, y), remaining);
         }
-        string
+
+        Ok(map_data)
     }
 
-    fn pos_to_grid_indices(&self, p: Vec2) -> (usize, usize) {
+    /// The cell at the position, it doesn't have to be loaded.
+    pub fn pos_to_cell(&self, p: Vec2) -> IVec2 {
         let p = p / TILE_SIZE + Vec2::ONE * 0.5 * MAP_SIZE as f32;
+        p.round().as_ivec2()
+    }
 
-        let x = p.x.clamp(0.0, MAP_SIZE as f32 - 1.0);
-        let y = p.y.clamp(0.0, MAP_SIZE as f32 - 1.0);
+    pub fn cell_to_pos(&self, cell: IVec2) -> Vec2 {
+        (cell - IVec2::splat(MAP_SIZE as i32 / 2)).as_vec2() * TILE_SIZE
+    }
 
-        self.clamp_indices(x.round() as usize, y.round() as usize)
+    /// The grid indices closest to the position, `None` if that cell isn't loaded.
+    fn pos_to_grid_indices(&self, p: Vec2) -> Option<(usize, usize)> {
+        cell_to_indices(self.pos_to_cell(p)).filter(|(x, y)| self.indices_in_grid(*x, *y))
     }
 
     fn grid_indices_to_pos(&self, x: usize, y: usize) -> Vec2 {
-        Vec2::new(
-            x as f32 * TILE_SIZE - (MAP_SIZE / 2) as f32 * TILE_SIZE,
-            y as f32 * TILE_SIZE - (MAP_SIZE / 2) as f32 * TILE_SIZE,
-        )
+        self.cell_to_pos(indices_to_cell(x, y))
+    }
+
+    /// `None` if the chunk of the cell isn't loaded.
+    pub fn cell_at(&self, cell: IVec2) -> Option<u16> {
+        self.grid.get(cell)
     }
 
-    fn clamp_indices(&self, x: usize, y: usize) -> (usize, usize) {
-        (x.min(MAP_SIZE - 1), y.min(MAP_SIZE - 1))
+    /// `None` if the chunk of the cell isn't loaded.
+    pub fn grid_index(&self, x: usize, y: usize) -> Option<u16> {
+        self.cell_at(indices_to_cell(x, y))
     }
 
-    pub fn grid_index(&self, x: usize, y: usize) -> u16 {
-        let (clamped_x, clamped_y) = self.clamp_indices(x, y);
-        self.grid[clamped_x][clamped_y]
+    fn set_grid_index(&mut self, x: usize, y: usize, value: u16) {
+        let loaded = self.grid.set(indices_to_cell(x, y), value);
+        debug_assert!(loaded, "(x, y): {}, {}", x, y);
     }
 
     pub fn indices_in_grid(&self, x: usize, y: usize) -> bool {
-        x < MAP_SIZE && y < MAP_SIZE
+        self.grid.is_loaded(indices_to_cell(x, y))
     }
 
-    pub fn flora_data(&self, index: usize) -> FloraData {
-        debug_assert!(index < self.flora_data.len());
+    pub fn flora_registry(&self) -> &FloraRegistry {
+        &self.flora_registry
+    }
+
+    pub fn flora_data(&self, flora: Flora) -> FloraData {
+        self.flora_registry.data(flora)
+    }
 
-        if index >= self.flora_data.len() {
-            error!("attempted to get flora data from index out of range, must never happen!");
-            return FloraData::default();
+    /// Swap in a new registry (e.g. after `flora.json` was reloaded). The flora are placed again
+    /// by their ids, the ones that no longer exist (or no longer fit) leave their cells empty.
+    fn set_flora_registry(&mut self, flora_registry: FloraRegistry) {
+        let placed: Vec<((usize, usize), String)> = self
+            .placed_flora()
+            .map(|(anchor, flora)| (anchor, self.flora_registry.id(flora).to_string()))
+            .collect();
+        for ((x, y), _) in &placed {
+            self.clear_flora(*x, *y);
         }
 
-        self.flora_data[index].clone()
+        self.flora_registry = flora_registry;
+        for ((x, y), id) in placed {
+            let Some(flora) = self.flora_registry.get(&id) else {
+                self.growth_ages.remove(&(x, y));
+                continue;
+            };
+
+            let (x_size, y_size) = self.flora_data(flora).size_on_grid();
+            if self.fits_at_empty_or_grass_position(x, y, x_size, y_size) {
+                self.place_flora(x, y, flora);
+            } else {
+                self.growth_ages.remove(&(x, y));
+            }
+        }
     }
 
-    /// Return whether the cells at the given position with the size are all empty or grass.
+    pub fn upgrade_data(&self) -> &[UpgradeData] {
+        &self.upgrade_data
+    }
+
+    /// Return whether the cells at the given position with the size are all in the grid and empty
+    /// or grass.
     fn fits_at_empty_or_grass_position(
         &self,
         x: usize,
@@ -346,7 +650,7 @@ impl MapData {
         for inner_x in 0..x_size {
             for inner_y in 0..y_size {
                 let index = self.grid_index(x + inner_x, y + inner_y);
-                if index != EMPTY_CELL_VALUE && index != TALL_GRASS_CELL_VALUE {
+                if index != Some(EMPTY_CELL_VALUE) && index != Some(TALL_GRASS_CELL_VALUE) {
                     return false;
                 }
             }
@@ -354,144 +658,132 @@ impl MapData {
         true
     }
 
+    /// Whether a new object fits at the position, which also requires the land to be owned.
     fn fits_at_pos(&self, pos: Vec2, object_size: (usize, usize)) -> bool {
-        let (x, y) = self.pos_to_grid_indices(pos);
-        let (x_size, y_size) = object_size;
-        self.fits_at_empty_or_grass_position(x, y, x_size, y_size)
-    }
-
-    fn set_map_data_value_at_pos(
-        &mut self,
-        bottom_left_corner_pos: Vec2,
-        object_size: (usize, usize),
-        value: u16,
-    ) {
-        let (x, y) = self.pos_to_grid_indices(bottom_left_corner_pos);
+        let Some((x, y)) = self.pos_to_grid_indices(pos) else {
+            return false;
+        };
         let (x_size, y_size) = object_size;
-
-        debug_assert!(
-            self.fits_at_empty_or_grass_position(x, y, x_size, y_size),
-            "value: {}, pos: {}, (x, y): {}, {}, grid: {}",
-            value,
-            bottom_left_corner_pos,
-            x,
-            y,
-            self.grid[x][y]
-        );
-
-        for inner_x in 0..x_size {
-            for inner_y in 0..y_size {
-                let (clamped_x, clamped_y) = self.clamp_indices(x + inner_x, y + inner_y);
-                self.grid[clamped_x][clamped_y] = value;
-            }
-        }
+        let owned = (x..x + x_size).all(|x| (y..y + y_size).all(|y| self.owns_cell(x, y)));
+        owned && self.fits_at_empty_or_grass_position(x, y, x_size, y_size)
     }
 
     /// Sets the value at the position to empty. Only works when the current index is tall grass.
     fn set_tall_grass_cell_value_to_empty(&mut self, pos: Vec2) {
-        let (x, y) = self.pos_to_grid_indices(pos);
-        if self.grid_index(x, y) != TALL_GRASS_CELL_VALUE {
+        let cell = self.pos_to_cell(pos);
+        if self.cell_at(cell) != Some(TALL_GRASS_CELL_VALUE) {
             return;
         }
-        self.grid[x][y] = EMPTY_CELL_VALUE;
+        self.grid.set(cell, EMPTY_CELL_VALUE);
     }
-}
 
-impl ZLevel {
-    pub fn value(&self) -> f32 {
-        match self {
-            ZLevel::Background => -1e5,
-            ZLevel::Floor => -3e4,
-            // ZLevel::GroundEffect => -2e4,
-            // ZLevel::BottomEnvironment => -1e4,
-            ZLevel::TopEnvironment => 1e4,
-            ZLevel::TopUi => 1e4 + 301.0,
-        }
+    fn serialize_plots(&self) -> String {
+        self.plots
+            .iter()
+            .map(|(x, y)| format!("{},{}", x, y))
+            .collect::<Vec<String>>()
+            .join(";")
     }
-}
 
-#[cfg(target_arch = "wasm32")]
-fn insert_map_data_wasm(commands: &mut Commands) {
-    use web_sys::window;
-
-    let storage = window().and_then(|w| w.local_storage().ok()).flatten();
+    /// The regrowth delays change every second without changing the grid, so they are always
+    /// serialized in full instead of per chunk.
+    fn serialize_regrowth(&self) -> String {
+        let mut regrowth: Vec<_> = self.regrowth.iter().collect();
+        regrowth.sort_by_key(|(xy, _)| **xy);
+        regrowth
+            .into_iter()
+            .map(|((x, y), remaining)| format!("{},{}:{}", x, y, remaining.max(0.0).ceil()))
+            .collect::<Vec<String>>()
+            .join(";")
+    }
 
-    let map_data: MapData = storage
-        .as_ref()
-        .and_then(|s| s.get_item(WASM_MAP_DATA_KEY_STORAGE).ok().flatten())
-        .and_then(|r| Some(MapData::from_str(&r)))
-        .unwrap_or_default();
+    /// The saved cells of the chunk, see `from_str` for the format.
+    fn serialize_chunk(&self, chunk: IVec2) -> String {
+        let mut cells = Vec::new();
+        for (cell, value) in self.grid.cells_in_chunk(chunk) {
+            let Some((x, y)) = cell_to_indices(cell) else {
+                continue;
+            };
+            if self.anchors.contains_key(&(x, y)) {
+                continue;
+            }
 
-    commands.insert_resource(map_data);
-}
+            if value == EMPTY_CELL_VALUE {
+                cells.push(format!("{},{}:{}", x, y, EMPTY_CELL_ID));
+                continue;
+            }
 
-#[cfg(not(target_arch = "wasm32"))]
-fn insert_map_data_native(commands: &mut Commands) {
-    let raw_map_data = read_to_string(MAP_DATA_FILE).unwrap_or_default();
-    let map_data = MapData::from_str(&raw_map_data);
-    commands.insert_resource(map_data);
-}
+            if let Some(structure) = self.structures.get(&(x, y)) {
+                let id = &self.structure_data(*structure).id;
+                cells.push(format!("{},{}:{}{}", x, y, STRUCTURE_ID_PREFIX, id));
+                continue;
+            }
 
-fn insert_map_data_resource(mut commands: Commands) {
-    #[cfg(target_arch = "wasm32")]
-    insert_map_data_wasm(&mut commands);
+            let id = match self.flora_at(x, y) {
+                Some(flora) => self.flora_registry.id(flora),
+                None => TALL_GRASS_CELL_ID,
+            };
+            let mut string = format!("{},{}:{}", x, y, id);
+            if let Some(age) = self.growth_ages.get(&(x, y)).filter(|age| **age > 0) {
+                string.push_str(&format!(":{}", age));
+            }
+            cells.push(string);
+        }
+        cells.join(";")
+    }
 
-    #[cfg(not(target_arch = "wasm32"))]
-    insert_map_data_native(&mut commands);
+    /// Put the saved map data together from the serialized chunks, in the order of the loaded
+    /// chunks.
+    fn serialize_with_chunks<'a>(&self, chunks: impl Iterator<Item = &'a str>) -> String {
+        let cells: Vec<&str> = chunks.filter(|chunk| !chunk.is_empty()).collect();
+        format!(
+            "{}{}{}{}{}",
+            self.serialize_plots(),
+            LAND_SEPARATOR,
+            cells.join(";"),
+            LAND_SEPARATOR,
+            self.serialize_regrowth()
+        )
+    }
 }
 
-#[cfg(target_arch = "wasm32")]
-fn insert_progression_core_wasm(commands: &mut Commands) {
-    use web_sys::window;
-
-    let storage = window().and_then(|w| w.local_storage().ok()).flatten();
-
-    let core: ProgressionCore = storage
-        .as_ref()
-        .and_then(|s| s.get_item(WASM_PROGRESSION_CORE_KEY_STORAGE).ok().flatten())
-        .and_then(|r| serde_json::from_str(&r).ok())
-        .unwrap_or_default();
-
-    commands.insert_resource(core);
+fn indices_to_cell(x: usize, y: usize) -> IVec2 {
+    IVec2::new(
+        i32::try_from(x).unwrap_or(i32::MAX),
+        i32::try_from(y).unwrap_or(i32::MAX),
+    )
 }
 
-#[cfg(not(target_arch = "wasm32"))]
-fn insert_progression_core_native(commands: &mut Commands) {
-    let raw_core =
-        read_to_string(PROGRESSION_CORE_FILE).expect("failed to read progression core file");
-    let core = if raw_core.is_empty() {
-        ProgressionCore::default()
-    } else {
-        serde_json::from_str(&raw_core).expect("failed to parse progression core from json string")
-    };
-    commands.insert_resource(core);
+/// `None` for cells with negative coordinates, those have no grid indices.
+fn cell_to_indices(cell: IVec2) -> Option<(usize, usize)> {
+    Some((usize::try_from(cell.x).ok()?, usize::try_from(cell.y).ok()?))
 }
 
-fn insert_progression_core(mut commands: Commands) {
-    #[cfg(target_arch = "wasm32")]
-    insert_progression_core_wasm(&mut commands);
-
-    #[cfg(not(target_arch = "wasm32"))]
-    insert_progression_core_native(&mut commands);
+impl fmt::Display for MapData {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let chunks: Vec<String> = self
+            .grid
+            .chunks()
+            .into_iter()
+            .map(|chunk| self.serialize_chunk(chunk))
+            .collect();
+        write!(
+            f,
+            "{}",
+            self.serialize_with_chunks(chunks.iter().map(String::as_str))
+        )
+    }
 }
 
-#[cfg(target_arch = "wasm32")]
-fn save_game_state_wasm(keys: &[&str; 3], data: &[String; 3]) {
-    use web_sys::window;
-
-    let Some(storage) = window().and_then(|w| w.local_storage().ok()).flatten() else {
-        error!("failed to get browser storage (for writing)");
-        return;
-    };
-
-    for i in 0..keys.len() {
-        let err_msg = format!(
-            "failed to store to local storage, key: {}, data: {}",
-            keys[i], &data[i]
-        );
-
-        if storage.set_item(keys[i], &data[i]).is_err() {
-            error!("{}", err_msg);
+impl ZLevel {
+    pub fn value(&self) -> f32 {
+        match self {
+            ZLevel::Background => -1e5,
+            ZLevel::Floor => -3e4,
+            // ZLevel::GroundEffect => -2e4,
+            // ZLevel::BottomEnvironment => -1e4,
+            ZLevel::TopEnvironment => 1e4,
+            ZLevel::TopUi => 1e4 + 301.0,
         }
     }
 }
@@ -508,9 +800,18 @@ fn sync_state_to_js(
     core: Res<ProgressionCore>,
     map_data: Res<MapData>,
     telemetry: Res<GameTelemetryManager>,
+    active_slot: Res<ActiveSlot>,
+    mut serialized_chunks: Local<SerializedChunks>,
 ) {
-    let data = package_save_data(&core, &map_data, &telemetry);
-    let save_data: HashMap<&&str, &String> = WASM_KEYS.iter().zip(data.iter()).collect();
+    let slot = package_save_data(
+        &active_slot,
+        &core,
+        &map_data,
+        &telemetry,
+        &mut serialized_chunks,
+    );
+    let save_data: HashMap<String, String> =
+        HashMap::from([(slots::slot_key(active_slot.id), slot.to_string())]);
 
     let Ok(save_data_string) = &serde_json::to_string(&save_data) else {
         error!("failed to parse hashmap save data to json");
@@ -520,33 +821,20 @@ fn sync_state_to_js(
     buffer_game_state(save_data_string);
 }
 
-#[cfg(not(target_arch = "wasm32"))]
-fn save_game_state_native(data: &[String; 3]) {
-    const FILES: [&str; 3] = [PROGRESSION_CORE_FILE, MAP_DATA_FILE, GAME_TELEMETRY_FILE];
-
-    for i in 0..FILES.len() {
-        let err_msg = format!(
-            "failed to write data to file, file: {}, data: {}",
-            FILES[i], data[i]
-        );
-
-        fs::write(FILES[i], &data[i]).expect(&err_msg);
-    }
-}
-
-/// 1. Core
-/// 2. MapData
-/// 3. Game Telemetry
+/// Bundle the core, the map data and the game telemetry into the active slot.
 fn package_save_data(
+    active_slot: &ActiveSlot,
     core: &ProgressionCore,
     map_data: &MapData,
     telemetry: &GameTelemetryManager,
-) -> [String; 3] {
-    [
-        serde_json::to_string(core).unwrap_or_default(),
-        map_data.to_string(),
-        serde_json::to_string(telemetry).unwrap_or_default(),
-    ]
+    serialized_chunks: &mut SerializedChunks,
+) -> SaveSlot {
+    SaveSlot {
+        name: active_slot.name.clone(),
+        core: save::serialize_progression_core(core, map_data.flora_registry()),
+        map_data: serialized_chunks.serialize_map_data(map_data),
+        telemetry: serde_json::to_string(telemetry).unwrap_or_default(),
+    }
 }
 
 fn trigger_auto_save(mut ev_auto_save: EventWriter<AutoSave>) {
@@ -557,17 +845,21 @@ fn save_game_state(
     core: Res<ProgressionCore>,
     map_data: Res<MapData>,
     telemetry: Res<GameTelemetryManager>,
+    active_slot: Res<ActiveSlot>,
+    mut serialized_chunks: Local<SerializedChunks>,
 ) {
-    let data = package_save_data(&core, &map_data, &telemetry);
-
-    #[cfg(target_arch = "wasm32")]
-    save_game_state_wasm(&WASM_KEYS, &data);
-
-    #[cfg(not(target_arch = "wasm32"))]
-    save_game_state_native(&data);
+    let slot = package_save_data(
+        &active_slot,
+        &core,
+        &map_data,
+        &telemetry,
+        &mut serialized_chunks,
+    );
+    slots::write_slot(active_slot.id, &slot);
 }
 
 fn reset_game_state(
+    mut commands: Commands,
     mut core: ResMut<ProgressionCore>,
     mut map_data: ResMut<MapData>,
     mut ev_menu_action: EventReader<MenuActionEvent>,
@@ -579,20 +871,44 @@ fn reset_game_state(
         return;
     }
 
-    *core = ProgressionCore::default();
-    *map_data = MapData::default();
+    *core = ProgressionCore::new_garden();
+    *map_data = MapData::with_flora_registry(map_data.flora_registry.clone());
+    // Right away, the grass of the new garden grows in this very frame.
+    commands.insert_resource(WorldRng::new(core.world_seed));
+}
+
+/// The pps of a single plant of the given flora, without any adjacency bonus.
+fn compute_flora_pps(core: &ProgressionCore, map_data: &MapData, flora: Flora) -> BigNumber {
+    core.apply_modifiers(
+        map_data,
+        ModifierTarget::FloraPps(map_data.flora_registry.id(flora).to_string()),
+        map_data.flora_data(flora).pps,
+    )
 }
 
-fn compute_current_pps(core: &ProgressionCore, map_data: &MapData) -> u32 {
-    let mut pps = 0;
-    for i in 0..core.flora.len() {
-        if core.flora[i] == 0 {
+/// The pps of all plants of each flora (indexed by the flora index), before the modifiers of the
+/// whole garden are applied.
+fn compute_pps_breakdown(core: &ProgressionCore, map_data: &MapData) -> Vec<BigNumber> {
+    let adjacency_bonuses = map_data.adjacency_bonuses();
+
+    let mut breakdown = vec![BigNumber::ZERO; map_data.flora_registry.len()];
+    for flora in map_data.flora_registry.iter() {
+        if core.flora_count(flora) == 0 {
             continue;
         }
 
-        pps += core.flora[i] as u32 * map_data.flora_data(i).pps;
+        // Every bonus is relative to the pps of a single plant, so they simply add to the count.
+        let count = core.flora_count(flora) as f64 + adjacency_bonuses[flora.index()];
+        breakdown[flora.index()] = compute_flora_pps(core, map_data, flora) * count.into();
     }
-    pps
+    breakdown
+}
+
+fn compute_current_pps(core: &ProgressionCore, map_data: &MapData) -> BigNumber {
+    let pps = compute_pps_breakdown(core, map_data)
+        .into_iter()
+        .fold(BigNumber::ZERO, |pps, flora_pps| pps + flora_pps);
+    core.apply_modifiers(map_data, ModifierTarget::Pps, pps)
 }
 
 fn update_points_per_second(mut core: ResMut<ProgressionCore>, map_data: Res<MapData>) {
@@ -600,13 +916,15 @@ fn update_points_per_second(mut core: ResMut<ProgressionCore>, map_data: Res<Map
     core.pps = pps;
 }
 
-fn update_points_cap(mut core: ResMut<ProgressionCore>) {
-    core.update_points_cap();
+fn update_points_cap(mut core: ResMut<ProgressionCore>, map_data: Res<MapData>) {
+    core.update_points_cap(&map_data);
 }
 
-fn add_points(mut core: ResMut<ProgressionCore>) {
-    debug_assert!(core.points <= core.points_cap);
-    core.points = (core.points + core.pps as u64).min(core.points_cap);
+fn add_points(mut core: ResMut<ProgressionCore>, map_data: Res<MapData>) {
+    let pps = core.pps;
+    let points = core.add_points(pps);
+    let flora_points = statistics::split_points_by_flora(&core, &map_data, points);
+    core.statistics.add_flora_points(&flora_points);
 }
 
 fn trigger_item_bought_on_item_pressed(
@@ -621,12 +939,13 @@ fn trigger_item_bought_on_item_pressed(
     }
 
     for ev in ev_item_pressed.read() {
-        if core.is_affordable(&map_data, &ev.flora) {
+        let cost = core.flora_bulk_cost(&map_data, ev.flora, ev.amount);
+        if core.points >= cost {
             ev_item_bought.write(ItemBought {
                 pos: Vec2::ZERO,
-                cost: map_data.flora_data[ev.flora.index()]
-                    .cost(core.flora[ev.flora.index()].into()),
+                cost,
                 item: ev.flora,
+                amount: ev.amount,
             });
         }
     }
@@ -660,16 +979,20 @@ fn trigger_item_bought_on_blueprint_build(
         return;
     };
 
-    if !blueprint.fits_at_pos {
+    if !blueprint.fits_at_pos || blueprint.moving_from.is_some() {
         return;
     }
+    // Structures are placed in `structures`.
+    let BlueprintItem::Flora(flora) = blueprint.item else {
+        return;
+    };
 
-    if core.is_affordable(&map_data, &blueprint.item) {
+    if core.is_affordable(&map_data, &flora) {
         ev_item_bought.write(ItemBought {
             pos: transform.translation.xy(),
-            cost: map_data.flora_data[blueprint.item.index()]
-                .cost(core.flora[blueprint.item.index()].into()),
-            item: blueprint.item,
+            cost: core.flora_cost(&map_data, flora),
+            item: flora,
+            amount: 1,
         });
     }
 }
@@ -680,13 +1003,8 @@ fn update_progression_core_on_item_bought(
     mut ev_item_bought: EventReader<ItemBought>,
 ) {
     for ev in ev_item_bought.read() {
-        let cost = map_data
-            .flora_data(ev.item.index())
-            .cost(core.flora[ev.item.index()].into()) as u64;
-
-        debug_assert!(core.points >= cost);
-        core.points -= cost.min(core.points);
-        core.flora[ev.item.index()] += 1;
+        let bought = core.buy_flora(&map_data, ev.item, ev.amount);
+        debug_assert!(bought);
     }
 }
 
@@ -725,46 +1043,47 @@ fn timestamp() -> u64 {
 }
 
 fn update_progression_core_timestamp(mut core: ResMut<ProgressionCore>) {
-    #[cfg(not(target_arch = "wasm32"))]
-    let timestamp = timestamp_native();
-
-    #[cfg(target_arch = "wasm32")]
-    let timestamp = timestamp_wasm();
-
-    core.previous_timestamp = timestamp;
+    core.previous_timestamp = timestamp();
 }
 
-fn add_offline_progression(mut core: ResMut<ProgressionCore>, map_data: Res<MapData>) {
+fn add_offline_progression(
+    mut commands: Commands,
+    mut core: ResMut<ProgressionCore>,
+    mut map_data: ResMut<MapData>,
+    mut world_rng: ResMut<WorldRng>,
+) {
     let timestamp = timestamp();
 
-    debug_assert!(timestamp > core.previous_timestamp);
-
-    if core.previous_timestamp > timestamp {
-        error!("The previous timestamp is greater than the current timestamp, did you alter the systems time?");
+    // A fresh garden was never saved, there is no offline time to pay out.
+    if core.previous_timestamp == 0 {
+        core.previous_timestamp = timestamp;
         return;
     }
 
-    let diff = timestamp - core.previous_timestamp;
-    let pps = compute_current_pps(&core, &map_data) as u64;
-
-    debug_assert!(core.points <= core.points_cap);
-    if core.points == core.points_cap {
-        core.offline_progression = 0;
+    if core.previous_timestamp > timestamp {
+        error!(
+            "The previous timestamp is greater than the current timestamp, did you alter the systems time?"
+        );
         return;
     }
 
-    let new_points = (pps * diff).min(core.points_cap - core.points);
-    core.points += new_points;
-    core.offline_progression = new_points;
+    let diff = timestamp - core.previous_timestamp;
+    let report = offline::apply_offline_progression(&mut core, &mut map_data, &mut world_rng, diff);
+    commands.insert_resource(report);
 }
 
 fn increase_points_on_cut_tall_grass(
     mut core: ResMut<ProgressionCore>,
+    map_data: Res<MapData>,
     mut ev_cut_tall_grass: EventReader<CutTallGrass>,
 ) {
     for _ in ev_cut_tall_grass.read() {
-        debug_assert!(core.points <= core.points_cap);
-        core.points = (core.points + TALL_GRASS_POINTS).min(core.points_cap);
+        let points = core.apply_modifiers(
+            &map_data,
+            ModifierTarget::GrassPoints,
+            TALL_GRASS_POINTS.into(),
+        );
+        core.add_points(points);
     }
 }
 
@@ -796,130 +1115,35 @@ fn update_music_on_core(
     }
 }
 
-#[cfg(debug_assertions)]
-pub fn simulate_progression() {
-    fn flora_evaluation(cost: u64, pps: u32) -> f32 {
-        if cost == 0 {
-            return f32::MAX;
-        }
-
-        (pps as f32) / (cost as f32)
-    }
-
-    fn get_next_item_index(core: &mut ProgressionCore, flora_data: &[FloraData]) -> Option<usize> {
-        let silo_cost = POINTS_CAP_COST_INCREASE_PER_SILO * core.silos;
-        if core.points >= silo_cost {
-            core.silos += 1;
-            core.points -= silo_cost;
-            return None;
-        }
-
-        let mut best_index = usize::MAX;
-        let mut best_evaluation = f32::NEG_INFINITY;
-        for i in 0..core.flora.len() {
-            let cost = flora_data[i].cost(core.flora[i].into()) as u64;
-            if core.points < cost {
-                continue;
-            }
-
-            let evaluation = flora_evaluation(cost, flora_data[i].pps);
-            if evaluation > best_evaluation {
-                best_index = i;
-                best_evaluation = evaluation;
-            }
-        }
-
-        if best_index == usize::MAX {
-            return None;
-        }
-        Some(best_index)
-    }
-
-    let mut core = ProgressionCore::default();
-    let map_data = MapData::default();
-    assert_eq!(core.flora.len(), map_data.flora_data.len());
-
-    core.points = DEFAULT_POINTS_CAP / 10;
-    assert!(core.points > 0);
-
-    let mut data = Vec::new();
-
-    const MAX_TICKS: usize = 1000;
-    for time in 0..MAX_TICKS {
-        data.push((time, core.clone()));
-
-        let pps = compute_current_pps(&core, &map_data);
-        core.pps = pps;
-        core.update_points_cap();
-        core.points = (core.points + core.pps as u64).min(core.points_cap);
-
-        let Some(index) = get_next_item_index(&mut core, &map_data.flora_data) else {
-            continue;
-        };
-
-        let cost = map_data.flora_data[index].cost(core.flora[index].into()) as u64;
-        assert!(core.points >= cost);
-        core.points -= cost;
-        core.flora[index] += 1;
-    }
-
-    let content = data
-        .iter()
-        .map(|(time, core)| {
-            format!(
-                "{}:{};{};{};[{}]",
-                time,
-                core.points,
-                core.points_cap,
-                core.pps,
-                core.flora
-                    .iter()
-                    .map(|f| format!("{}", f))
-                    .collect::<Vec<String>>()
-                    .join(",")
-            )
-        })
-        .collect::<Vec<String>>()
-        .join("\n");
-
-    std::fs::write("SIMULATION_PROGRESS_OUT.csv", content).expect("failed to write to file");
-
-    println!("{}", core.points);
-    println!("{}", core.points_cap);
-    println!("{}", core.pps);
-
-    println!("{:?}", core.flora);
-}
-
 #[test]
 fn validate_pos_to_grid_indices() {
     let map_data = MapData::default();
 
     assert_eq!(
         map_data.pos_to_grid_indices(Vec2::ZERO),
-        (MAP_SIZE / 2, MAP_SIZE / 2)
+        Some((MAP_SIZE / 2, MAP_SIZE / 2))
     );
 
+    // Positions off the map don't end up on its edge.
     assert_eq!(
         map_data.pos_to_grid_indices(-Vec2::ONE * TILE_SIZE * MAP_SIZE as f32),
-        (0, 0)
+        None
     );
-
     assert_eq!(
         map_data.pos_to_grid_indices(Vec2::ONE * TILE_SIZE * MAP_SIZE as f32),
-        (MAP_SIZE - 1, MAP_SIZE - 1)
+        None
     );
 
     assert_eq!(
         map_data.pos_to_grid_indices(Vec2::ONE * TILE_SIZE),
-        (MAP_SIZE / 2 + 1, MAP_SIZE / 2 + 1)
+        Some((MAP_SIZE / 2 + 1, MAP_SIZE / 2 + 1))
     );
 
     assert_eq!(
         map_data.pos_to_grid_indices(
             -Vec2::ONE * 0.5 * TILE_SIZE * MAP_SIZE as f32 + Vec2::ONE * TILE_SIZE
         ),
-        (1, 1)
+        Some((1, 1))
     );
 }
 
@@ -943,23 +1167,42 @@ fn validate_grid_indices_to_pos() {
 
 #[test]
 fn validate_grid_index() {
-    let map_data = MapData::default();
-
-    map_data.grid_index(0, 0);
-    map_data.grid_index(MAP_SIZE, 0);
-    map_data.grid_index(MAP_SIZE + 100, 0);
-    map_data.grid_index(MAP_SIZE + 100, MAP_SIZE);
-    map_data.grid_index(MAP_SIZE + 100, MAP_SIZE + 100);
+    let mut map_data = MapData::default();
+
+    assert_eq!(map_data.grid_index(0, 0), Some(TALL_GRASS_CELL_VALUE));
+    assert_eq!(map_data.grid_index(MAP_SIZE, 0), None);
+    assert_eq!(map_data.grid_index(MAP_SIZE + 100, 0), None);
+    assert_eq!(map_data.grid_index(MAP_SIZE + 100, MAP_SIZE), None);
+    assert_eq!(map_data.grid_index(MAP_SIZE + 100, MAP_SIZE + 100), None);
+
+    // Cells outside of the map aren't loaded, they don't alias to the edge.
+    let edge = IVec2::splat(MAP_SIZE as i32 - 1);
+    let corn = map_data.flora_registry().get("corn").unwrap();
+    map_data.set_grid_index(MAP_SIZE - 1, MAP_SIZE - 1, corn.cell_value());
+    assert_eq!(map_data.cell_at(edge), Some(corn.cell_value()));
+    assert_eq!(map_data.cell_at(edge + IVec2::X), None);
+    assert_eq!(map_data.cell_at(-IVec2::ONE), None);
+    assert_eq!(map_data.grid_index(MAP_SIZE, MAP_SIZE - 1), None);
+    assert_eq!(map_data.flora_at(MAP_SIZE, MAP_SIZE - 1), None);
+
+    let outside = map_data.cell_to_pos(-IVec2::ONE);
+    assert_eq!(map_data.pos_to_cell(outside), -IVec2::ONE);
+    assert_eq!(map_data.pos_to_grid_indices(outside), None);
+    assert!(!map_data.fits_at_pos(outside, (1, 1)));
 }
 
 #[test]
-fn validate_flora_len_matches_json_data() {
-    let map: HashMap<Flora, FloraData> = serde_json::from_str(FLORA_DATA_CORE).unwrap_or_default();
-
-    assert_eq!(Flora::len(), map.len());
-}
-
-#[test]
-fn validate_silo_points_cap_increases_fast_enough() {
-    assert!(POINTS_CAP_INCEASE_PER_SILO >= POINTS_CAP_COST_INCREASE_PER_SILO)
+fn validate_bulk_buying_flora() {
+    let mut core = ProgressionCore::default();
+    let map_data = MapData::default();
+    let carrot = map_data.flora_registry().get("carrot").unwrap();
+    let cost = core.flora_bulk_cost(&map_data, carrot, 10);
+    core.points_cap = cost * 2u64.into();
+    core.points = cost;
+
+    assert!(core.buy_flora(&map_data, carrot, 10));
+    assert!(core.points.is_zero());
+    assert_eq!(core.flora_count(carrot), 10);
+    assert!(!core.buy_flora(&map_data, carrot, 10));
+    assert_eq!(core.flora_count(carrot), 10);
 }
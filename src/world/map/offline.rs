@@ -0,0 +1,179 @@
+//! Offline progression.
+//!
+//! When a save is loaded we pay out everything the garden produced while the game was closed, up
+//! to `max_offline_seconds`, which upgrades can raise. The result is kept around as an `OfflineReport` so the UI can show
+//! the player what happened while they were away. The running random event and the phases of the
+//! day keep changing the rates on the way.
+
+use bevy::prelude::*;
+
+use crate::world::BigNumber;
+
+use super::{
+    compute_current_pps, statistics::split_points_by_flora, upgrades::ModifierTarget, MapData,
+    ProgressionCore, RngStream, WorldRng,
+};
+
+#[cfg(test)]
+use super::{EMPTY_CELL_VALUE, TALL_GRASS_CELL_VALUE};
+
+/// Offline time above this is simply lost, unless upgrades raise it.
+const BASE_OFFLINE_SECONDS: u64 = 8 * 60 * 60;
+
+#[derive(Resource, Clone, Debug, PartialEq)]
+pub struct OfflineReport {
+    /// The real time the game was closed.
+    pub elapsed_seconds: u64,
+    /// The part of the elapsed time that actually counted, see `max_offline_seconds`.
+    pub counted_seconds: u64,
+    pub points: BigNumber,
+    /// Points earned per flora, indexed by the flora index.
+    pub flora_points: Vec<BigNumber>,
+    /// How long the points sat at the cap.
+    pub seconds_at_cap: u64,
+    pub points_lost_to_cap: BigNumber,
+    /// The name of the random event that ran out while the player was away.
+    pub expired_event: Option<String>,
+}
+
+impl ProgressionCore {
+    /// The most offline time that gets paid out.
+    pub fn max_offline_seconds(&self, map_data: &MapData) -> u64 {
+        self.apply_modifiers(
+            map_data,
+            ModifierTarget::OfflineTime,
+            BASE_OFFLINE_SECONDS.into(),
+        )
+        .to_f64() as u64
+    }
+}
+
+/// Pay out the offline progression for the given time and report what happened.
+pub fn apply_offline_progression(
+    core: &mut ProgressionCore,
+    map_data: &mut MapData,
+    world_rng: &mut WorldRng,
+    elapsed_seconds: u64,
+) -> OfflineReport {
+    let counted_seconds = elapsed_seconds.min(core.max_offline_seconds(map_data));
+
+    let event_name = core.active_event(map_data).map(|event| event.name.clone());
+
+    // The running event and the phase of the day change the rates when they end, so the time is
+    // paid out in segments between those.
+    let mut produced = BigNumber::ZERO;
+    let mut regrowth_seconds = 0.0;
+    let mut remaining = counted_seconds;
+    while remaining > 0 {
+        let event_left = core
+            .random_events
+            .active
+            .as_ref()
+            .map_or(remaining, |active| active.remaining);
+        let phase_left = core
+            .day_night
+            .seconds_left_in_phase(map_data.day_phase_data())
+            .unwrap_or(remaining);
+        let segment = remaining.min(event_left).min(phase_left).max(1);
+
+        produced += compute_current_pps(core, map_data) * segment.into();
+        regrowth_seconds += segment as f64 * core.grass_regrowth_rate(map_data);
+
+        core.random_events
+            .expire(segment, world_rng.stream(RngStream::Events));
+        core.day_night.advance(map_data.day_phase_data(), segment);
+        remaining -= segment;
+    }
+    let expired_event = event_name.filter(|_| core.random_events.active.is_none());
+    let pps = compute_current_pps(core, map_data);
+
+    let points = core.add_points(produced);
+    let points_lost_to_cap = produced - points;
+    let seconds_at_cap = if pps.is_zero() {
+        0
+    } else {
+        points_lost_to_cap.ratio(pps) as u64
+    };
+
+    let flora_points = split_points_by_flora(core, map_data, points);
+    core.statistics.add_flora_points(&flora_points);
+    core.statistics.offline_seconds += counted_seconds;
+
+    map_data.advance_growth(counted_seconds);
+    map_data.advance_regrowth(regrowth_seconds, world_rng.stream(RngStream::Regrowth));
+
+    // The time of day follows the real time, even the part that didn't count.
+    core.day_night
+        .advance(map_data.day_phase_data(), elapsed_seconds - counted_seconds);
+
+    OfflineReport {
+        elapsed_seconds,
+        counted_seconds,
+        points,
+        flora_points,
+        seconds_at_cap,
+        points_lost_to_cap,
+        expired_event,
+    }
+}
+
+#[test]
+fn validate_offline_progression_hits_cap() {
+    let mut core = ProgressionCore::default();
+    let mut map_data = MapData::default();
+    let raddish = map_data.flora_registry().get("raddish").unwrap();
+    let carrot = map_data.flora_registry().get("carrot").unwrap();
+    core.add_flora(raddish);
+    let pps = compute_current_pps(&core, &map_data);
+    assert!(!pps.is_zero());
+
+    // Exactly enough time to fill the cap, then an hour more.
+    let seconds_to_cap = core.points_cap.ratio(pps) as u64;
+    let report = apply_offline_progression(
+        &mut core,
+        &mut map_data,
+        &mut WorldRng::new(0),
+        seconds_to_cap + 3600,
+    );
+
+    assert_eq!(core.points, core.points_cap);
+    assert_eq!(report.points, core.points_cap);
+    assert_eq!(report.seconds_at_cap, 3600);
+    assert_eq!(report.points_lost_to_cap, pps * 3600u64.into());
+    assert_eq!(report.flora_points[raddish.index()], report.points);
+    assert_eq!(core.statistics.offline_seconds, seconds_to_cap + 3600);
+    assert_eq!(core.statistics.flora_points(raddish), report.points);
+    assert!(report.flora_points[carrot.index()].is_zero());
+}
+
+#[test]
+fn validate_offline_progression_is_limited() {
+    let mut core = ProgressionCore::default();
+    let mut map_data = MapData::default();
+    let (x, y) = map_data.pos_to_grid_indices(Vec2::ZERO).unwrap();
+    map_data.set_tall_grass_cell_value_to_empty(Vec2::ZERO);
+    assert_eq!(map_data.grid_index(x, y), Some(EMPTY_CELL_VALUE));
+
+    let report = apply_offline_progression(
+        &mut core,
+        &mut map_data,
+        &mut WorldRng::new(0),
+        BASE_OFFLINE_SECONDS * 3,
+    );
+    assert_eq!(report.elapsed_seconds, BASE_OFFLINE_SECONDS * 3);
+    assert_eq!(report.counted_seconds, BASE_OFFLINE_SECONDS);
+    assert!(report.points.is_zero());
+    assert_eq!(report.seconds_at_cap, 0);
+    // The cut grass grew back while the game was closed.
+    assert_eq!(map_data.grid_index(x, y), Some(TALL_GRASS_CELL_VALUE));
+
+    // Upgrades let more of the offline time count.
+    core.upgrades.push("cozy_hammock".to_string());
+    let report = apply_offline_progression(
+        &mut core,
+        &mut map_data,
+        &mut WorldRng::new(0),
+        BASE_OFFLINE_SECONDS * 3,
+    );
+    assert_eq!(report.counted_seconds, BASE_OFFLINE_SECONDS * 3 / 2);
+}
@@ -0,0 +1,244 @@
+//! Prestige (rebirth).
+//!
+//! Once the player earned enough points in a run they can rebirth. That resets the garden, but
+//! they get seeds for it, which are kept forever and can be spent on permanent multipliers.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{world::BigNumber, GameState};
+
+use super::{MapData, ProgressionCore, ProgressionSystemSet};
+
+#[cfg(test)]
+use super::SILO_ID;
+
+/// Lifetime points of a run that are needed to be able to rebirth at all.
+pub const PRESTIGE_POINTS_THRESHOLD: u64 = 100_000;
+/// The seeds of a run grow with the square root of the lifetime points, so that pushing a single
+/// run forever is never better than rebirthing.
+const PRESTIGE_SEEDS_EXPONENT: f64 = 0.5;
+
+const PPS_MULTIPLIER_PER_LEVEL: f64 = 0.25;
+const GRASS_MULTIPLIER_PER_LEVEL: f64 = 1.0;
+const CAP_MULTIPLIER_PER_LEVEL: f64 = 0.5;
+
+/// The part of the save that survives rebirths.
+#[derive(Serialize, Deserialize, Clone, Default, PartialEq, Debug)]
+pub struct Prestige {
+    pub seeds: BigNumber,
+    pub rebirths: u32,
+    pub pps_level: u32,
+    pub grass_level: u32,
+    pub cap_level: u32,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum PrestigeUpgrade {
+    Pps,
+    Grass,
+    Cap,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum PrestigeAction {
+    Rebirth,
+    Upgrade(PrestigeUpgrade),
+}
+
+#[derive(Event)]
+pub struct PrestigeActionEvent {
+    pub action: PrestigeAction,
+}
+
+impl PrestigeUpgrade {
+    pub fn string(self) -> &'static str {
+        match self {
+            Self::Pps => "Points/s",
+            Self::Grass => "Grass Points",
+            Self::Cap => "Points Cap",
+        }
+    }
+}
+
+impl Prestige {
+    pub fn level(&self, upgrade: PrestigeUpgrade) -> u32 {
+        match upgrade {
+            PrestigeUpgrade::Pps => self.pps_level,
+            PrestigeUpgrade::Grass => self.grass_level,
+            PrestigeUpgrade::Cap => self.cap_level,
+        }
+    }
+
+    fn level_mut(&mut self, upgrade: PrestigeUpgrade) -> &mut u32 {
+        match upgrade {
+            PrestigeUpgrade::Pps => &mut self.pps_level,
+            PrestigeUpgrade::Grass => &mut self.grass_level,
+            PrestigeUpgrade::Cap => &mut self.cap_level,
+        }
+    }
+
+    /// Every level costs one seed more than the previous one.
+    pub fn upgrade_cost(&self, upgrade: PrestigeUpgrade) -> BigNumber {
+        BigNumber::from(self.level(upgrade) as u64 + 1)
+    }
+
+    pub fn multiplier(&self, upgrade: PrestigeUpgrade) -> BigNumber {
+        let per_level = match upgrade {
+            PrestigeUpgrade::Pps => PPS_MULTIPLIER_PER_LEVEL,
+            PrestigeUpgrade::Grass => GRASS_MULTIPLIER_PER_LEVEL,
+            PrestigeUpgrade::Cap => CAP_MULTIPLIER_PER_LEVEL,
+        };
+        BigNumber::from(1.0 + per_level * self.level(upgrade) as f64)
+    }
+
+    /// Returns `false` if the player can't afford the upgrade.
+    pub fn buy_upgrade(&mut self, upgrade: PrestigeUpgrade) -> bool {
+        let cost = self.upgrade_cost(upgrade);
+        if self.seeds < cost {
+            return false;
+        }
+
+        self.seeds -= cost;
+        *self.level_mut(upgrade) += 1;
+        true
+    }
+}
+
+/// Seeds the player would get for rebirthing right now.
+pub fn seeds_for_run(lifetime_points: BigNumber) -> BigNumber {
+    let threshold = BigNumber::from(PRESTIGE_POINTS_THRESHOLD);
+    if lifetime_points < threshold {
+        return BigNumber::ZERO;
+    }
+
+    let ratio = BigNumber::from(lifetime_points.ratio(threshold));
+    ratio.powf(PRESTIGE_SEEDS_EXPONENT).floor()
+}
+
+pub fn can_rebirth(core: &ProgressionCore) -> bool {
+    !seeds_for_run(core.lifetime_points).is_zero()
+}
+
+/// Start a fresh run, only the prestige, the achievements, the statistics, the world seed, the time
+/// of day and the settings are kept.
+pub fn rebirth(core: &mut ProgressionCore, map_data: &mut MapData) {
+    let mut prestige = core.prestige.clone();
+    prestige.seeds += seeds_for_run(core.lifetime_points);
+    prestige.rebirths += 1;
+
+    *core = ProgressionCore {
+        previous_timestamp: core.previous_timestamp,
+        world_seed: core.world_seed,
+        day_night: core.day_night.clone(),
+        prestige,
+        player: core.player,
+        music: core.music,
+        sound: core.sound,
+        achievements: core.achievements.clone(),
+        statistics: core.statistics.clone(),
+        ..default()
+    };
+    *map_data = MapData::with_flora_registry(map_data.flora_registry().clone());
+    core.update_points_cap(map_data);
+}
+
+fn handle_prestige_actions(
+    mut core: ResMut<ProgressionCore>,
+    mut map_data: ResMut<MapData>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut ev_prestige_action: EventReader<PrestigeActionEvent>,
+) {
+    for ev in ev_prestige_action.read() {
+        match ev.action {
+            PrestigeAction::Rebirth => {
+                if !can_rebirth(&core) {
+                    continue;
+                }
+                rebirth(&mut core, &mut map_data);
+                next_state.set(GameState::Gaming);
+            }
+            PrestigeAction::Upgrade(upgrade) => {
+                core.prestige.buy_upgrade(upgrade);
+            }
+        }
+    }
+}
+
+pub struct MapPrestigePlugin;
+
+impl Plugin for MapPrestigePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<PrestigeActionEvent>().add_systems(
+            Update,
+            handle_prestige_actions
+                .in_set(ProgressionSystemSet)
+                .run_if(resource_exists::<ProgressionCore>.and(resource_exists::<MapData>)),
+        );
+    }
+}
+
+#[test]
+fn validate_seeds_for_run() {
+    assert!(seeds_for_run(BigNumber::ZERO).is_zero());
+    assert!(seeds_for_run((PRESTIGE_POINTS_THRESHOLD - 1).into()).is_zero());
+    assert_eq!(
+        seeds_for_run(PRESTIGE_POINTS_THRESHOLD.into()),
+        BigNumber::from(1u64)
+    );
+    assert_eq!(
+        seeds_for_run((PRESTIGE_POINTS_THRESHOLD * 100).into()),
+        BigNumber::from(10u64)
+    );
+}
+
+#[test]
+fn validate_rebirth_keeps_prestige() {
+    let mut core = ProgressionCore::default();
+    let mut map_data = MapData::default();
+    core.points = 5_000u64.into();
+    core.lifetime_points = (PRESTIGE_POINTS_THRESHOLD * 4).into();
+    let corn = map_data.flora_registry().get("corn").unwrap();
+    core.flora = vec![10; map_data.flora_registry().len()];
+    core.unplaced_structures.insert(SILO_ID.to_string(), 3);
+    let silo = map_data.structure_by_id(SILO_ID).unwrap();
+    map_data.place_structure(30, 30, silo);
+    core.music = false;
+    core.prestige.pps_level = 2;
+    core.statistics.grass_cut = 7;
+
+    rebirth(&mut core, &mut map_data);
+
+    assert!(core.points.is_zero());
+    assert!(core.lifetime_points.is_zero());
+    assert_eq!(core.flora_count(corn), 0);
+    assert!(core.unplaced_structures.is_empty());
+    assert_eq!(map_data.placed_structures().count(), 0);
+    assert!(!core.music);
+    assert_eq!(core.prestige.seeds, BigNumber::from(2u64));
+    assert_eq!(core.prestige.rebirths, 1);
+    assert_eq!(core.prestige.pps_level, 2);
+    assert_eq!(core.statistics.grass_cut, 7);
+}
+
+#[test]
+fn validate_prestige_upgrades() {
+    let mut prestige = Prestige {
+        seeds: 3u64.into(),
+        ..default()
+    };
+
+    assert!(prestige.buy_upgrade(PrestigeUpgrade::Grass));
+    assert!(prestige.buy_upgrade(PrestigeUpgrade::Grass));
+    assert!(!prestige.buy_upgrade(PrestigeUpgrade::Grass));
+    assert_eq!(prestige.grass_level, 2);
+    assert!(prestige.seeds.is_zero());
+    assert_eq!(
+        prestige.multiplier(PrestigeUpgrade::Grass),
+        BigNumber::from(3u64)
+    );
+    assert_eq!(
+        prestige.multiplier(PrestigeUpgrade::Pps),
+        BigNumber::from(1u64)
+    );
+}
@@ -0,0 +1,263 @@
+//! Random events.
+//!
+//! Every couple of minutes an event from `random_events.json` starts, picked by its weight. While
+//! it runs its modifier applies on top of the upgrades, see `ProgressionCore::apply_modifiers`.
+//! Only one event runs at a time.
+//!
+//! The running event and the time until the next one are saved in the core. If the game is closed
+//! mid-event, the event still applies for the rest of its duration during the offline progression
+//! and ends there, see `offline`. No new events start while the game is closed.
+
+use std::{ops::Range, time::Duration};
+
+use bevy::{prelude::*, time::common_conditions::on_timer};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    upgrades::{Modifier, ModifierTarget},
+    MapData, ProgressionCore, ProgressionSystemSet, RngStream, WorldRng,
+};
+
+#[cfg(test)]
+use super::{compute_current_pps, offline::apply_offline_progression};
+
+/// A fresh garden gets a bit of time before anything happens.
+const FIRST_EVENT_DELAY: u64 = 180;
+/// The seconds between the end of an event and the start of the next one.
+const EVENT_INTERVAL: Range<u64> = 180..480;
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct RandomEventData {
+    pub id: String,
+    pub name: String,
+    /// How likely the event is compared to the others.
+    weight: u32,
+    /// Seconds the event runs for.
+    pub duration: u64,
+    pub target: ModifierTarget,
+    pub modifier: Modifier,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct ActiveEvent {
+    pub id: String,
+    /// Seconds until the event ends.
+    pub remaining: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct RandomEvents {
+    pub active: Option<ActiveEvent>,
+    /// Seconds until the next event starts, only counts down while no event runs.
+    next_event_in: u64,
+}
+
+impl Default for RandomEvents {
+    fn default() -> Self {
+        Self {
+            active: None,
+            next_event_in: FIRST_EVENT_DELAY,
+        }
+    }
+}
+
+pub fn build_random_event_data(raw: &str) -> Vec<RandomEventData> {
+    match serde_json::from_str(raw) {
+        Ok(data) => data,
+        Err(err) => {
+            error!("failed to parse random event data, {}", err);
+            Vec::new()
+        }
+    }
+}
+
+/// Pick an event by the weights, `None` if there is nothing to pick.
+fn pick_event<'a>(
+    event_data: &'a [RandomEventData],
+    rng: &mut impl Rng,
+) -> Option<&'a RandomEventData> {
+    let total: u32 = event_data.iter().map(|event| event.weight).sum();
+    if total == 0 {
+        return None;
+    }
+
+    let mut roll = rng.random_range(0..total);
+    event_data.iter().find(|event| {
+        if roll < event.weight {
+            return true;
+        }
+        roll -= event.weight;
+        false
+    })
+}
+
+impl MapData {
+    pub fn random_event_data(&self) -> &[RandomEventData] {
+        &self.random_event_data
+    }
+
+    pub fn random_event_by_id(&self, id: &str) -> Option<&RandomEventData> {
+        self.random_event_data.iter().find(|event| event.id == id)
+    }
+}
+
+impl RandomEvents {
+    fn end_event(&mut self, rng: &mut impl Rng) {
+        self.active = None;
+        self.next_event_in = rng.random_range(EVENT_INTERVAL);
+    }
+
+    /// Let a second pass, an event starts once it's time. Returns whether an event started.
+    fn tick(&mut self, event_data: &[RandomEventData], rng: &mut impl Rng) -> bool {
+        if let Some(active) = &mut self.active {
+            active.remaining = active.remaining.saturating_sub(1);
+            if active.remaining == 0 {
+                self.end_event(rng);
+            }
+            return false;
+        }
+
+        self.next_event_in = self.next_event_in.saturating_sub(1);
+        if self.next_event_in > 0 {
+            return false;
+        }
+
+        let Some(event) = pick_event(event_data, rng) else {
+            self.next_event_in = rng.random_range(EVENT_INTERVAL);
+            return false;
+        };
+        self.active = Some(ActiveEvent {
+            id: event.id.clone(),
+            remaining: event.duration,
+        });
+        true
+    }
+
+    /// Let the running event go on for the given seconds of offline time. Nothing new starts.
+    pub(super) fn expire(&mut self, seconds: u64, rng: &mut impl Rng) {
+        let Some(active) = &mut self.active else {
+            return;
+        };
+
+        active.remaining = active.remaining.saturating_sub(seconds);
+        if active.remaining == 0 {
+            self.end_event(rng);
+        }
+    }
+}
+
+impl ProgressionCore {
+    /// The data of the running event, `None` if there is none (or it isn't known anymore).
+    pub fn active_event<'a>(&self, map_data: &'a MapData) -> Option<&'a RandomEventData> {
+        let active = self.random_events.active.as_ref()?;
+        map_data.random_event_by_id(&active.id)
+    }
+}
+
+/// An event just started.
+#[derive(Event)]
+pub struct RandomEventStarted {
+    pub id: String,
+}
+
+fn tick_random_events(
+    mut core: ResMut<ProgressionCore>,
+    map_data: Res<MapData>,
+    mut world_rng: ResMut<WorldRng>,
+    mut ev_random_event_started: EventWriter<RandomEventStarted>,
+) {
+    let rng = world_rng.stream(RngStream::Events);
+    if !core.random_events.tick(map_data.random_event_data(), rng) {
+        return;
+    }
+
+    if let Some(active) = &core.random_events.active {
+        ev_random_event_started.write(RandomEventStarted {
+            id: active.id.clone(),
+        });
+    }
+}
+
+pub struct MapRandomEventsPlugin;
+
+impl Plugin for MapRandomEventsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<RandomEventStarted>().add_systems(
+            Update,
+            tick_random_events
+                .run_if(on_timer(Duration::from_secs(1)))
+                .in_set(ProgressionSystemSet)
+                .run_if(
+                    resource_exists::<ProgressionCore>
+                        .and(resource_exists::<MapData>)
+                        .and(resource_exists::<WorldRng>),
+                ),
+        );
+    }
+}
+
+#[test]
+fn validate_random_event_json_data() {
+    use crate::assets::RANDOM_EVENT_DATA_CORE;
+
+    let data: Vec<RandomEventData> = serde_json::from_str(RANDOM_EVENT_DATA_CORE).unwrap();
+    assert!(!data.is_empty());
+    for (i, event) in data.iter().enumerate() {
+        assert!(data[..i].iter().all(|other| other.id != event.id));
+        assert!(event.weight > 0, "{}", event.id);
+        assert!(event.duration > 0, "{}", event.id);
+    }
+}
+
+#[test]
+fn validate_random_event_lifecycle() {
+    let map_data = MapData::default();
+    let mut world_rng = WorldRng::new(0);
+    let rng = world_rng.stream(RngStream::Events);
+    let mut events = RandomEvents::default();
+
+    for _ in 1..FIRST_EVENT_DELAY {
+        assert!(!events.tick(map_data.random_event_data(), rng));
+    }
+    assert!(events.tick(map_data.random_event_data(), rng));
+    let active = events.active.clone().unwrap();
+    let duration = map_data.random_event_by_id(&active.id).unwrap().duration;
+    assert_eq!(active.remaining, duration);
+
+    for _ in 0..duration {
+        assert!(!events.tick(map_data.random_event_data(), rng));
+    }
+    assert_eq!(events.active, None);
+    assert!(EVENT_INTERVAL.contains(&events.next_event_in));
+
+    // Nothing to pick from, the garden just waits for the next interval.
+    events.next_event_in = 1;
+    assert!(!events.tick(&[], rng));
+    assert_eq!(events.active, None);
+    assert!(EVENT_INTERVAL.contains(&events.next_event_in));
+}
+
+#[test]
+fn validate_random_event_resolves_offline() {
+    let mut core = ProgressionCore::default();
+    let mut map_data = MapData::default();
+    let mut world_rng = WorldRng::new(0);
+    let raddish = map_data.flora_registry().get("raddish").unwrap();
+    core.add_flora(raddish);
+    core.points_cap = 1_000_000u64.into();
+    let pps = compute_current_pps(&core, &map_data);
+
+    core.random_events.active = Some(ActiveEvent {
+        id: "bumper_harvest".to_string(),
+        remaining: 60,
+    });
+    assert_eq!(compute_current_pps(&core, &map_data), pps * 2u64.into());
+
+    // A minute with the bumper harvest, then another one without.
+    let report = apply_offline_progression(&mut core, &mut map_data, &mut world_rng, 120);
+    assert_eq!(report.points, pps * 180u64.into());
+    assert_eq!(report.expired_event.as_deref(), Some("Bumper Harvest"));
+    assert_eq!(core.random_events.active, None);
+    assert_eq!(compute_current_pps(&core, &map_data), pps);
+}
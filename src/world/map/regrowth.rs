@@ -0,0 +1,143 @@
+//! Tall grass growing back.
+//!
+//! Empty ground on the owned land grows tall grass again after a random delay, no matter whether
+//! the grass was cut or a plant was removed. The delay counts down faster with a higher regrowth
+//! rate, which upgrades can raise, and it also counts down while the game is closed, see
+//! `offline`. Regrown cells change their chunk, so `grass` spawns the blades just like it does for
+//! new land.
+
+use std::{collections::HashSet, ops::Range, time::Duration};
+
+use bevy::{prelude::*, time::common_conditions::on_timer};
+use rand::Rng;
+
+use super::{
+    cell_to_indices, indices_to_cell, upgrades::ModifierTarget, MapData, ProgressionCore,
+    ProgressionSystemSet, RngStream, WorldRng, EMPTY_CELL_VALUE, TALL_GRASS_CELL_VALUE,
+};
+
+#[cfg(test)]
+use super::{FloraRegistry, EMPTY_CELL_ID};
+
+/// The seconds it takes empty ground to grow tall grass at the base regrowth rate.
+pub const GRASS_REGROWTH_DELAY: Range<f64> = 120.0..300.0;
+
+impl ProgressionCore {
+    /// How many seconds of regrowth pass per second.
+    pub fn grass_regrowth_rate(&self, map_data: &MapData) -> f64 {
+        self.apply_modifiers(map_data, ModifierTarget::GrassRegrowth, 1u64.into())
+            .to_f64()
+    }
+}
+
+impl MapData {
+    /// Let the empty ground of the owned land grow for the given seconds of regrowth, returns how
+    /// many cells grew tall grass again. Cells that just became empty get their random delay first.
+    pub(super) fn advance_regrowth(&mut self, seconds: f64, rng: &mut impl Rng) -> usize {
+        let empty: Vec<(usize, usize)> = self
+            .grid
+            .chunks()
+            .into_iter()
+            .flat_map(|chunk| self.grid.cells_in_chunk(chunk))
+            .filter(|(_, value)| *value == EMPTY_CELL_VALUE)
+            .filter_map(|(cell, _)| cell_to_indices(cell))
+            .filter(|(x, y)| self.owns_cell(*x, *y))
+            .collect();
+
+        // Cells that got built on in the meantime don't grow anything.
+        let empty_set: HashSet<(usize, usize)> = empty.iter().copied().collect();
+        self.regrowth.retain(|cell, _| empty_set.contains(cell));
+
+        let mut regrown = 0;
+        for (x, y) in empty {
+            let remaining = self
+                .regrowth
+                .entry((x, y))
+                .or_insert_with(|| rng.random_range(GRASS_REGROWTH_DELAY));
+            *remaining -= seconds;
+
+            if *remaining > 0.0 {
+                continue;
+            }
+
+            self.regrowth.remove(&(x, y));
+            self.grid.set(indices_to_cell(x, y), TALL_GRASS_CELL_VALUE);
+            regrown += 1;
+        }
+        regrown
+    }
+}
+
+fn regrow_grass(
+    core: Res<ProgressionCore>,
+    mut map_data: ResMut<MapData>,
+    mut world_rng: ResMut<WorldRng>,
+) {
+    let rate = core.grass_regrowth_rate(&map_data);
+    map_data.advance_regrowth(rate, world_rng.stream(RngStream::Regrowth));
+}
+
+pub struct MapRegrowthPlugin;
+
+impl Plugin for MapRegrowthPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            regrow_grass
+                .run_if(on_timer(Duration::from_secs(1)))
+                .in_set(ProgressionSystemSet)
+                .run_if(
+                    resource_exists::<ProgressionCore>
+                        .and(resource_exists::<MapData>)
+                        .and(resource_exists::<WorldRng>),
+                ),
+        );
+    }
+}
+
+#[test]
+fn validate_grass_regrowth() {
+    let mut map_data = MapData::default();
+    let mut world_rng = WorldRng::new(0);
+    let rng = world_rng.stream(RngStream::Regrowth);
+    let corn = map_data.flora_registry().get("corn").unwrap();
+    let (x, y) = map_data.pos_to_grid_indices(Vec2::ZERO).unwrap();
+    map_data.set_tall_grass_cell_value_to_empty(map_data.grid_indices_to_pos(x, y));
+    map_data.set_grid_index(x + 1, y, EMPTY_CELL_VALUE);
+    map_data.set_grid_index(x + 2, y, corn.cell_value());
+    // Not owned, so it stays empty.
+    map_data.set_grid_index(0, 0, EMPTY_CELL_VALUE);
+    assert!(!map_data.owns_cell(0, 0));
+
+    let stamp = map_data.grid.latest_stamp();
+    assert_eq!(map_data.advance_regrowth(1.0, rng), 0);
+    assert_eq!(map_data.regrowth.len(), 2);
+    // Counting down doesn't change any chunk, so nothing has to be serialized again.
+    assert!(map_data.grid.changed_since(stamp).is_empty());
+    assert!(map_data
+        .regrowth
+        .values()
+        .all(|remaining| GRASS_REGROWTH_DELAY.contains(&(remaining + 1.0))));
+
+    // The delay survives saving and loading.
+    let raw = map_data.to_string();
+    let (cells, regrowth) = raw.rsplit_once('#').unwrap();
+    assert!(cells.contains(&format!("{},{}:{}", x, y, EMPTY_CELL_ID)));
+    assert!(regrowth.contains(&format!("{},{}:", x, y)));
+    let loaded = MapData::from_str(&raw, FloraRegistry::default()).unwrap();
+    assert_eq!(loaded.grid, map_data.grid);
+    assert_eq!(
+        loaded.regrowth.keys().collect::<HashSet<_>>(),
+        map_data.regrowth.keys().collect::<HashSet<_>>()
+    );
+    assert_eq!(loaded.to_string(), raw);
+
+    let stamp = map_data.grid.latest_stamp();
+    assert_eq!(map_data.advance_regrowth(GRASS_REGROWTH_DELAY.end, rng), 2);
+    assert_eq!(map_data.grid_index(x, y), Some(TALL_GRASS_CELL_VALUE));
+    assert_eq!(map_data.grid_index(x + 1, y), Some(TALL_GRASS_CELL_VALUE));
+    assert_eq!(map_data.grid_index(x + 2, y), Some(corn.cell_value()));
+    assert_eq!(map_data.grid_index(0, 0), Some(EMPTY_CELL_VALUE));
+    assert!(map_data.regrowth.is_empty());
+    assert!(!map_data.grid.changed_since(stamp).is_empty());
+}
@@ -0,0 +1,281 @@
+//! Moving placed flora.
+//!
+//! The move tool picks up the plant under the cursor and turns into a `Blueprint` that carries it.
+//! Nothing in the `MapData` changes until the plant is dropped on a free spot, so cancelling the
+//! move simply leaves the plant where it was.
+
+use bevy::prelude::*;
+
+use crate::{
+    player::{GamingInput, Player},
+    BachelorBuild, GameAssets,
+};
+
+use super::{
+    building::{spawn_blueprint, spawn_cursor_selector, CursorTool},
+    Blueprint, BlueprintItem, BuildingSystemSet, Flora, MapData, ProgressionSystemSet, ZLevel,
+    EMPTY_CELL_VALUE, TALL_GRASS_CELL_VALUE,
+};
+
+#[cfg(test)]
+use super::MAP_SIZE;
+
+/// Picks up the plant that gets clicked.
+#[derive(Component)]
+struct MovePicker;
+
+#[derive(Event)]
+pub struct FloraMoved {
+    /// Position of the anchor cell the flora was placed at.
+    pub from: Vec2,
+    /// Position of the anchor cell the flora is placed at now.
+    pub to: Vec2,
+}
+
+impl MapData {
+    /// Whether the flora that covers `from` can be moved so that its anchor is at `to`. The full
+    /// footprint must be free, except for the cells the flora itself occupies right now.
+    pub(super) fn can_move_flora(&self, from: (usize, usize), to: (usize, usize)) -> bool {
+        let Some(anchor) = self.anchor_of(from.0, from.1) else {
+            return false;
+        };
+        let Some(flora) = self.flora_at(from.0, from.1) else {
+            return false;
+        };
+        let (x_size, y_size) = self.flora_data(flora).size_on_grid();
+
+        for inner_x in 0..x_size {
+            for inner_y in 0..y_size {
+                let (x, y) = (to.0 + inner_x, to.1 + inner_y);
+                if !self.owns_cell(x, y) {
+                    return false;
+                }
+
+                let value = self.grid_index(x, y);
+                if self.anchor_of(x, y) != Some(anchor)
+                    && value != Some(EMPTY_CELL_VALUE)
+                    && value != Some(TALL_GRASS_CELL_VALUE)
+                {
+                    return false;
+                }
+            }
+        }
+        true
+    }
+
+    /// Move the flora that covers `from` so that its anchor is at `to`, either all of its cells
+    /// move or nothing changes.
+    fn move_flora(&mut self, from: (usize, usize), to: (usize, usize)) -> Option<Flora> {
+        if !self.can_move_flora(from, to) {
+            return None;
+        }
+
+        let (anchor, flora) = self.clear_flora(from.0, from.1)?;
+        self.place_flora(to.0, to.1, flora);
+        if let Some(age) = self.growth_ages.remove(&anchor) {
+            self.growth_ages.insert(to, age);
+        }
+        Some(flora)
+    }
+}
+
+fn toggle_move_picker(
+    mut commands: Commands,
+    assets: Res<GameAssets>,
+    gaming_input: Res<GamingInput>,
+    bachelor_build: Res<BachelorBuild>,
+    q_pickers: Query<Entity, With<MovePicker>>,
+    q_cursor_tools: Query<Entity, With<CursorTool>>,
+) {
+    if !gaming_input.move_flora || !bachelor_build.with_building {
+        return;
+    }
+
+    let was_picking = !q_pickers.is_empty();
+    for entity in &q_cursor_tools {
+        commands.entity(entity).despawn();
+    }
+    if was_picking {
+        return;
+    }
+
+    let root = commands
+        .spawn((
+            CursorTool,
+            MovePicker,
+            Visibility::Inherited,
+            Transform::from_xyz(0.0, 0.0, ZLevel::TopUi.value()),
+        ))
+        .id();
+    spawn_cursor_selector(&mut commands, &assets, root, (1, 1));
+}
+
+fn move_picker(
+    gaming_input: Res<GamingInput>,
+    map_data: Res<MapData>,
+    mut q_picker: Query<&mut Transform, With<MovePicker>>,
+) {
+    let Ok(mut transform) = q_picker.single_mut() else {
+        return;
+    };
+
+    let pos = map_data.cell_to_pos(map_data.pos_to_cell(gaming_input.mouse_world_coords));
+    transform.translation.x = pos.x;
+    transform.translation.y = pos.y;
+}
+
+fn update_picker_color(
+    map_data: Res<MapData>,
+    q_picker: Query<(&Transform, &Children), With<MovePicker>>,
+    mut q_sprites: Query<&mut Sprite>,
+) {
+    let Ok((transform, children)) = q_picker.single() else {
+        return;
+    };
+
+    let has_flora = map_data
+        .pos_to_grid_indices(transform.translation.xy())
+        .is_some_and(|(x, y)| map_data.flora_at(x, y).is_some());
+    for child in children {
+        if let Ok(mut sprite) = q_sprites.get_mut(*child) {
+            sprite.color = if has_flora {
+                Color::WHITE
+            } else {
+                Color::WHITE.with_alpha(0.5)
+            };
+        }
+    }
+}
+
+fn pick_up_flora(
+    mut commands: Commands,
+    assets: Res<GameAssets>,
+    map_data: Res<MapData>,
+    gaming_input: Res<GamingInput>,
+    q_player: Query<&Player>,
+    q_picker: Query<(Entity, &Transform), With<MovePicker>>,
+) {
+    if !gaming_input.confirm {
+        return;
+    }
+
+    let Ok(player) = q_player.single() else {
+        return;
+    };
+    if player.is_over_ui {
+        return;
+    }
+
+    let Ok((entity, transform)) = q_picker.single() else {
+        return;
+    };
+
+    let Some((x, y)) = map_data.pos_to_grid_indices(transform.translation.xy()) else {
+        return;
+    };
+    let Some((anchor_x, anchor_y)) = map_data.anchor_of(x, y) else {
+        return;
+    };
+    let Some(flora) = map_data.flora_at(x, y) else {
+        return;
+    };
+
+    commands.entity(entity).despawn();
+    spawn_blueprint(
+        &mut commands,
+        &assets,
+        map_data.flora_data(flora).size_on_grid(),
+        Blueprint {
+            item: BlueprintItem::Flora(flora),
+            fits_at_pos: false,
+            moving_from: Some(map_data.grid_indices_to_pos(anchor_x, anchor_y)),
+        },
+    );
+}
+
+fn drop_flora(
+    mut commands: Commands,
+    mut map_data: ResMut<MapData>,
+    gaming_input: Res<GamingInput>,
+    q_player: Query<&Player>,
+    q_blueprint: Query<(Entity, &Transform, &Blueprint)>,
+    mut ev_flora_moved: EventWriter<FloraMoved>,
+) {
+    if !gaming_input.confirm {
+        return;
+    }
+
+    let Ok(player) = q_player.single() else {
+        return;
+    };
+    if player.is_over_ui {
+        return;
+    }
+
+    let Ok((entity, transform, blueprint)) = q_blueprint.single() else {
+        return;
+    };
+    let Some(from) = blueprint.moving_from else {
+        return;
+    };
+
+    let to = transform.translation.xy();
+    let (Some(from_indices), Some(to_indices)) = (
+        map_data.pos_to_grid_indices(from),
+        map_data.pos_to_grid_indices(to),
+    ) else {
+        return;
+    };
+    if map_data.move_flora(from_indices, to_indices).is_none() {
+        return;
+    }
+
+    commands.entity(entity).despawn();
+    ev_flora_moved.write(FloraMoved { from, to });
+}
+
+pub struct MapRelocatePlugin;
+
+impl Plugin for MapRelocatePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<FloraMoved>().add_systems(
+            Update,
+            (
+                toggle_move_picker
+                    .run_if(resource_exists::<GameAssets>.and(resource_exists::<BachelorBuild>)),
+                move_picker.run_if(resource_exists::<MapData>),
+                // Drop before picking up, otherwise the same click would do both.
+                drop_flora.run_if(resource_exists::<MapData>),
+                pick_up_flora.run_if(resource_exists::<GameAssets>.and(resource_exists::<MapData>)),
+                update_picker_color.run_if(resource_exists::<MapData>),
+            )
+                .chain()
+                .after(BuildingSystemSet)
+                .after(ProgressionSystemSet),
+        );
+    }
+}
+
+#[test]
+fn validate_move_flora_checks_full_footprint() {
+    let mut map_data = MapData::default();
+    let corn = map_data.flora_registry().get("corn").unwrap();
+    let onion = map_data.flora_registry().get("onion").unwrap();
+    map_data.set_grid_index(25, 25, corn.cell_value());
+    map_data.set_grid_index(27, 25, onion.cell_value());
+    map_data.growth_ages.insert((25, 25), 42);
+
+    // Occupied, not owned and out of the grid.
+    assert!(!map_data.can_move_flora((25, 25), (27, 25)));
+    assert!(!map_data.can_move_flora((25, 25), (0, 0)));
+    assert!(!map_data.can_move_flora((25, 25), (MAP_SIZE, 25)));
+    assert!(!map_data.can_move_flora((26, 26), (28, 28)));
+    assert_eq!(map_data.move_flora((25, 25), (27, 25)), None);
+    assert_eq!(map_data.flora_at(25, 25), Some(corn));
+
+    assert_eq!(map_data.move_flora((25, 25), (26, 25)), Some(corn));
+    assert_eq!(map_data.grid_index(25, 25), Some(EMPTY_CELL_VALUE));
+    assert_eq!(map_data.flora_at(26, 25), Some(corn));
+    assert_eq!(map_data.growth_ages.get(&(26, 25)), Some(&42));
+    assert!(!map_data.growth_ages.contains_key(&(25, 25)));
+}
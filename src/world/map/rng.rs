@@ -0,0 +1,123 @@
+//! Seeded randomness.
+//!
+//! Every garden has a world seed which is saved in the progression core. All the randomness of the
+//! game goes through `GameRng` streams derived from it, one stream per use, so that shuffling the
+//! music doesn't change where the grass grows. The grass of a cell only depends on the seed and the
+//! cell, no matter when or in which order the cells grow.
+//!
+//! The streams start over on every load, which makes a bug report with the save reproducible.
+
+use bevy::prelude::*;
+use rand::{RngCore, SeedableRng};
+
+use crate::GameRng;
+
+use super::ProgressionCore;
+
+#[cfg(test)]
+use rand::Rng;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum RngStream {
+    /// The blades of grass on a cell, see `WorldRng::for_cell`.
+    Grass,
+    /// The delays until empty ground grows tall grass again.
+    Regrowth,
+    Music,
+    /// When random events start and which ones, see `random_events`.
+    Events,
+}
+
+const STREAMS: [RngStream; 4] = [
+    RngStream::Grass,
+    RngStream::Regrowth,
+    RngStream::Music,
+    RngStream::Events,
+];
+
+#[derive(Resource)]
+pub struct WorldRng {
+    seed: u64,
+    streams: Vec<GameRng>,
+}
+
+impl WorldRng {
+    pub fn new(seed: u64) -> Self {
+        // Jumping ahead gives every stream its own non-overlapping part of the sequence.
+        let mut rng = GameRng::seed_from_u64(seed);
+        let streams = STREAMS
+            .iter()
+            .map(|_| {
+                let stream = rng.clone();
+                rng.jump();
+                stream
+            })
+            .collect();
+        Self { seed, streams }
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn stream(&mut self, stream: RngStream) -> &mut GameRng {
+        &mut self.streams[stream as usize]
+    }
+
+    /// A generator that only depends on the seed, the stream and the cell, it doesn't advance
+    /// the stream.
+    pub fn for_cell(&self, stream: RngStream, cell: IVec2) -> GameRng {
+        let base = self.streams[stream as usize].clone().next_u64();
+        let key = ((cell.x as u32 as u64) << 32) | cell.y as u32 as u64;
+        GameRng::seed_from_u64(base ^ key)
+    }
+}
+
+/// Follow the world seed of the core, a new garden (or loaded slot) gets fresh streams.
+pub(super) fn sync_world_rng(
+    mut commands: Commands,
+    core: Res<ProgressionCore>,
+    world_rng: Option<Res<WorldRng>>,
+) {
+    if world_rng.is_some_and(|world_rng| world_rng.seed() == core.world_seed) {
+        return;
+    }
+    commands.insert_resource(WorldRng::new(core.world_seed));
+}
+
+#[test]
+fn validate_world_rng_streams() {
+    let mut first = WorldRng::new(42);
+    let mut second = WorldRng::new(42);
+
+    // Using one stream doesn't change any other.
+    let music: Vec<u32> = (0..8)
+        .map(|_| first.stream(RngStream::Music).random())
+        .collect();
+    assert_eq!(
+        first.stream(RngStream::Regrowth).random::<u64>(),
+        second.stream(RngStream::Regrowth).random::<u64>()
+    );
+    let second_music: Vec<u32> = (0..8)
+        .map(|_| second.stream(RngStream::Music).random())
+        .collect();
+    assert_eq!(music, second_music);
+
+    let cell = IVec2::new(-3, 7);
+    assert_eq!(
+        first.for_cell(RngStream::Grass, cell).next_u64(),
+        second.for_cell(RngStream::Grass, cell).next_u64()
+    );
+    assert_ne!(
+        first.for_cell(RngStream::Grass, cell).next_u64(),
+        first
+            .for_cell(RngStream::Grass, IVec2::new(7, -3))
+            .next_u64()
+    );
+    assert_ne!(
+        first.for_cell(RngStream::Grass, cell).next_u64(),
+        WorldRng::new(43)
+            .for_cell(RngStream::Grass, cell)
+            .next_u64()
+    );
+}
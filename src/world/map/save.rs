@@ -0,0 +1,1066 @@
+//! Versioned save format.
+//!
+//! Every saved piece of state is wrapped in an envelope that carries a version. When loading we
+//! run all migrations from the saved version up to the current one, so old saves get upgraded
+//! instead of being thrown away.
+//!
+//! Saves without a version (everything written before this module existed) are treated as
+//! version 0.
+//!
+//! To add a new migration, write a function that upgrades the data from the last version by one
+//! step and append it to the corresponding `*_MIGRATIONS` array. The current version is always the
+//! length of that array.
+//!
+//! Flora are always saved by their id, the flora indices depend on the order in `flora.json`.
+
+use std::{collections::HashMap, fmt};
+
+use bevy::prelude::*;
+use serde_json::{Map, Value};
+
+#[cfg(test)]
+use crate::{assets::FLORA_DATA_CORE, world::BigNumber};
+
+use super::{
+    day_night::DayNight,
+    land::{square_plots, PLOT_SIZE},
+    prestige::Prestige,
+    random_events::RandomEvents,
+    Achievements, FloraRegistry, MapData, ProgressionCore, Statistics, EMPTY_CELL_ID,
+    EMPTY_CELL_VALUE, LAND_SEPARATOR, MAP_SIZE, SILO_ID, TALL_GRASS_CELL_ID, TALL_GRASS_CELL_VALUE,
+};
+
+const MAP_DATA_VERSION_PREFIX: char = 'v';
+const MAP_DATA_VERSION_SEPARATOR: char = '|';
+
+/// The size of the garden before land could be bought.
+const LEGACY_MAP_SIZE: usize = 30;
+
+/// The order of the old `Flora` enum, saves before the flora ids stored the flora by this index.
+const LEGACY_FLORA_IDS: [&str; 6] = ["potatoe", "raddish", "carrot", "corn", "pumpkin", "onion"];
+
+/// The fields of the core that hold one value per flora.
+const CORE_FLORA_FIELDS: [&[&str]; 3] = [
+    &["flora"],
+    &["statistics", "flora_bought"],
+    &["statistics", "flora_points"],
+];
+
+type CoreMigration = fn(Value) -> Result<Value, SaveError>;
+type MapDataMigration = fn(&str) -> Result<String, SaveError>;
+
+/// Index `i` migrates from version `i` to version `i + 1`.
+const CORE_MIGRATIONS: [CoreMigration; 12] = [
+    migrate_core_v0_to_v1,
+    migrate_core_v1_to_v2,
+    migrate_core_v2_to_v3,
+    migrate_core_v3_to_v4,
+    migrate_core_v4_to_v5,
+    migrate_core_v5_to_v6,
+    migrate_core_v6_to_v7,
+    migrate_core_v7_to_v8,
+    migrate_core_v8_to_v9,
+    migrate_core_v9_to_v10,
+    migrate_core_v10_to_v11,
+    migrate_core_v11_to_v12,
+];
+/// Index `i` migrates from version `i` to version `i + 1`.
+const MAP_DATA_MIGRATIONS: [MapDataMigration; 5] = [
+    migrate_map_data_v0_to_v1,
+    migrate_map_data_v1_to_v2,
+    migrate_map_data_v2_to_v3,
+    migrate_map_data_v3_to_v4,
+    migrate_map_data_v4_to_v5,
+];
+
+pub const PROGRESSION_CORE_VERSION: u32 = CORE_MIGRATIONS.len() as u32;
+pub const MAP_DATA_VERSION: u32 = MAP_DATA_MIGRATIONS.len() as u32;
+
+#[derive(Debug, PartialEq)]
+pub enum SaveError {
+    /// The save data couldn't be parsed at all.
+    Malformed(String),
+    /// The save was written by a newer version of the game.
+    UnsupportedVersion(u32),
+    /// The checksum doesn't match the data, it was either corrupted or edited by hand.
+    ChecksumMismatch,
+}
+
+impl fmt::Display for SaveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SaveError::Malformed(msg) => write!(f, "malformed save data: {}", msg),
+            SaveError::UnsupportedVersion(version) => {
+                write!(f, "save data has unsupported version: {}", version)
+            }
+            SaveError::ChecksumMismatch => write!(f, "save data checksum doesn't match"),
+        }
+    }
+}
+
+fn run_core_migrations(mut value: Value, version: u32) -> Result<Value, SaveError> {
+    if version > PROGRESSION_CORE_VERSION {
+        return Err(SaveError::UnsupportedVersion(version));
+    }
+
+    for migration in &CORE_MIGRATIONS[version as usize..] {
+        value = migration(value)?;
+    }
+    Ok(value)
+}
+
+fn run_map_data_migrations(raw: &str, version: u32) -> Result<String, SaveError> {
+    if version > MAP_DATA_VERSION {
+        return Err(SaveError::UnsupportedVersion(version));
+    }
+
+    let mut data = raw.to_string();
+    for migration in &MAP_DATA_MIGRATIONS[version as usize..] {
+        data = migration(&data)?;
+    }
+    Ok(data)
+}
+
+/// Split the versioned envelope `{"version": u32, "data": {..}}` into its parts.
+/// Anything that isn't an envelope is a legacy save of version 0.
+fn split_core_envelope(value: Value) -> Result<(u32, Value), SaveError> {
+    let Value::Object(mut object) = value else {
+        return Err(SaveError::Malformed(
+            "progression core is not a json object".to_string(),
+        ));
+    };
+
+    if !object.contains_key("version") || !object.contains_key("data") {
+        return Ok((0, Value::Object(object)));
+    }
+
+    let version = object
+        .remove("version")
+        .and_then(|v| v.as_u64())
+        .ok_or_else(|| SaveError::Malformed("version is not a number".to_string()))?;
+    let data = object.remove("data").unwrap_or_default();
+
+    Ok((version as u32, data))
+}
+
+/// Split `v<version>|<payload>` into its parts. Strings without the prefix are legacy saves of
+/// version 0.
+fn split_map_data_envelope(raw: &str) -> Result<(u32, &str), SaveError> {
+    let Some(rest) = raw.strip_prefix(MAP_DATA_VERSION_PREFIX) else {
+        return Ok((0, raw));
+    };
+    let Some((version, payload)) = rest.split_once(MAP_DATA_VERSION_SEPARATOR) else {
+        return Err(SaveError::Malformed(
+            "map data version is missing separator".to_string(),
+        ));
+    };
+    let version = version
+        .parse::<u32>()
+        .map_err(|_| SaveError::Malformed(format!("invalid map data version: {}", version)))?;
+    Ok((version, payload))
+}
+
+fn flora_field<'a>(object: &'a mut Value, path: &[&str]) -> Option<&'a mut Value> {
+    path.iter()
+        .try_fold(object, |value, key| value.get_mut(*key))
+}
+
+/// Turn `[a, b, ..]`, indexed by the flora index, into `{"<id>": a, ..}`.
+fn flora_array_to_object<'a>(value: &mut Value, id: impl Fn(usize) -> Option<&'a str>) {
+    let Value::Array(array) = value.take() else {
+        return;
+    };
+
+    let mut object = Map::new();
+    for (index, entry) in array.into_iter().enumerate() {
+        let Some(id) = id(index) else {
+            warn!("dropping value of unknown flora index: {}", index);
+            continue;
+        };
+        object.insert(id.to_string(), entry);
+    }
+    *value = Value::Object(object);
+}
+
+/// The inverse of `flora_array_to_object`, values of flora that don't exist anymore are dropped.
+fn flora_object_to_array(value: &mut Value, flora_registry: &FloraRegistry) {
+    let Value::Object(object) = value.take() else {
+        return;
+    };
+
+    let mut array = vec![Value::from(0); flora_registry.len()];
+    for (id, entry) in object {
+        let Some(flora) = flora_registry.get(&id) else {
+            warn!("dropping value of unknown flora: {}", id);
+            continue;
+        };
+        array[flora.index()] = entry;
+    }
+    *value = Value::Array(array);
+}
+
+pub fn serialize_progression_core(
+    core: &ProgressionCore,
+    flora_registry: &FloraRegistry,
+) -> String {
+    let mut data = serde_json::to_value(core).unwrap_or_default();
+    for path in CORE_FLORA_FIELDS {
+        if let Some(field) = flora_field(&mut data, path) {
+            flora_array_to_object(field, |index| {
+                flora_registry
+                    .by_index(index)
+                    .map(|flora| flora_registry.id(flora))
+            });
+        }
+    }
+
+    let mut envelope = Map::new();
+    envelope.insert("version".to_string(), PROGRESSION_CORE_VERSION.into());
+    envelope.insert("data".to_string(), data);
+    Value::Object(envelope).to_string()
+}
+
+pub fn deserialize_progression_core(
+    raw: &str,
+    flora_registry: &FloraRegistry,
+) -> Result<ProgressionCore, SaveError> {
+    if raw.trim().is_empty() {
+        return Ok(ProgressionCore::new_garden());
+    }
+
+    let value: Value =
+        serde_json::from_str(raw).map_err(|err| SaveError::Malformed(err.to_string()))?;
+    let (version, data) = split_core_envelope(value)?;
+    let mut data = run_core_migrations(data, version)?;
+    for path in CORE_FLORA_FIELDS {
+        if let Some(field) = flora_field(&mut data, path) {
+            flora_object_to_array(field, flora_registry);
+        }
+    }
+
+    serde_json::from_value(data).map_err(|err| SaveError::Malformed(err.to_string()))
+}
+
+fn map_data_envelope(payload: &str) -> String {
+    format!(
+        "{}{}{}{}",
+        MAP_DATA_VERSION_PREFIX, MAP_DATA_VERSION, MAP_DATA_VERSION_SEPARATOR, payload
+    )
+}
+
+#[cfg(test)]
+pub fn serialize_map_data(map_data: &MapData) -> String {
+    map_data_envelope(&map_data.to_string())
+}
+
+/// Keeps the serialized chunks of the map data around, so that saving only serializes the chunks
+/// that changed since the last save.
+#[derive(Default)]
+pub struct SerializedChunks {
+    stamp: u64,
+    chunks: HashMap<IVec2, String>,
+}
+
+impl SerializedChunks {
+    /// Same as `serialize_map_data`.
+    pub fn serialize_map_data(&mut self, map_data: &MapData) -> String {
+        let stamp = map_data.grid.latest_stamp();
+        self.chunks
+            .retain(|chunk, _| map_data.grid.is_chunk_loaded(*chunk));
+        for chunk in map_data.grid.changed_since(self.stamp) {
+            self.chunks.insert(chunk, map_data.serialize_chunk(chunk));
+        }
+        self.stamp = stamp;
+
+        let chunks = map_data.grid.chunks();
+        let payload = map_data.serialize_with_chunks(
+            chunks
+                .iter()
+                .filter_map(|chunk| self.chunks.get(chunk).map(String::as_str)),
+        );
+        map_data_envelope(&payload)
+    }
+}
+
+pub fn deserialize_map_data(
+    raw: &str,
+    flora_registry: &FloraRegistry,
+) -> Result<MapData, SaveError> {
+    let (version, payload) = split_map_data_envelope(raw.trim())?;
+    let payload = run_map_data_migrations(payload, version)?;
+    MapData::from_str(&payload, flora_registry.clone())
+}
+
+/// Legacy saves were written by several builds, some of which didn't know about every field yet,
+/// so fill in whatever is missing with the defaults of the v1 core. They are spelled out on
+/// purpose, the current defaults would change what this migration does with every new field.
+fn migrate_core_v0_to_v1(value: Value) -> Result<Value, SaveError> {
+    let Value::Object(mut object) = value else {
+        return Err(SaveError::Malformed(
+            "progression core is not a json object".to_string(),
+        ));
+    };
+
+    let Value::Object(defaults) = serde_json::json!({
+        "previous_timestamp": 0,
+        "offline_progression": 0,
+        "points": 0,
+        "points_cap": 800,
+        "pps": 0,
+        "flora": vec![0; LEGACY_FLORA_IDS.len()],
+        "silos": 0,
+        "player": [0.0, 0.0],
+        "music": true,
+        "sound": true,
+    }) else {
+        unreachable!("the defaults are a json object");
+    };
+
+    for (key, default_value) in defaults {
+        object.entry(key).or_insert(default_value);
+    }
+
+    Ok(Value::Object(object))
+}
+
+/// Points, the points cap and the pps used to be plain integers, they are now `BigNumber`s which
+/// are stored as strings.
+fn migrate_core_v1_to_v2(value: Value) -> Result<Value, SaveError> {
+    let Value::Object(mut object) = value else {
+        return Err(SaveError::Malformed(
+            "progression core is not a json object".to_string(),
+        ));
+    };
+
+    for key in ["offline_progression", "points", "points_cap", "pps"] {
+        let Some(number) = object.get(key).and_then(|v| v.as_u64()) else {
+            return Err(SaveError::Malformed(format!(
+                "progression core field is not a number: {}",
+                key
+            )));
+        };
+        object.insert(key.to_string(), Value::String(number.to_string()));
+    }
+
+    Ok(Value::Object(object))
+}
+
+/// Adds the prestige section. Nobody could rebirth before, so the points of the current run are
+/// everything they ever earned (or at least the best guess we have).
+fn migrate_core_v2_to_v3(value: Value) -> Result<Value, SaveError> {
+    let Value::Object(mut object) = value else {
+        return Err(SaveError::Malformed(
+            "progression core is not a json object".to_string(),
+        ));
+    };
+
+    let points = object.get("points").cloned().unwrap_or_default();
+    let prestige = serde_json::to_value(Prestige::default())
+        .map_err(|err| SaveError::Malformed(err.to_string()))?;
+    object.insert("lifetime_points".to_string(), points);
+    object.insert("prestige".to_string(), prestige);
+
+    Ok(Value::Object(object))
+}
+
+/// Adds the (empty) list of bought upgrades.
+fn migrate_core_v3_to_v4(value: Value) -> Result<Value, SaveError> {
+    let Value::Object(mut object) = value else {
+        return Err(SaveError::Malformed(
+            "progression core is not a json object".to_string(),
+        ));
+    };
+
+    object.insert("upgrades".to_string(), Value::Array(Vec::new()));
+
+    Ok(Value::Object(object))
+}
+
+/// The offline progression is computed on load and never saved anymore, see `offline`.
+fn migrate_core_v4_to_v5(value: Value) -> Result<Value, SaveError> {
+    let Value::Object(mut object) = value else {
+        return Err(SaveError::Malformed(
+            "progression core is not a json object".to_string(),
+        ));
+    };
+
+    object.remove("offline_progression");
+
+    Ok(Value::Object(object))
+}
+
+/// Adds the achievements, nothing was tracked before so everything starts at zero.
+fn migrate_core_v5_to_v6(value: Value) -> Result<Value, SaveError> {
+    let Value::Object(mut object) = value else {
+        return Err(SaveError::Malformed(
+            "progression core is not a json object".to_string(),
+        ));
+    };
+
+    let achievements = serde_json::to_value(Achievements::default())
+        .map_err(|err| SaveError::Malformed(err.to_string()))?;
+    object.insert("achievements".to_string(), achievements);
+
+    Ok(Value::Object(object))
+}
+
+/// Moves the counters of the achievements into the new statistics ledger. Everything else wasn't
+/// tracked before, the best guess for the points earned are the points of the current run.
+fn migrate_core_v6_to_v7(value: Value) -> Result<Value, SaveError> {
+    let Value::Object(mut object) = value else {
+        return Err(SaveError::Malformed(
+            "progression core is not a json object".to_string(),
+        ));
+    };
+
+    let mut statistics = serde_json::to_value(Statistics::default())
+        .map_err(|err| SaveError::Malformed(err.to_string()))?;
+    if let Some(points) = object.get("lifetime_points") {
+        statistics["points_earned"] = points.clone();
+    }
+    if let Some(Value::Object(achievements)) = object.get_mut("achievements") {
+        for key in ["grass_cut", "slashes", "items_bought", "times_capped"] {
+            if let Some(counter) = achievements.remove(key) {
+                statistics[key] = counter;
+            }
+        }
+    }
+    object.insert("statistics".to_string(), statistics);
+
+    Ok(Value::Object(object))
+}
+
+/// The flora used to be stored by the index of the old `Flora` enum, they are now stored by id.
+fn migrate_core_v7_to_v8(value: Value) -> Result<Value, SaveError> {
+    let Value::Object(object) = value else {
+        return Err(SaveError::Malformed(
+            "progression core is not a json object".to_string(),
+        ));
+    };
+
+    let mut value = Value::Object(object);
+    for path in CORE_FLORA_FIELDS {
+        if let Some(field) = flora_field(&mut value, path) {
+            flora_array_to_object(field, |index| LEGACY_FLORA_IDS.get(index).copied());
+        }
+    }
+
+    Ok(value)
+}
+
+/// Silos used to be a plain counter, now they are structures on the grid. The counted silos go
+/// into the inventory, the player can place them wherever they like.
+fn migrate_core_v8_to_v9(value: Value) -> Result<Value, SaveError> {
+    let Value::Object(mut object) = value else {
+        return Err(SaveError::Malformed(
+            "progression core is not a json object".to_string(),
+        ));
+    };
+
+    let silos = object
+        .remove("silos")
+        .and_then(|silos| silos.as_u64())
+        .unwrap_or_default();
+    let mut unplaced_structures = Map::new();
+    if silos > 0 {
+        unplaced_structures.insert(SILO_ID.to_string(), silos.into());
+    }
+    object.insert(
+        "unplaced_structures".to_string(),
+        Value::Object(unplaced_structures),
+    );
+
+    Ok(Value::Object(object))
+}
+
+/// Gardens from before the world seed get a random one, their grass just grows a bit differently
+/// after loading.
+fn migrate_core_v9_to_v10(value: Value) -> Result<Value, SaveError> {
+    let Value::Object(mut object) = value else {
+        return Err(SaveError::Malformed(
+            "progression core is not a json object".to_string(),
+        ));
+    };
+
+    object
+        .entry("world_seed")
+        .or_insert_with(|| rand::random::<u64>().into());
+
+    Ok(Value::Object(object))
+}
+
+/// Adds the random events, the first one comes a little while after loading.
+fn migrate_core_v10_to_v11(value: Value) -> Result<Value, SaveError> {
+    let Value::Object(mut object) = value else {
+        return Err(SaveError::Malformed(
+            "progression core is not a json object".to_string(),
+        ));
+    };
+
+    let random_events = serde_json::to_value(RandomEvents::default())
+        .map_err(|err| SaveError::Malformed(err.to_string()))?;
+    object.entry("random_events").or_insert(random_events);
+
+    Ok(Value::Object(object))
+}
+
+/// Adds the time of day, older gardens start at the beginning of the first day.
+fn migrate_core_v11_to_v12(value: Value) -> Result<Value, SaveError> {
+    let Value::Object(mut object) = value else {
+        return Err(SaveError::Malformed(
+            "progression core is not a json object".to_string(),
+        ));
+    };
+
+    let day_night = serde_json::to_value(DayNight::default())
+        .map_err(|err| SaveError::Malformed(err.to_string()))?;
+    object.entry("day_night").or_insert(day_night);
+
+    Ok(Value::Object(object))
+}
+
+/// The cell format didn't change, but legacy saves reset the entire grid on a single bad cell.
+/// Drop cells we can't make sense of instead.
+fn migrate_map_data_v0_to_v1(raw: &str) -> Result<String, SaveError> {
+    let mut cells = Vec::new();
+
+    for raw_cell in raw.split(';').filter(|s| !s.is_empty()) {
+        let Some((xy, value)) = raw_cell.split_once(':') else {
+            warn!("dropping malformed legacy map data cell: {}", raw_cell);
+            continue;
+        };
+        let Some((x, y)) = xy.split_once(',') else {
+            warn!("dropping malformed legacy map data cell: {}", raw_cell);
+            continue;
+        };
+        let (Ok(x), Ok(y), Ok(value)) =
+            (x.parse::<usize>(), y.parse::<usize>(), value.parse::<u16>())
+        else {
+            warn!("dropping malformed legacy map data cell: {}", raw_cell);
+            continue;
+        };
+
+        if x >= LEGACY_MAP_SIZE || y >= LEGACY_MAP_SIZE {
+            warn!("dropping out of bounds legacy map data cell: {}", raw_cell);
+            continue;
+        }
+
+        let is_known_value = value == EMPTY_CELL_VALUE
+            || value == TALL_GRASS_CELL_VALUE
+            || (value as usize) < LEGACY_FLORA_IDS.len();
+        if !is_known_value {
+            warn!(
+                "dropping legacy map data cell with unknown value: {}",
+                raw_cell
+            );
+            continue;
+        }
+
+        cells.push(format!("{},{}:{}", x, y, value));
+    }
+
+    Ok(cells.join(";"))
+}
+
+/// Cells of growing crops got an optional age (`x,y:value:age`). Old cells simply have none, so
+/// the data stays the same, the version only keeps older builds from loading the new format.
+fn migrate_map_data_v1_to_v2(raw: &str) -> Result<String, SaveError> {
+    Ok(raw.to_string())
+}
+
+/// Cells store the flora id (or `TALL_GRASS_CELL_ID`) instead of the index of the old `Flora`
+/// enum. Empty cells were never written by the v2 format, so whatever is left of them is dropped.
+fn migrate_map_data_v2_to_v3(raw: &str) -> Result<String, SaveError> {
+    let mut cells = Vec::new();
+
+    for raw_cell in raw.split(';').filter(|s| !s.is_empty()) {
+        let malformed = || SaveError::Malformed(format!("invalid cell: {}", raw_cell));
+
+        let (xy, rest) = raw_cell.split_once(':').ok_or_else(malformed)?;
+        let (value, age) = match rest.split_once(':') {
+            Some((value, age)) => (value, Some(age)),
+            None => (rest, None),
+        };
+        let value = value.parse::<u16>().map_err(|_| malformed())?;
+
+        let id = if value == TALL_GRASS_CELL_VALUE {
+            TALL_GRASS_CELL_ID
+        } else if let Some(id) = LEGACY_FLORA_IDS.get(value as usize) {
+            id
+        } else {
+            continue;
+        };
+
+        match age {
+            Some(age) => cells.push(format!("{}:{}:{}", xy, id, age)),
+            None => cells.push(format!("{}:{}", xy, id)),
+        }
+    }
+
+    Ok(cells.join(";"))
+}
+
+/// The fixed garden became the middle of a bigger grid where land has to be bought. The cells
+/// move along with it and all of the old garden is owned.
+fn migrate_map_data_v3_to_v4(raw: &str) -> Result<String, SaveError> {
+    let offset = (MAP_SIZE - LEGACY_MAP_SIZE) / 2;
+    debug_assert_eq!(offset % PLOT_SIZE, 0);
+    debug_assert_eq!(LEGACY_MAP_SIZE % PLOT_SIZE, 0);
+
+    let plots: Vec<String> =
+        square_plots(offset / PLOT_SIZE..(offset + LEGACY_MAP_SIZE) / PLOT_SIZE)
+            .into_iter()
+            .map(|(x, y)| format!("{},{}", x, y))
+            .collect();
+
+    let mut cells = Vec::new();
+    for raw_cell in raw.split(';').filter(|s| !s.is_empty()) {
+        let malformed = || SaveError::Malformed(format!("invalid cell: {}", raw_cell));
+
+        let (xy, rest) = raw_cell.split_once(':').ok_or_else(malformed)?;
+        let (x, y) = xy.split_once(',').ok_or_else(malformed)?;
+        let x = x.parse::<usize>().map_err(|_| malformed())?;
+        let y = y.parse::<usize>().map_err(|_| malformed())?;
+
+        cells.push(format!("{},{}:{}", x + offset, y + offset, rest));
+    }
+
+    Ok(format!(
+        "{}{}{}",
+        plots.join(";"),
+        LAND_SEPARATOR,
+        cells.join(";")
+    ))
+}
+
+/// The seconds until empty ground grows grass again moved from the empty cells
+/// (`x,y:empty:remaining`) to their own part after the cells, so they can change without
+/// touching the chunks.
+fn migrate_map_data_v4_to_v5(raw: &str) -> Result<String, SaveError> {
+    let (plots, raw_cells) = raw
+        .split_once(LAND_SEPARATOR)
+        .ok_or_else(|| SaveError::Malformed(format!("missing '{}' in map data", LAND_SEPARATOR)))?;
+
+    let mut cells = Vec::new();
+    let mut regrowth = Vec::new();
+    for raw_cell in raw_cells.split(';').filter(|s| !s.is_empty()) {
+        let malformed = || SaveError::Malformed(format!("invalid cell: {}", raw_cell));
+
+        let (xy, rest) = raw_cell.split_once(':').ok_or_else(malformed)?;
+        match rest.split_once(':') {
+            Some((EMPTY_CELL_ID, remaining)) => {
+                cells.push(format!("{}:{}", xy, EMPTY_CELL_ID));
+                regrowth.push(format!("{}:{}", xy, remaining));
+            }
+            _ => cells.push(raw_cell.to_string()),
+        }
+    }
+
+    Ok(format!(
+        "{}{}{}{}{}",
+        plots,
+        LAND_SEPARATOR,
+        cells.join(";"),
+        LAND_SEPARATOR,
+        regrowth.join(";")
+    ))
+}
+
+#[test]
+fn validate_core_migration_v0_to_v1_fills_missing_fields() {
+    let legacy = serde_json::json!({
+        "previous_timestamp": 10,
+        "offline_progression": 0,
+        "points": 42,
+        "points_cap": 800,
+        "pps": 3,
+        "flora": [1, 0, 0, 0, 0],
+        "player": [0.0, 0.0],
+    });
+
+    let migrated = migrate_core_v0_to_v1(legacy).unwrap();
+    assert_eq!(migrated["points"], 42);
+    assert_eq!(migrated["silos"], 0);
+    assert_eq!(migrated["music"], true);
+    assert_eq!(migrated["sound"], true);
+    // Only the fields of the v1 core, everything newer is up to the later migrations.
+    assert_eq!(migrated.get("world_seed"), None);
+    assert_eq!(migrated.get("unplaced_structures"), None);
+}
+
+#[test]
+fn validate_core_migration_v0_to_v1_rejects_non_objects() {
+    assert!(migrate_core_v0_to_v1(Value::Null).is_err());
+}
+
+#[test]
+fn validate_core_migration_v1_to_v2_converts_numbers() {
+    let v1 = serde_json::json!({
+        "offline_progression": 0,
+        "points": 42,
+        "points_cap": 800,
+        "pps": 3,
+    });
+
+    let migrated = migrate_core_v1_to_v2(v1).unwrap();
+    assert_eq!(migrated["points"], "42");
+    assert_eq!(migrated["points_cap"], "800");
+    assert_eq!(migrated["pps"], "3");
+
+    assert!(migrate_core_v1_to_v2(serde_json::json!({ "points": "42" })).is_err());
+}
+
+#[test]
+fn validate_core_migration_v2_to_v3_adds_prestige() {
+    let v2 = serde_json::json!({ "points": "42" });
+
+    let migrated = migrate_core_v2_to_v3(v2).unwrap();
+    assert_eq!(migrated["lifetime_points"], "42");
+    assert_eq!(migrated["prestige"]["rebirths"], 0);
+}
+
+#[test]
+fn validate_core_migration_v3_to_v4_adds_upgrades() {
+    let v3 = serde_json::json!({ "points": "42" });
+
+    let migrated = migrate_core_v3_to_v4(v3).unwrap();
+    assert_eq!(migrated["upgrades"], serde_json::json!([]));
+    assert!(migrate_core_v3_to_v4(Value::Null).is_err());
+}
+
+#[test]
+fn validate_core_migration_v4_to_v5_drops_offline_progression() {
+    let v4 = serde_json::json!({ "points": "42", "offline_progression": "7" });
+
+    let migrated = migrate_core_v4_to_v5(v4).unwrap();
+    assert_eq!(migrated, serde_json::json!({ "points": "42" }));
+}
+
+#[test]
+fn validate_core_migration_v5_to_v6_adds_achievements() {
+    let v5 = serde_json::json!({ "points": "42" });
+
+    let migrated = migrate_core_v5_to_v6(v5).unwrap();
+    assert_eq!(migrated["achievements"]["unlocked"], serde_json::json!({}));
+}
+
+#[test]
+fn validate_core_migration_v6_to_v7_moves_counters_to_statistics() {
+    let v6 = serde_json::json!({
+        "lifetime_points": "42",
+        "achievements": { "grass_cut": 12, "slashes": 3, "unlocked": { "a": 1 } },
+    });
+
+    let migrated = migrate_core_v6_to_v7(v6).unwrap();
+    assert_eq!(
+        migrated["achievements"],
+        serde_json::json!({ "unlocked": { "a": 1 } })
+    );
+    assert_eq!(migrated["statistics"]["grass_cut"], 12);
+    assert_eq!(migrated["statistics"]["slashes"], 3);
+    assert_eq!(migrated["statistics"]["items_bought"], 0);
+    assert_eq!(migrated["statistics"]["points_earned"], "42");
+}
+
+#[test]
+fn validate_core_migration_v7_to_v8_stores_flora_by_id() {
+    let v7 = serde_json::json!({
+        "flora": [1, 0, 3, 0, 0, 2],
+        "statistics": { "flora_bought": [1, 0, 3], "flora_points": ["5", "0"] },
+    });
+
+    let migrated = migrate_core_v7_to_v8(v7).unwrap();
+    assert_eq!(migrated["flora"]["potatoe"], 1);
+    assert_eq!(migrated["flora"]["carrot"], 3);
+    assert_eq!(migrated["flora"]["onion"], 2);
+    assert_eq!(migrated["statistics"]["flora_bought"]["carrot"], 3);
+    assert_eq!(migrated["statistics"]["flora_points"]["potatoe"], "5");
+}
+
+#[test]
+fn validate_core_migration_v8_to_v9_moves_silos_to_inventory() {
+    let v8 = serde_json::json!({ "points": "42", "silos": 3 });
+
+    let migrated = migrate_core_v8_to_v9(v8).unwrap();
+    assert_eq!(
+        migrated,
+        serde_json::json!({ "points": "42", "unplaced_structures": { "silo": 3 } })
+    );
+
+    let migrated = migrate_core_v8_to_v9(serde_json::json!({ "silos": 0 })).unwrap();
+    assert_eq!(migrated["unplaced_structures"], serde_json::json!({}));
+}
+
+#[test]
+fn validate_core_migration_v9_to_v10_adds_world_seed() {
+    let migrated = migrate_core_v9_to_v10(serde_json::json!({ "points": "42" })).unwrap();
+    assert_eq!(migrated["points"], "42");
+    assert!(migrated["world_seed"].is_u64());
+
+    // Cores that went through the first migration already have one.
+    let migrated = migrate_core_v9_to_v10(serde_json::json!({ "world_seed": 7 })).unwrap();
+    assert_eq!(migrated["world_seed"], 7);
+}
+
+#[test]
+fn validate_core_migration_v10_to_v11_adds_random_events() {
+    let migrated = migrate_core_v10_to_v11(serde_json::json!({ "points": "42" })).unwrap();
+    assert_eq!(migrated["points"], "42");
+    let random_events: RandomEvents =
+        serde_json::from_value(migrated["random_events"].clone()).unwrap();
+    assert_eq!(random_events, RandomEvents::default());
+}
+
+#[test]
+fn validate_core_migration_v11_to_v12_adds_day_night() {
+    let migrated = migrate_core_v11_to_v12(serde_json::json!({ "points": "42" })).unwrap();
+    assert_eq!(migrated["points"], "42");
+    let day_night: DayNight = serde_json::from_value(migrated["day_night"].clone()).unwrap();
+    assert_eq!(day_night, DayNight::default());
+}
+
+#[test]
+fn validate_map_data_migration_v0_to_v1_drops_bad_cells() {
+    let legacy = format!(
+        "0,0:1;1,1:{};banana;2,2:{};{},0:1;3,3:9000",
+        TALL_GRASS_CELL_VALUE, EMPTY_CELL_VALUE, LEGACY_MAP_SIZE
+    );
+    let migrated = migrate_map_data_v0_to_v1(&legacy).unwrap();
+    assert_eq!(
+        migrated,
+        format!(
+            "0,0:1;1,1:{};2,2:{}",
+            TALL_GRASS_CELL_VALUE, EMPTY_CELL_VALUE
+        )
+    );
+}
+
+#[test]
+fn validate_legacy_progression_core_is_migrated() {
+    let flora_registry = FloraRegistry::default();
+    let legacy = r#"{"previous_timestamp":10,"offline_progression":0,"points":42,"points_cap":800,"pps":3,"flora":[1,0],"silos":1,"player":[1.0,2.0],"music":false,"sound":true}"#;
+    let core = deserialize_progression_core(legacy, &flora_registry).unwrap();
+
+    assert_eq!(core.points, 42u64.into());
+    assert_eq!(core.unplaced_structures.get(SILO_ID), Some(&1));
+    assert!(!core.music);
+    assert_eq!(core.flora.len(), flora_registry.len());
+    assert_eq!(core.flora_count(flora_registry.get("potatoe").unwrap()), 1);
+}
+
+#[test]
+fn validate_v0_progression_core_runs_all_migrations() {
+    let v0 = serde_json::json!({
+        "previous_timestamp": 10,
+        "points": 42,
+        "pps": 3,
+        "flora": [0, 2, 0, 0, 0, 1],
+        "silos": 2,
+    });
+
+    let migrated = run_core_migrations(v0.clone(), 0).unwrap();
+    assert_eq!(migrated["points"], "42");
+    assert_eq!(migrated["points_cap"], "800");
+    assert_eq!(migrated["lifetime_points"], "42");
+    assert_eq!(migrated["statistics"]["points_earned"], "42");
+    assert_eq!(migrated["flora"]["raddish"], 2);
+    assert_eq!(migrated["flora"]["onion"], 1);
+    assert_eq!(migrated["unplaced_structures"][SILO_ID], 2);
+    assert!(migrated["world_seed"].is_u64());
+    assert_eq!(migrated.get("offline_progression"), None);
+    assert_eq!(migrated.get("silos"), None);
+
+    let flora_registry = FloraRegistry::default();
+    let core = deserialize_progression_core(&v0.to_string(), &flora_registry).unwrap();
+    assert_eq!(core.points, 42u64.into());
+    assert_eq!(core.flora_count(flora_registry.get("raddish").unwrap()), 2);
+    assert_eq!(core.random_events, RandomEvents::default());
+    assert_eq!(core.day_night, DayNight::default());
+}
+
+#[test]
+fn validate_progression_core_round_trip() {
+    let flora_registry = FloraRegistry::default();
+    let corn = flora_registry.get("corn").unwrap();
+    let mut core = ProgressionCore::default();
+    core.points = BigNumber::new(1.234, 100);
+    core.flora = vec![0; flora_registry.len()];
+    core.flora[corn.index()] = 5;
+    core.statistics.add_points_of_flora(corn, 42u64.into());
+    core.day_night.day = 3;
+
+    let raw = serialize_progression_core(&core, &flora_registry);
+    assert!(raw.contains(r#""corn":5"#));
+
+    let loaded = deserialize_progression_core(&raw, &flora_registry).unwrap();
+    assert_eq!(loaded.points, core.points);
+    assert_eq!(loaded.world_seed, core.world_seed);
+    assert_eq!(loaded.day_night, core.day_night);
+    assert_eq!(loaded.flora, core.flora);
+    assert_eq!(loaded.statistics.flora_points(corn), 42u64.into());
+}
+
+#[test]
+fn validate_progression_core_survives_reordered_flora() {
+    let flora_registry = FloraRegistry::default();
+    let mut core = ProgressionCore::default();
+    core.flora = vec![0; flora_registry.len()];
+    core.flora[flora_registry.get("corn").unwrap().index()] = 5;
+    let raw = serialize_progression_core(&core, &flora_registry);
+
+    let mut reordered: Value = serde_json::from_str(FLORA_DATA_CORE).unwrap();
+    reordered.as_array_mut().unwrap().reverse();
+    let reordered = FloraRegistry::from_json(reordered.to_string().as_bytes()).unwrap();
+
+    let loaded = deserialize_progression_core(&raw, &reordered).unwrap();
+    assert_eq!(loaded.flora_count(reordered.get("corn").unwrap()), 5);
+    assert_eq!(loaded.flora_count(reordered.get("onion").unwrap()), 0);
+}
+
+#[test]
+fn validate_future_versions_are_rejected() {
+    let raw = format!(
+        r#"{{"version":{},"data":{{}}}}"#,
+        PROGRESSION_CORE_VERSION + 1
+    );
+    assert_eq!(
+        deserialize_progression_core(&raw, &FloraRegistry::default()).err(),
+        Some(SaveError::UnsupportedVersion(PROGRESSION_CORE_VERSION + 1))
+    );
+
+    let raw = format!("v{}|0,0:1", MAP_DATA_VERSION + 1);
+    assert!(deserialize_map_data(&raw, &FloraRegistry::default()).is_err());
+}
+
+#[test]
+fn validate_legacy_map_data_is_migrated() {
+    let flora_registry = FloraRegistry::default();
+    let map_data = deserialize_map_data("0,0:1;1,0:2", &flora_registry).unwrap();
+    // The old garden sits in the middle of the grid now.
+    let offset = (MAP_SIZE - LEGACY_MAP_SIZE) / 2;
+    assert_eq!(
+        map_data.flora_at(offset, offset),
+        flora_registry.get("raddish")
+    );
+    assert_eq!(
+        map_data.flora_at(offset + 1, offset),
+        flora_registry.get("carrot")
+    );
+    assert_eq!(
+        map_data.grid_index(offset + 2, offset),
+        Some(TALL_GRASS_CELL_VALUE)
+    );
+}
+
+#[test]
+fn validate_map_data_migration_v1_to_v2_keeps_cells() {
+    let flora_registry = FloraRegistry::default();
+    let v1 = format!("0,0:1;1,1:{}", TALL_GRASS_CELL_VALUE);
+    assert_eq!(migrate_map_data_v1_to_v2(&v1).unwrap(), v1);
+
+    let map_data = deserialize_map_data(&format!("v1|{}", v1), &flora_registry).unwrap();
+    let offset = (MAP_SIZE - LEGACY_MAP_SIZE) / 2;
+    assert_eq!(
+        map_data.flora_at(offset, offset),
+        flora_registry.get("raddish")
+    );
+    assert!(map_data.growth_ages.is_empty());
+}
+
+#[test]
+fn validate_map_data_migration_v2_to_v3_stores_flora_by_id() {
+    let v2 = format!(
+        "0,0:3:42;1,1:{};2,2:{};3,3:5",
+        TALL_GRASS_CELL_VALUE, EMPTY_CELL_VALUE
+    );
+    assert_eq!(
+        migrate_map_data_v2_to_v3(&v2).unwrap(),
+        "0,0:corn:42;1,1:tall_grass;3,3:onion"
+    );
+    assert!(migrate_map_data_v2_to_v3("0,0:corn").is_err());
+}
+
+#[test]
+fn validate_map_data_migration_v3_to_v4_owns_the_old_garden() {
+    let migrated = migrate_map_data_v3_to_v4("0,0:corn:42;29,3:tall_grass").unwrap();
+    let (plots, cells) = migrated.split_once(LAND_SEPARATOR).unwrap();
+    assert_eq!(plots.split(';').count(), 36);
+    assert!(plots.starts_with("3,3;3,4;"));
+    assert_eq!(cells, "15,15:corn:42;44,18:tall_grass");
+
+    let map_data = deserialize_map_data("v3|0,0:corn:42", &FloraRegistry::default()).unwrap();
+    let corn = map_data.flora_registry().get("corn");
+    assert_eq!(map_data.flora_at(15, 15), corn);
+    assert_eq!(map_data.growth_ages[&(15, 15)], 42);
+    assert!(map_data.owns_cell(15, 15));
+    assert!(map_data.owns_cell(44, 44));
+    assert!(!map_data.owns_cell(14, 15));
+    assert!(!map_data.owns_cell(45, 44));
+}
+
+#[test]
+fn validate_map_data_migration_v4_to_v5_moves_the_regrowth_out_of_the_cells() {
+    let migrated =
+        migrate_map_data_v4_to_v5("15,15#16,15:empty:42;17,15:corn:7;18,15:empty").unwrap();
+    assert_eq!(
+        migrated,
+        "15,15#16,15:empty;17,15:corn:7;18,15:empty#16,15:42"
+    );
+
+    let map_data =
+        deserialize_map_data("v4|3,3#16,15:empty:42", &FloraRegistry::default()).unwrap();
+    assert_eq!(map_data.grid_index(16, 15), Some(EMPTY_CELL_VALUE));
+    assert_eq!(map_data.regrowth[&(16, 15)], 42.0);
+    assert!(migrate_map_data_v4_to_v5("16,15:empty:42").is_err());
+}
+
+#[test]
+fn validate_map_data_round_trip() {
+    let mut map_data = MapData::default();
+    let corn = map_data.flora_registry().get("corn").unwrap();
+    let onion = map_data.flora_registry().get("onion").unwrap();
+    map_data.set_grid_index(3, 4, corn.cell_value());
+    map_data.set_grid_index(5, 6, onion.cell_value());
+    map_data.growth_ages.insert((3, 4), 42);
+
+    let raw = serialize_map_data(&map_data);
+    assert!(raw.starts_with(&format!("v{}|", MAP_DATA_VERSION)));
+    assert!(raw.contains("3,4:corn:42"));
+
+    let loaded = deserialize_map_data(&raw, map_data.flora_registry()).unwrap();
+    assert_eq!(loaded.grid, map_data.grid);
+    assert_eq!(loaded.growth_ages, map_data.growth_ages);
+}
+
+#[test]
+fn validate_serialized_chunks_only_serialize_changes() {
+    let mut map_data = MapData::default();
+    let corn = map_data.flora_registry().get("corn").unwrap();
+    let mut serialized_chunks = SerializedChunks::default();
+    assert_eq!(
+        serialized_chunks.serialize_map_data(&map_data),
+        serialize_map_data(&map_data)
+    );
+
+    map_data.set_grid_index(3, 4, corn.cell_value());
+    map_data.set_tall_grass_cell_value_to_empty(map_data.grid_indices_to_pos(40, 50));
+    assert_eq!(
+        map_data.grid.changed_since(serialized_chunks.stamp).len(),
+        2
+    );
+    assert_eq!(
+        serialized_chunks.serialize_map_data(&map_data),
+        serialize_map_data(&map_data)
+    );
+    assert!(map_data
+        .grid
+        .changed_since(serialized_chunks.stamp)
+        .is_empty());
+
+    // A fresh map is newer than everything that was serialized before.
+    let fresh = MapData::default();
+    assert_eq!(
+        serialized_chunks.serialize_map_data(&fresh),
+        serialize_map_data(&fresh)
+    );
+}
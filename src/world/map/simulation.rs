@@ -0,0 +1,599 @@
+//! Headless economy simulation, run with `simulate [options]` from the command line.
+//!
+//! The simulation buys flora and silos with one of the purchase strategies and reports how long
+//! the runs took to reach the milestones. It goes through the same economy code as the game
+//! (costs, modifiers, the day/night cycle, the points cap, rebirths). Random events aren't
+//! simulated, no event ever starts. The player is assumed to cut a steady amount of grass and to
+//! never place anything, just like without the building mode.
+//!
+//! `simulation_progress/main.py` plots the `--series` output.
+
+use std::{fmt, fs, str::FromStr};
+
+use rand::{Rng, SeedableRng};
+use serde::Serialize;
+
+use crate::{assets::FLORA_DATA_CORE, world::BigNumber, GameRng};
+
+use super::{
+    compute_current_pps, prestige, upgrades::ModifierTarget, Flora, FloraRegistry, MapData,
+    PrestigeUpgrade, ProgressionCore, Structure, SILO_ID, TALL_GRASS_POINTS,
+};
+
+const USAGE: &str = "usage: simulate [--flora <file>] [--duration <seconds>] [--tick <seconds>] \
+[--strategy greedy-roi|cheapest-first|save-for-silo|random] [--runs <n>] [--seed <u64>] \
+[--grass <cuts per second>] [--format csv|json] [--out <file>] [--series <file>]";
+
+/// Guards against strategies that would keep buying forever within a single tick.
+const MAX_PURCHASES_PER_TICK: usize = 1_000;
+/// Total points earned, over all runs.
+const POINT_MILESTONES: [u64; 6] = [1_000, 10_000, 100_000, 1_000_000, 10_000_000, 100_000_000];
+/// How far the points have to be towards the cap before `SaveForSilo` stops buying flora.
+const SAVE_FOR_SILO_CAP_RATIO: f64 = 0.5;
+const REBIRTH_MILESTONE: &str = "first_rebirth";
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum StrategyKind {
+    /// The flora with the best pps per cost, silos only once the points hit the cap.
+    GreedyRoi,
+    /// Whatever is the cheapest.
+    CheapestFirst,
+    /// Like `GreedyRoi`, but once the points are halfway to the cap it saves up for the next silo.
+    SaveForSilo,
+    /// Anything that is affordable, seeded with the `--seed` (plus the index of the run).
+    Random,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum OutputFormat {
+    Csv,
+    Json,
+}
+
+#[derive(Debug)]
+struct SimulationOptions {
+    /// The flora data, the embedded `flora.json` if not given.
+    flora: Option<String>,
+    /// Seconds of a single run.
+    duration: u64,
+    /// Seconds per tick.
+    tick: u64,
+    strategy: StrategyKind,
+    runs: u32,
+    seed: u64,
+    /// Tall grass the player cuts per second.
+    grass: f64,
+    format: OutputFormat,
+    /// The milestone statistics go to stdout if not given.
+    out: Option<String>,
+    /// Write the progress of the first run to this file.
+    series: Option<String>,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Purchase {
+    Flora(Flora),
+    Structure(Structure),
+}
+
+/// The progress of a run at a single tick, the `--series` output.
+struct Sample {
+    time: u64,
+    points: BigNumber,
+    points_cap: BigNumber,
+    pps: BigNumber,
+    seeds: BigNumber,
+    rebirths: u32,
+    flora: Vec<u16>,
+}
+
+struct RunResult {
+    /// Seconds until the milestone was reached, in the order of `milestone_names`.
+    milestones: Vec<Option<u64>>,
+    samples: Vec<Sample>,
+}
+
+#[derive(Serialize, PartialEq, Debug)]
+struct MilestoneStatistics {
+    milestone: String,
+    /// How many runs reached the milestone, the times are only over those.
+    reached: u32,
+    min_seconds: Option<u64>,
+    mean_seconds: Option<f64>,
+    median_seconds: Option<u64>,
+    max_seconds: Option<u64>,
+}
+
+#[derive(Serialize)]
+struct SimulationReport {
+    strategy: String,
+    runs: u32,
+    duration: u64,
+    tick: u64,
+    milestones: Vec<MilestoneStatistics>,
+}
+
+impl FromStr for StrategyKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "greedy-roi" => Ok(Self::GreedyRoi),
+            "cheapest-first" => Ok(Self::CheapestFirst),
+            "save-for-silo" => Ok(Self::SaveForSilo),
+            "random" => Ok(Self::Random),
+            _ => Err(format!("unknown strategy: {}", s)),
+        }
+    }
+}
+
+impl fmt::Display for StrategyKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::GreedyRoi => "greedy-roi",
+            Self::CheapestFirst => "cheapest-first",
+            Self::SaveForSilo => "save-for-silo",
+            Self::Random => "random",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl Default for SimulationOptions {
+    fn default() -> Self {
+        Self {
+            flora: None,
+            duration: 4 * 60 * 60,
+            tick: 1,
+            strategy: StrategyKind::GreedyRoi,
+            runs: 1,
+            seed: 0,
+            grass: 1.0,
+            format: OutputFormat::Csv,
+            out: None,
+            series: None,
+        }
+    }
+}
+
+impl SimulationOptions {
+    /// Parse the `--name value` pairs after the `simulate` argument.
+    fn parse(args: &[String]) -> Result<Self, String> {
+        fn value<T: FromStr>(name: &str, value: Option<&String>) -> Result<T, String> {
+            let value = value.ok_or_else(|| format!("missing value for {}", name))?;
+            value
+                .parse()
+                .map_err(|_| format!("invalid value for {}: {}", name, value))
+        }
+
+        let mut options = Self::default();
+        let mut args = args.iter();
+        while let Some(name) = args.next() {
+            let next = args.next();
+            match name.as_str() {
+                "--flora" => options.flora = Some(value(name, next)?),
+                "--duration" => options.duration = value(name, next)?,
+                "--tick" => options.tick = value(name, next)?,
+                "--strategy" => options.strategy = value::<String>(name, next)?.parse()?,
+                "--runs" => options.runs = value(name, next)?,
+                "--seed" => options.seed = value(name, next)?,
+                "--grass" => options.grass = value(name, next)?,
+                "--format" => {
+                    options.format = match value::<String>(name, next)?.as_str() {
+                        "csv" => OutputFormat::Csv,
+                        "json" => OutputFormat::Json,
+                        format => return Err(format!("unknown format: {}", format)),
+                    }
+                }
+                "--out" => options.out = Some(value(name, next)?),
+                "--series" => options.series = Some(value(name, next)?),
+                _ => return Err(format!("unknown option: {}", name)),
+            }
+        }
+
+        if options.tick == 0 || options.runs == 0 || options.grass < 0.0 {
+            return Err("tick and runs must be positive, grass must not be negative".to_string());
+        }
+        Ok(options)
+    }
+}
+
+/// Everything the strategy could buy right now with its cost.
+fn affordable_purchases(core: &ProgressionCore, map_data: &MapData) -> Vec<(Purchase, BigNumber)> {
+    let mut purchases: Vec<(Purchase, BigNumber)> = map_data
+        .flora_registry()
+        .iter()
+        .map(|flora| (Purchase::Flora(flora), core.flora_cost(map_data, flora)))
+        .collect();
+    if let Some(silo) = map_data.structure_by_id(SILO_ID) {
+        purchases.push((
+            Purchase::Structure(silo),
+            core.structure_cost(map_data, silo),
+        ));
+    }
+
+    purchases.retain(|(_, cost)| core.points >= *cost);
+    purchases
+}
+
+/// The affordable flora with the most pps per point spent.
+fn best_roi_flora(purchases: &[(Purchase, BigNumber)], map_data: &MapData) -> Option<Purchase> {
+    purchases
+        .iter()
+        .filter_map(|(purchase, cost)| match purchase {
+            Purchase::Flora(flora) => {
+                Some((*purchase, map_data.flora_data(*flora).pps.ratio(*cost)))
+            }
+            Purchase::Structure(_) => None,
+        })
+        .max_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(purchase, _)| purchase)
+}
+
+fn next_purchase(
+    strategy: StrategyKind,
+    rng: &mut GameRng,
+    core: &ProgressionCore,
+    map_data: &MapData,
+) -> Option<Purchase> {
+    let purchases = affordable_purchases(core, map_data);
+    let silo = purchases
+        .iter()
+        .find(|(purchase, _)| matches!(purchase, Purchase::Structure(_)))
+        .map(|(purchase, _)| *purchase);
+
+    match strategy {
+        StrategyKind::GreedyRoi => {
+            if core.points >= core.points_cap {
+                return silo.or_else(|| best_roi_flora(&purchases, map_data));
+            }
+            best_roi_flora(&purchases, map_data)
+        }
+        StrategyKind::CheapestFirst => purchases
+            .iter()
+            .min_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal))
+            .map(|(purchase, _)| *purchase),
+        StrategyKind::SaveForSilo => {
+            if silo.is_some() {
+                return silo;
+            }
+            if core.points.ratio(core.points_cap) >= SAVE_FOR_SILO_CAP_RATIO {
+                return None;
+            }
+            best_roi_flora(&purchases, map_data)
+        }
+        StrategyKind::Random => {
+            if purchases.is_empty() {
+                return None;
+            }
+            Some(purchases[rng.random_range(0..purchases.len())].0)
+        }
+    }
+}
+
+/// Returns whether it was bought.
+fn buy(core: &mut ProgressionCore, map_data: &MapData, purchase: Purchase) -> bool {
+    match purchase {
+        Purchase::Flora(flora) => core.buy_flora(map_data, flora, 1),
+        Purchase::Structure(structure) => core.buy_structure(map_data, structure),
+    }
+}
+
+/// Rebirth once a run would give more seeds than the previous one did, then spend all seeds on
+/// the cheapest prestige upgrades.
+fn try_rebirth(core: &mut ProgressionCore, map_data: &mut MapData) -> bool {
+    let seeds = prestige::seeds_for_run(core.lifetime_points);
+    if seeds < BigNumber::from(core.prestige.rebirths as u64 + 1) {
+        return false;
+    }
+
+    prestige::rebirth(core, map_data);
+    while let Some(upgrade) = [
+        PrestigeUpgrade::Pps,
+        PrestigeUpgrade::Grass,
+        PrestigeUpgrade::Cap,
+    ]
+    .into_iter()
+    .min_by_key(|upgrade| core.prestige.level(*upgrade))
+    {
+        if !core.prestige.buy_upgrade(upgrade) {
+            break;
+        }
+    }
+    true
+}
+
+fn milestone_names() -> Vec<String> {
+    POINT_MILESTONES
+        .iter()
+        .map(|points| format!("points_{}", points))
+        .chain([REBIRTH_MILESTONE.to_string()])
+        .collect()
+}
+
+fn simulate_run(
+    options: &SimulationOptions,
+    flora_registry: &FloraRegistry,
+    seed: u64,
+    with_samples: bool,
+) -> RunResult {
+    let mut rng = GameRng::seed_from_u64(seed);
+    let mut core = ProgressionCore::default();
+    let mut map_data = MapData::with_flora_registry(flora_registry.clone());
+    let mut milestones = vec![None; POINT_MILESTONES.len() + 1];
+    let mut samples = Vec::new();
+
+    let mut time = 0;
+    while time < options.duration {
+        if try_rebirth(&mut core, &mut map_data) {
+            milestones[POINT_MILESTONES.len()].get_or_insert(time);
+        }
+
+        core.pps = compute_current_pps(&core, &map_data);
+        core.update_points_cap(&map_data);
+        let grass_points = core.apply_modifiers(
+            &map_data,
+            ModifierTarget::GrassPoints,
+            TALL_GRASS_POINTS.into(),
+        ) * (options.grass * options.tick as f64).into();
+        core.add_points(core.pps * options.tick.into() + grass_points);
+
+        for _ in 0..MAX_PURCHASES_PER_TICK {
+            let Some(purchase) = next_purchase(options.strategy, &mut rng, &core, &map_data) else {
+                break;
+            };
+            if !buy(&mut core, &map_data, purchase) {
+                break;
+            }
+        }
+        // The cap of the bought silos counts right away, not just on the next tick.
+        core.update_points_cap(&map_data);
+
+        core.day_night
+            .advance(map_data.day_phase_data(), options.tick);
+        time += options.tick;
+        for (milestone, points) in milestones.iter_mut().zip(POINT_MILESTONES) {
+            if core.statistics.points_earned >= BigNumber::from(points) {
+                milestone.get_or_insert(time);
+            }
+        }
+
+        if with_samples {
+            samples.push(Sample {
+                time,
+                points: core.points,
+                points_cap: core.points_cap,
+                pps: core.pps,
+                seeds: core.prestige.seeds,
+                rebirths: core.prestige.rebirths,
+                flora: map_data
+                    .flora_registry()
+                    .iter()
+                    .map(|flora| core.flora_count(flora))
+                    .collect(),
+            });
+        }
+    }
+
+    RunResult {
+        milestones,
+        samples,
+    }
+}
+
+fn milestone_statistics(results: &[RunResult]) -> Vec<MilestoneStatistics> {
+    milestone_names()
+        .into_iter()
+        .enumerate()
+        .map(|(index, milestone)| {
+            let mut times: Vec<u64> = results
+                .iter()
+                .filter_map(|result| result.milestones[index])
+                .collect();
+            times.sort_unstable();
+
+            let mean =
+                (!times.is_empty()).then(|| times.iter().sum::<u64>() as f64 / times.len() as f64);
+            MilestoneStatistics {
+                milestone,
+                reached: times.len() as u32,
+                min_seconds: times.first().copied(),
+                mean_seconds: mean,
+                median_seconds: times.get(times.len() / 2).copied(),
+                max_seconds: times.last().copied(),
+            }
+        })
+        .collect()
+}
+
+fn report_to_csv(report: &SimulationReport) -> String {
+    fn optional<T: ToString>(value: Option<T>) -> String {
+        value.map(|value| value.to_string()).unwrap_or_default()
+    }
+
+    let mut lines = vec![
+        "strategy,runs,milestone,reached,min_seconds,mean_seconds,median_seconds,max_seconds"
+            .to_string(),
+    ];
+    for statistics in &report.milestones {
+        lines.push(format!(
+            "{},{},{},{},{},{},{},{}",
+            report.strategy,
+            report.runs,
+            statistics.milestone,
+            statistics.reached,
+            optional(statistics.min_seconds),
+            optional(statistics.mean_seconds),
+            optional(statistics.median_seconds),
+            optional(statistics.max_seconds),
+        ));
+    }
+    lines.join("\n") + "\n"
+}
+
+fn samples_to_csv(samples: &[Sample], flora_registry: &FloraRegistry) -> String {
+    let mut header = vec!["time", "points", "points_cap", "pps", "seeds", "rebirths"];
+    header.extend(flora_registry.iter().map(|flora| flora_registry.id(flora)));
+
+    let mut lines = vec![header.join(",")];
+    for sample in samples {
+        let mut values = vec![
+            sample.time.to_string(),
+            sample.points.to_f64().to_string(),
+            sample.points_cap.to_f64().to_string(),
+            sample.pps.to_f64().to_string(),
+            sample.seeds.to_f64().to_string(),
+            sample.rebirths.to_string(),
+        ];
+        values.extend(sample.flora.iter().map(|count| count.to_string()));
+        lines.push(values.join(","));
+    }
+    lines.join("\n") + "\n"
+}
+
+fn run_simulation(options: &SimulationOptions) -> Result<(), String> {
+    let raw_flora = match &options.flora {
+        Some(path) => fs::read(path).map_err(|err| format!("failed to read {}, {}", path, err))?,
+        None => FLORA_DATA_CORE.as_bytes().to_vec(),
+    };
+    let flora_registry = FloraRegistry::from_json(&raw_flora)
+        .map_err(|err| format!("invalid flora data, {}", err))?;
+
+    let results: Vec<RunResult> = (0..options.runs)
+        .map(|run| {
+            simulate_run(
+                options,
+                &flora_registry,
+                options.seed.wrapping_add(run as u64),
+                run == 0 && options.series.is_some(),
+            )
+        })
+        .collect();
+
+    if let Some(path) = &options.series {
+        fs::write(path, samples_to_csv(&results[0].samples, &flora_registry))
+            .map_err(|err| format!("failed to write {}, {}", path, err))?;
+    }
+
+    let report = SimulationReport {
+        strategy: options.strategy.to_string(),
+        runs: options.runs,
+        duration: options.duration,
+        tick: options.tick,
+        milestones: milestone_statistics(&results),
+    };
+    let output = match options.format {
+        OutputFormat::Csv => report_to_csv(&report),
+        OutputFormat::Json => serde_json::to_string_pretty(&report)
+            .map_err(|err| format!("failed to serialize report, {}", err))?,
+    };
+
+    match &options.out {
+        Some(path) => {
+            fs::write(path, output).map_err(|err| format!("failed to write {}, {}", path, err))
+        }
+        None => {
+            println!("{}", output.trim_end());
+            Ok(())
+        }
+    }
+}
+
+/// Handle `simulate [options]` from the command line. Returns `None` if the arguments didn't
+/// contain it.
+pub fn run_simulation_command(args: &[String]) -> Option<Result<(), String>> {
+    let index = args.iter().position(|s| s == "simulate")?;
+
+    Some(
+        SimulationOptions::parse(&args[index + 1..])
+            .and_then(|options| run_simulation(&options))
+            .map_err(|err| format!("{}\n{}", err, USAGE)),
+    )
+}
+
+#[test]
+fn validate_simulation_options() {
+    let args: Vec<String> = [
+        "--duration",
+        "60",
+        "--strategy",
+        "random",
+        "--format",
+        "json",
+    ]
+    .iter()
+    .map(|s| s.to_string())
+    .collect();
+    let options = SimulationOptions::parse(&args).unwrap();
+    assert_eq!(options.duration, 60);
+    assert_eq!(options.strategy, StrategyKind::Random);
+    assert_eq!(options.format, OutputFormat::Json);
+    assert_eq!(options.tick, 1);
+
+    let invalid = |args: &[&str]| {
+        let args: Vec<String> = args.iter().map(|s| s.to_string()).collect();
+        SimulationOptions::parse(&args).is_err()
+    };
+    assert!(invalid(&["--strategy", "yolo"]));
+    assert!(invalid(&["--tick", "0"]));
+    assert!(invalid(&["--runs"]));
+    assert!(invalid(&["--banana", "1"]));
+
+    let command = |args: &[&str]| {
+        let args: Vec<String> = args.iter().map(|s| s.to_string()).collect();
+        run_simulation_command(&args)
+    };
+    assert!(command(&["idle-garden"]).is_none());
+    assert!(command(&["idle-garden", "simulate", "--tick", "0"])
+        .unwrap()
+        .is_err());
+}
+
+#[test]
+fn validate_simulation_strategies() {
+    let flora_registry = FloraRegistry::default();
+    for strategy in [
+        StrategyKind::GreedyRoi,
+        StrategyKind::CheapestFirst,
+        StrategyKind::SaveForSilo,
+        StrategyKind::Random,
+    ] {
+        let options = SimulationOptions {
+            duration: 30 * 60,
+            strategy,
+            ..Default::default()
+        };
+        let result = simulate_run(&options, &flora_registry, 7, true);
+        assert_eq!(result.samples.len(), 30 * 60);
+        // Cutting grass alone gets there, the strategy only makes it faster.
+        assert!(result.milestones[0].is_some(), "{}", strategy);
+        assert!(result
+            .samples
+            .last()
+            .unwrap()
+            .flora
+            .iter()
+            .any(|count| *count > 0));
+
+        // Runs with the same seed are the same.
+        let again = simulate_run(&options, &flora_registry, 7, false);
+        assert_eq!(again.milestones, result.milestones);
+    }
+
+    let options = SimulationOptions::default();
+    let results: Vec<RunResult> = [10, 30, 20]
+        .into_iter()
+        .map(|time| RunResult {
+            milestones: vec![Some(time); milestone_names().len()],
+            samples: Vec::new(),
+        })
+        .collect();
+    let statistics = milestone_statistics(&results);
+    assert_eq!(statistics.len(), milestone_names().len());
+    assert_eq!(statistics[0].reached, 3);
+    assert_eq!(statistics[0].min_seconds, Some(10));
+    assert_eq!(statistics[0].mean_seconds, Some(20.0));
+    assert_eq!(statistics[0].median_seconds, Some(20));
+    assert_eq!(statistics[0].max_seconds, Some(30));
+    assert_eq!(options.strategy.to_string().parse(), Ok(options.strategy));
+}
@@ -0,0 +1,673 @@
+//! Named save slots.
+//!
+//! Every slot stores the progression core, the map data and the game telemetry together in one
+//! place (a file on native, a local storage key on wasm). The slot picker in `ui` only sends
+//! `SlotActionEvent`s, all of the actual storage handling happens in here.
+
+use std::fmt;
+#[cfg(not(target_arch = "wasm32"))]
+use std::fs;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[cfg(not(target_arch = "wasm32"))]
+use crate::assets::{
+    GAME_TELEMETRY_FILE, LEGACY_SAVE_IMPORTED_FILE, MAP_DATA_FILE, PROGRESSION_CORE_FILE,
+    SAVE_SLOTS_DIR,
+};
+#[cfg(target_arch = "wasm32")]
+use crate::assets::{
+    WASM_GAME_TELEMETRY_KEY_STORAGE, WASM_LEGACY_SAVE_IMPORTED_KEY_STORAGE,
+    WASM_MAP_DATA_KEY_STORAGE, WASM_PROGRESSION_CORE_KEY_STORAGE, WASM_SAVE_SLOT_KEY_PREFIX,
+};
+use crate::{GameAssets, GameState};
+
+use super::{
+    flora, save, telemetry::GameTelemetryManager, timestamp, FloraRegistry, MapData,
+    ProgressionCore, SAVE_BACKUP_SUFFIX,
+};
+
+pub const DEFAULT_SLOT_NAME: &str = "Garden";
+pub const MAX_SLOT_NAME_LENGTH: usize = 16;
+
+/// Everything that belongs to one garden, see `package_save_data`.
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct SaveSlot {
+    pub name: String,
+    pub core: String,
+    pub map_data: String,
+    pub telemetry: String,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct SlotEntry {
+    pub id: u64,
+    pub name: String,
+}
+
+/// All the slots that exist in the storage, sorted by id (which is the creation time).
+#[derive(Resource, Default)]
+pub struct SaveSlots {
+    pub entries: Vec<SlotEntry>,
+}
+
+/// The slot that is currently being played, all saving goes into this slot.
+#[derive(Resource, Clone)]
+pub struct ActiveSlot {
+    pub id: u64,
+    pub name: String,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum SlotAction {
+    /// A new garden, with a random world seed if none is given.
+    Create {
+        seed: Option<u64>,
+    },
+    Rename {
+        id: u64,
+        name: String,
+    },
+    Duplicate {
+        id: u64,
+    },
+    Delete {
+        id: u64,
+    },
+    Load {
+        id: u64,
+    },
+}
+
+#[derive(Event)]
+pub struct SlotActionEvent {
+    pub action: SlotAction,
+}
+
+impl SaveSlot {
+    pub fn from_str(raw: &str) -> Option<Self> {
+        serde_json::from_str(raw).ok()
+    }
+}
+
+impl fmt::Display for SaveSlot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let json = serde_json::to_string(self).map_err(|_| fmt::Error)?;
+        write!(f, "{}", json)
+    }
+}
+
+impl SaveSlots {
+    fn next_id(&self) -> u64 {
+        let max_id = self.entries.iter().map(|e| e.id).max().unwrap_or_default();
+        timestamp().max(max_id + 1)
+    }
+
+    fn name(&self, id: u64) -> Option<&str> {
+        self.entries
+            .iter()
+            .find(|e| e.id == id)
+            .map(|e| e.name.as_str())
+    }
+
+    /// Store the given slot under a new id, renaming it if its name is already taken.
+    pub fn add_slot(&mut self, mut slot: SaveSlot) -> u64 {
+        let id = self.next_id();
+        slot.name = self.unique_name(&sanitize_slot_name(&slot.name));
+        if slot.name.is_empty() {
+            slot.name = self.unique_name(DEFAULT_SLOT_NAME);
+        }
+
+        write_slot(id, &slot);
+        self.entries.push(SlotEntry {
+            id,
+            name: slot.name,
+        });
+        id
+    }
+
+    /// Returns a name that isn't used by any of the slots yet, e.g. "Garden 2".
+    fn unique_name(&self, base: &str) -> String {
+        if !self.entries.iter().any(|e| e.name == base) {
+            return base.to_string();
+        }
+
+        let mut index = 2;
+        loop {
+            let name = format!("{} {}", base, index);
+            if !self.entries.iter().any(|e| e.name == name) {
+                return name;
+            }
+            index += 1;
+        }
+    }
+
+    /// The name the slot ends up with when renamed, `None` if the name doesn't change (or is
+    /// empty). Taken names get a number just like new slots.
+    fn renamed(&self, id: u64, name: &str) -> Option<String> {
+        let name = sanitize_slot_name(name);
+        if name.is_empty() || self.name(id) == Some(name.as_str()) {
+            return None;
+        }
+        Some(self.unique_name(&name))
+    }
+}
+
+pub fn sanitize_slot_name(name: &str) -> String {
+    name.trim().chars().take(MAX_SLOT_NAME_LENGTH).collect()
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn slot_file(id: u64) -> String {
+    format!("{}/{}.json", SAVE_SLOTS_DIR, id)
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn read_slot_ids_native() -> Vec<u64> {
+    let Ok(entries) = fs::read_dir(SAVE_SLOTS_DIR) else {
+        return Vec::new();
+    };
+
+    entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let name = entry.file_name().into_string().ok()?;
+            name.strip_suffix(".json")?.parse::<u64>().ok()
+        })
+        .collect()
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn read_raw_slot_native(id: u64) -> Option<String> {
+    fs::read_to_string(slot_file(id)).ok()
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn write_raw_slot_native(id: u64, raw: &str) {
+    if fs::create_dir_all(SAVE_SLOTS_DIR).is_err() {
+        error!("failed to create save slots directory: {}", SAVE_SLOTS_DIR);
+        return;
+    }
+
+    if fs::write(slot_file(id), raw).is_err() {
+        error!("failed to write save slot to file: {}", slot_file(id));
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn delete_slot_native(id: u64) {
+    if fs::remove_file(slot_file(id)).is_err() {
+        error!("failed to delete save slot file: {}", slot_file(id));
+    }
+}
+
+/// Saves from before slots existed are stored in fixed files.
+#[cfg(not(target_arch = "wasm32"))]
+fn read_legacy_save_native() -> Option<SaveSlot> {
+    let core = fs::read_to_string(PROGRESSION_CORE_FILE).unwrap_or_default();
+    if core.trim().is_empty() {
+        return None;
+    }
+
+    Some(SaveSlot {
+        name: DEFAULT_SLOT_NAME.to_string(),
+        core,
+        map_data: fs::read_to_string(MAP_DATA_FILE).unwrap_or_default(),
+        telemetry: fs::read_to_string(GAME_TELEMETRY_FILE).unwrap_or_default(),
+    })
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn legacy_save_imported_native() -> bool {
+    fs::exists(LEGACY_SAVE_IMPORTED_FILE).unwrap_or_default()
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn mark_legacy_save_imported_native() {
+    if fs::create_dir_all(SAVE_SLOTS_DIR).is_err()
+        || fs::write(LEGACY_SAVE_IMPORTED_FILE, "").is_err()
+    {
+        error!(
+            "failed to write legacy save marker: {}",
+            LEGACY_SAVE_IMPORTED_FILE
+        );
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn slot_key(id: u64) -> String {
+    format!("{}{}", WASM_SAVE_SLOT_KEY_PREFIX, id)
+}
+
+#[cfg(target_arch = "wasm32")]
+fn storage_wasm() -> Option<web_sys::Storage> {
+    web_sys::window()
+        .and_then(|w| w.local_storage().ok())
+        .flatten()
+}
+
+#[cfg(target_arch = "wasm32")]
+fn read_slot_ids_wasm() -> Vec<u64> {
+    let Some(storage) = storage_wasm() else {
+        return Vec::new();
+    };
+
+    let len = storage.length().unwrap_or_default();
+    (0..len)
+        .filter_map(|i| storage.key(i).ok().flatten())
+        .filter_map(|key| {
+            key.strip_prefix(WASM_SAVE_SLOT_KEY_PREFIX)?
+                .parse::<u64>()
+                .ok()
+        })
+        .collect()
+}
+
+#[cfg(target_arch = "wasm32")]
+fn read_raw_slot_wasm(id: u64) -> Option<String> {
+    storage_wasm()?.get_item(&slot_key(id)).ok().flatten()
+}
+
+#[cfg(target_arch = "wasm32")]
+fn write_raw_slot_wasm(id: u64, raw: &str) {
+    let Some(storage) = storage_wasm() else {
+        error!("failed to get browser storage (for writing)");
+        return;
+    };
+
+    if storage.set_item(&slot_key(id), raw).is_err() {
+        error!(
+            "failed to store save slot to local storage, key: {}",
+            slot_key(id)
+        );
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+fn delete_slot_wasm(id: u64) {
+    let Some(storage) = storage_wasm() else {
+        error!("failed to get browser storage (for deleting)");
+        return;
+    };
+
+    if storage.remove_item(&slot_key(id)).is_err() {
+        error!(
+            "failed to delete save slot from local storage, key: {}",
+            slot_key(id)
+        );
+    }
+}
+
+/// Saves from before slots existed are stored in fixed keys.
+#[cfg(target_arch = "wasm32")]
+fn read_legacy_save_wasm() -> Option<SaveSlot> {
+    let storage = storage_wasm()?;
+    let read = |key: &str| storage.get_item(key).ok().flatten().unwrap_or_default();
+
+    let core = read(WASM_PROGRESSION_CORE_KEY_STORAGE);
+    if core.trim().is_empty() {
+        return None;
+    }
+
+    Some(SaveSlot {
+        name: DEFAULT_SLOT_NAME.to_string(),
+        core,
+        map_data: read(WASM_MAP_DATA_KEY_STORAGE),
+        telemetry: read(WASM_GAME_TELEMETRY_KEY_STORAGE),
+    })
+}
+
+#[cfg(target_arch = "wasm32")]
+fn legacy_save_imported_wasm() -> bool {
+    storage_wasm()
+        .and_then(|storage| {
+            storage
+                .get_item(WASM_LEGACY_SAVE_IMPORTED_KEY_STORAGE)
+                .ok()
+                .flatten()
+        })
+        .is_some()
+}
+
+#[cfg(target_arch = "wasm32")]
+fn mark_legacy_save_imported_wasm() {
+    if storage_wasm()
+        .and_then(|storage| {
+            storage
+                .set_item(WASM_LEGACY_SAVE_IMPORTED_KEY_STORAGE, "")
+                .ok()
+        })
+        .is_none()
+    {
+        error!("failed to store legacy save marker to local storage");
+    }
+}
+
+fn read_slot_ids() -> Vec<u64> {
+    #[cfg(not(target_arch = "wasm32"))]
+    let ids = read_slot_ids_native();
+
+    #[cfg(target_arch = "wasm32")]
+    let ids = read_slot_ids_wasm();
+
+    ids
+}
+
+fn read_raw_slot(id: u64) -> Option<String> {
+    #[cfg(not(target_arch = "wasm32"))]
+    let raw = read_raw_slot_native(id);
+
+    #[cfg(target_arch = "wasm32")]
+    let raw = read_raw_slot_wasm(id);
+
+    raw
+}
+
+pub fn read_slot(id: u64) -> Option<SaveSlot> {
+    let raw = read_raw_slot(id)?;
+    let slot = SaveSlot::from_str(&raw);
+
+    if slot.is_none() {
+        error!("failed to parse save slot: {}", id);
+        write_raw_slot(&format!("{}{}", id, SAVE_BACKUP_SUFFIX), &raw);
+    }
+    slot
+}
+
+/// Only used to back up unreadable slots, which is why the id is a string here.
+fn write_raw_slot(name: &str, raw: &str) {
+    #[cfg(not(target_arch = "wasm32"))]
+    if fs::create_dir_all(SAVE_SLOTS_DIR).is_err()
+        || fs::write(format!("{}/{}", SAVE_SLOTS_DIR, name), raw).is_err()
+    {
+        error!("failed to back up save slot: {}", name);
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    if storage_wasm()
+        .and_then(|s| {
+            s.set_item(&format!("{}{}", WASM_SAVE_SLOT_KEY_PREFIX, name), raw)
+                .ok()
+        })
+        .is_none()
+    {
+        error!("failed to back up save slot: {}", name);
+    }
+}
+
+pub fn write_slot(id: u64, slot: &SaveSlot) {
+    #[cfg(not(target_arch = "wasm32"))]
+    write_raw_slot_native(id, &slot.to_string());
+
+    #[cfg(target_arch = "wasm32")]
+    write_raw_slot_wasm(id, &slot.to_string());
+}
+
+fn delete_slot(id: u64) {
+    #[cfg(not(target_arch = "wasm32"))]
+    delete_slot_native(id);
+
+    #[cfg(target_arch = "wasm32")]
+    delete_slot_wasm(id);
+}
+
+fn read_legacy_save() -> Option<SaveSlot> {
+    #[cfg(not(target_arch = "wasm32"))]
+    let slot = read_legacy_save_native();
+
+    #[cfg(target_arch = "wasm32")]
+    let slot = read_legacy_save_wasm();
+
+    slot
+}
+
+fn legacy_save_imported() -> bool {
+    #[cfg(not(target_arch = "wasm32"))]
+    let imported = legacy_save_imported_native();
+
+    #[cfg(target_arch = "wasm32")]
+    let imported = legacy_save_imported_wasm();
+
+    imported
+}
+
+fn mark_legacy_save_imported() {
+    #[cfg(not(target_arch = "wasm32"))]
+    mark_legacy_save_imported_native();
+
+    #[cfg(target_arch = "wasm32")]
+    mark_legacy_save_imported_wasm();
+}
+
+pub fn read_save_slots() -> SaveSlots {
+    let mut ids = read_slot_ids();
+    ids.sort_unstable();
+
+    let entries = ids
+        .into_iter()
+        .filter_map(|id| {
+            read_slot(id).map(|slot| SlotEntry {
+                id,
+                name: slot.name,
+            })
+        })
+        .collect();
+
+    SaveSlots { entries }
+}
+
+fn insert_save_slots(mut commands: Commands) {
+    let mut slots = read_save_slots();
+
+    // Move the single save from before slots existed into its own slot, the fixed files (or
+    // keys) are left untouched so older builds can still read them. This only happens once,
+    // otherwise the old save would come back after deleting all slots.
+    if !legacy_save_imported() {
+        if slots.entries.is_empty()
+            && let Some(legacy_slot) = read_legacy_save()
+        {
+            let id = slots.next_id();
+            write_slot(id, &legacy_slot);
+            slots.entries.push(SlotEntry {
+                id,
+                name: legacy_slot.name,
+            });
+        }
+        mark_legacy_save_imported();
+    }
+
+    commands.insert_resource(slots);
+}
+
+fn load_slot(
+    commands: &mut Commands,
+    telemetry: &mut GameTelemetryManager,
+    next_state: &mut NextState<GameState>,
+    flora_registry: &FloraRegistry,
+    id: u64,
+) {
+    let Some(slot) = read_slot(id) else {
+        return;
+    };
+
+    let core =
+        save::deserialize_progression_core(&slot.core, flora_registry).unwrap_or_else(|err| {
+            error!("failed to load progression core of slot: {}, {}", id, err);
+            write_raw_slot(&format!("{}{}", id, SAVE_BACKUP_SUFFIX), &slot.to_string());
+            ProgressionCore::new_garden()
+        });
+    let map_data =
+        save::deserialize_map_data(&slot.map_data, flora_registry).unwrap_or_else(|err| {
+            error!("failed to load map data of slot: {}, {}", id, err);
+            write_raw_slot(&format!("{}{}", id, SAVE_BACKUP_SUFFIX), &slot.to_string());
+            MapData::with_flora_registry(flora_registry.clone())
+        });
+
+    // Fresh slots don't have any telemetry yet, in that case we keep the one from startup (so
+    // that the id stays the same).
+    if let Ok(slot_telemetry) = serde_json::from_str::<GameTelemetryManager>(&slot.telemetry) {
+        *telemetry = slot_telemetry;
+    }
+
+    commands.insert_resource(core);
+    commands.insert_resource(map_data);
+    commands.insert_resource(ActiveSlot {
+        id,
+        name: slot.name,
+    });
+    next_state.set(GameState::Gaming);
+}
+
+fn handle_slot_actions(
+    mut commands: Commands,
+    mut slots: ResMut<SaveSlots>,
+    mut telemetry: ResMut<GameTelemetryManager>,
+    mut next_state: ResMut<NextState<GameState>>,
+    assets: Res<GameAssets>,
+    flora_registries: Res<Assets<FloraRegistry>>,
+    mut ev_slot_action: EventReader<SlotActionEvent>,
+) {
+    for ev in ev_slot_action.read() {
+        match &ev.action {
+            SlotAction::Create { seed } => {
+                let id = slots.next_id();
+                let name = slots.unique_name(DEFAULT_SLOT_NAME);
+                // An empty core is a fresh garden with a random seed, see `save`.
+                let core = seed
+                    .map(|seed| {
+                        let core = ProgressionCore {
+                            world_seed: seed,
+                            ..default()
+                        };
+                        let flora_registry =
+                            flora::loaded_flora_registry(&assets, &flora_registries);
+                        save::serialize_progression_core(&core, &flora_registry)
+                    })
+                    .unwrap_or_default();
+                write_slot(
+                    id,
+                    &SaveSlot {
+                        name: name.clone(),
+                        core,
+                        ..default()
+                    },
+                );
+                slots.entries.push(SlotEntry { id, name });
+            }
+            SlotAction::Rename { id, name } => {
+                let Some(name) = slots.renamed(*id, name) else {
+                    continue;
+                };
+                let Some(mut slot) = read_slot(*id) else {
+                    continue;
+                };
+
+                slot.name = name.clone();
+                write_slot(*id, &slot);
+
+                if let Some(entry) = slots.entries.iter_mut().find(|e| e.id == *id) {
+                    entry.name = name;
+                }
+            }
+            SlotAction::Duplicate { id } => {
+                let Some(mut slot) = read_slot(*id) else {
+                    continue;
+                };
+
+                slot.name = slots.name(*id).unwrap_or(DEFAULT_SLOT_NAME).to_string();
+                slots.add_slot(slot);
+            }
+            SlotAction::Delete { id } => {
+                delete_slot(*id);
+                slots.entries.retain(|e| e.id != *id);
+            }
+            SlotAction::Load { id } => {
+                let flora_registry = flora::loaded_flora_registry(&assets, &flora_registries);
+                load_slot(
+                    &mut commands,
+                    &mut telemetry,
+                    &mut next_state,
+                    &flora_registry,
+                    *id,
+                );
+            }
+        }
+    }
+}
+
+pub struct MapSlotsPlugin;
+
+impl Plugin for MapSlotsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<SlotActionEvent>()
+            .add_systems(Startup, insert_save_slots)
+            .add_systems(
+                Update,
+                handle_slot_actions.run_if(
+                    in_state(GameState::SlotSelect)
+                        .and(resource_exists::<SaveSlots>)
+                        .and(resource_exists::<GameTelemetryManager>)
+                        .and(resource_exists::<GameAssets>),
+                ),
+            );
+    }
+}
+
+#[test]
+fn validate_unique_slot_names() {
+    let slots = SaveSlots {
+        entries: vec![
+            SlotEntry {
+                id: 1,
+                name: DEFAULT_SLOT_NAME.to_string(),
+            },
+            SlotEntry {
+                id: 2,
+                name: format!("{} 2", DEFAULT_SLOT_NAME),
+            },
+        ],
+    };
+
+    assert_eq!(
+        slots.unique_name(DEFAULT_SLOT_NAME),
+        format!("{} 3", DEFAULT_SLOT_NAME)
+    );
+    assert_eq!(slots.unique_name("Balancing"), "Balancing".to_string());
+    assert!(slots.next_id() > 2);
+
+    assert_eq!(
+        slots.renamed(2, DEFAULT_SLOT_NAME),
+        Some(format!("{} 3", DEFAULT_SLOT_NAME))
+    );
+    assert_eq!(slots.renamed(1, DEFAULT_SLOT_NAME), None);
+    assert_eq!(slots.renamed(1, "   "), None);
+    assert_eq!(
+        slots.renamed(1, " Balancing "),
+        Some("Balancing".to_string())
+    );
+}
+
+#[test]
+fn validate_slot_name_sanitizing() {
+    assert_eq!(sanitize_slot_name("  Test  "), "Test".to_string());
+    assert_eq!(
+        sanitize_slot_name("a very very long garden name").len(),
+        MAX_SLOT_NAME_LENGTH
+    );
+}
+
+#[test]
+fn validate_save_slot_round_trip() {
+    let slot = SaveSlot {
+        name: "Test".to_string(),
+        core: "{}".to_string(),
+        map_data: "v1|0,0:1".to_string(),
+        telemetry: String::new(),
+    };
+
+    let loaded = SaveSlot::from_str(&slot.to_string()).unwrap();
+    assert_eq!(loaded.name, slot.name);
+    assert_eq!(loaded.core, slot.core);
+    assert_eq!(loaded.map_data, slot.map_data);
+}
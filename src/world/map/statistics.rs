@@ -0,0 +1,166 @@
+//! Lifetime statistics.
+//!
+//! A ledger of everything that happened in a save, it is never reset by rebirths. It is part of the
+//! `ProgressionCore`, so it's saved with it and also ends up in the telemetry, which makes it handy
+//! to sanity check the telemetry against.
+
+use std::time::Duration;
+
+use bevy::{prelude::*, time::common_conditions::on_real_timer};
+use serde::{Deserialize, Serialize};
+
+use crate::{player::SpawnedSlash, world::BigNumber};
+
+use super::{
+    compute_pps_breakdown, CutTallGrass, Flora, ItemBought, MapData, ProgressionCore,
+    ProgressionSystemSet,
+};
+
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct StatisticsSystemSet;
+
+#[derive(Serialize, Deserialize, Clone, Default, PartialEq, Debug)]
+pub struct Statistics {
+    /// All points ever earned, over all runs.
+    pub points_earned: BigNumber,
+    pub grass_cut: u64,
+    pub slashes: u64,
+    pub items_bought: u64,
+    /// Number of times the points went from below the cap up to the cap.
+    pub times_capped: u64,
+    pub play_seconds: u64,
+    /// Offline time that counted, see `offline`.
+    pub offline_seconds: u64,
+    /// Indexed by the flora index, saved by the flora ids like `ProgressionCore::flora`.
+    pub flora_bought: Vec<u64>,
+    /// Points produced by each flora, indexed by the flora index.
+    pub flora_points: Vec<BigNumber>,
+}
+
+impl Statistics {
+    pub fn flora_bought(&self, flora: Flora) -> u64 {
+        self.flora_bought
+            .get(flora.index())
+            .copied()
+            .unwrap_or_default()
+    }
+
+    pub fn flora_points(&self, flora: Flora) -> BigNumber {
+        self.flora_points
+            .get(flora.index())
+            .copied()
+            .unwrap_or(BigNumber::ZERO)
+    }
+
+    fn add_flora_bought(&mut self, flora: Flora, amount: u32) {
+        if self.flora_bought.len() <= flora.index() {
+            self.flora_bought.resize(flora.index() + 1, 0);
+        }
+        self.flora_bought[flora.index()] += amount as u64;
+    }
+
+    pub fn add_points_of_flora(&mut self, flora: Flora, points: BigNumber) {
+        if self.flora_points.len() <= flora.index() {
+            self.flora_points.resize(flora.index() + 1, BigNumber::ZERO);
+        }
+        self.flora_points[flora.index()] += points;
+    }
+
+    /// Add the points of each flora, indexed by the flora index.
+    pub fn add_flora_points(&mut self, flora_points: &[BigNumber]) {
+        if self.flora_points.len() < flora_points.len() {
+            self.flora_points
+                .resize(flora_points.len(), BigNumber::ZERO);
+        }
+        for (total, points) in self.flora_points.iter_mut().zip(flora_points) {
+            *total += *points;
+        }
+    }
+}
+
+/// Split the given points up between the flora by how much each contributes to the pps.
+pub fn split_points_by_flora(
+    core: &ProgressionCore,
+    map_data: &MapData,
+    points: BigNumber,
+) -> Vec<BigNumber> {
+    let breakdown = compute_pps_breakdown(core, map_data);
+    let total = breakdown
+        .iter()
+        .fold(BigNumber::ZERO, |total, flora_pps| total + *flora_pps);
+
+    breakdown
+        .iter()
+        .map(|flora_pps| {
+            if total.is_zero() {
+                return BigNumber::ZERO;
+            }
+            points * flora_pps.ratio(total).into()
+        })
+        .collect()
+}
+
+fn count_statistics(
+    mut core: ResMut<ProgressionCore>,
+    mut ev_cut_tall_grass: EventReader<CutTallGrass>,
+    mut ev_spawned_slash: EventReader<SpawnedSlash>,
+    mut ev_item_bought: EventReader<ItemBought>,
+    mut was_capped: Local<bool>,
+) {
+    let statistics = &mut core.statistics;
+    statistics.grass_cut += ev_cut_tall_grass.read().count() as u64;
+    statistics.slashes += ev_spawned_slash.read().count() as u64;
+    for ev in ev_item_bought.read() {
+        statistics.items_bought += ev.amount as u64;
+        statistics.add_flora_bought(ev.item, ev.amount);
+    }
+
+    let is_capped = core.points >= core.points_cap;
+    if is_capped && !*was_capped {
+        core.statistics.times_capped += 1;
+    }
+    *was_capped = is_capped;
+}
+
+fn count_play_time(mut core: ResMut<ProgressionCore>) {
+    core.statistics.play_seconds += 1;
+}
+
+pub struct MapStatisticsPlugin;
+
+impl Plugin for MapStatisticsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                count_statistics,
+                count_play_time.run_if(on_real_timer(Duration::from_secs(1))),
+            )
+                .in_set(StatisticsSystemSet)
+                .after(ProgressionSystemSet)
+                .run_if(resource_exists::<ProgressionCore>.and(resource_exists::<MapData>)),
+        );
+    }
+}
+
+#[test]
+fn validate_split_points_by_flora() {
+    let mut core = ProgressionCore::default();
+    let map_data = MapData::default();
+
+    let split = split_points_by_flora(&core, &map_data, 100u64.into());
+    assert!(split.iter().all(|points| points.is_zero()));
+
+    let raddish = map_data.flora_registry().get("raddish").unwrap();
+    let potatoe = map_data.flora_registry().get("potatoe").unwrap();
+    core.add_flora(raddish);
+    let split = split_points_by_flora(&core, &map_data, 100u64.into());
+    assert_eq!(split[raddish.index()], BigNumber::from(100u64));
+    assert!(split[potatoe.index()].is_zero());
+
+    let mut statistics = Statistics::default();
+    statistics.add_flora_points(&split);
+    statistics.add_flora_points(&split);
+    assert_eq!(statistics.flora_points(raddish), BigNumber::from(200u64));
+    assert!(statistics.flora_points(potatoe).is_zero());
+}
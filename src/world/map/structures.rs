@@ -0,0 +1,480 @@
+//! Structures, buildings like the silos that are placed on the grid.
+//!
+//! Structures are defined in `structures.json` and placed through a `Blueprint`, just like flora.
+//! Their footprint is filled with `STRUCTURE_CELL_VALUE`, the `structures` of the `MapData` keep
+//! the anchors and the other cells point back to them through the same `anchors` as the flora.
+//!
+//! Every owned structure raises the points cap, no matter whether it's placed or still waiting in
+//! the inventory of the core (bought without the building mode or carried over from the silo
+//! counter of older saves). Placed structures can also give the flora around them a bonus.
+
+use bevy::{color::palettes::css::ORANGE, prelude::*};
+use serde::Deserialize;
+
+use crate::{
+    player::{GamingInput, Player},
+    ui::{MenuAction, MenuActionEvent, StructurePressed},
+    world::{
+        camera::YSort,
+        collisions::{ColliderColor, StaticCollider, WORLD_COLLISION_GROUPS},
+        BigNumber, TILE_SIZE,
+    },
+    BachelorBuild, GameAssets,
+};
+
+use super::{
+    building::{spawn_blueprint, BlueprintItem},
+    footprint::size_offset,
+    upgrades::ModifierTarget,
+    Blueprint, BuildingSystemSet, Daylit, MapData, PrestigeAction, PrestigeActionEvent,
+    ProgressionCore, ProgressionSystemSet, STRUCTURE_CELL_VALUE,
+};
+
+#[cfg(test)]
+use super::FloraRegistry;
+
+/// Structures are saved as `STRUCTURE_ID_PREFIX` followed by their id in the map data, so they can
+/// never be mistaken for flora.
+pub const STRUCTURE_ID_PREFIX: &str = "structure/";
+/// The structure the `+Cap` button of the stats builds.
+pub const SILO_ID: &str = "silo";
+
+#[derive(Deserialize, Clone, Copy, PartialEq, Debug)]
+struct NearbyBonus {
+    /// Flora are nearby if there are less than `radius` cells between them and the structure,
+    /// diagonals included.
+    radius: usize,
+    /// Relative bonus to the pps of every nearby plant, `0.05` is +5%.
+    bonus: f64,
+}
+
+/// A single entry of `structures.json`.
+#[derive(Deserialize, Clone, Debug)]
+pub struct StructureData {
+    pub id: String,
+    sprite: String,
+    ysort: f32,
+    size_on_grid: (usize, usize),
+    /// How much every owned structure raises the points cap.
+    capacity: u64,
+    /// The first structure is free, every one after that costs this much more than the last.
+    cost_increase: u64,
+    nearby_bonus: Option<NearbyBonus>,
+}
+
+/// Index into the structure data of the `MapData`.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct Structure(u16);
+
+#[derive(Component)]
+struct StructureMarker;
+
+impl StructureData {
+    pub fn size_on_grid(&self) -> (usize, usize) {
+        let (x, y) = self.size_on_grid;
+        debug_assert!(x > 0);
+        debug_assert!(y > 0);
+        (x, y)
+    }
+}
+
+pub fn build_structure_data(raw: &str) -> Vec<StructureData> {
+    match serde_json::from_str(raw) {
+        Ok(data) => data,
+        Err(err) => {
+            error!("failed to parse structure data, {}", err);
+            Vec::new()
+        }
+    }
+}
+
+/// The cells between two ranges, zero if they touch or overlap.
+fn gap(a: (usize, usize), b: (usize, usize)) -> usize {
+    b.0.saturating_sub(a.1).max(a.0.saturating_sub(b.1))
+}
+
+impl MapData {
+    pub fn structure_data(&self, structure: Structure) -> &StructureData {
+        &self.structure_data[structure.0 as usize]
+    }
+
+    pub fn structure_by_id(&self, id: &str) -> Option<Structure> {
+        self.structure_data
+            .iter()
+            .position(|data| data.id == id)
+            .map(|index| Structure(index as u16))
+    }
+
+    fn all_structures(&self) -> impl Iterator<Item = Structure> + use<> {
+        (0..self.structure_data.len() as u16).map(Structure)
+    }
+
+    pub fn placed_structures(&self) -> impl Iterator<Item = ((usize, usize), Structure)> + '_ {
+        self.structures
+            .iter()
+            .map(|(anchor, structure)| (*anchor, *structure))
+    }
+
+    fn placed_structure_count(&self, structure: Structure) -> u32 {
+        self.structures
+            .values()
+            .filter(|placed| **placed == structure)
+            .count() as u32
+    }
+
+    /// Place the structure with its anchor at the given cell, the footprint must be free.
+    pub(super) fn place_structure(&mut self, x: usize, y: usize, structure: Structure) {
+        let size = self.structure_data(structure).size_on_grid();
+        debug_assert!(
+            self.fits_at_empty_or_grass_position(x, y, size.0, size.1),
+            "structure: {}, (x, y): {}, {}, grid: {:?}",
+            self.structure_data(structure).id,
+            x,
+            y,
+            self.grid_index(x, y)
+        );
+
+        self.fill_footprint(x, y, size, STRUCTURE_CELL_VALUE);
+        self.structures.insert((x, y), structure);
+    }
+
+    /// The bonus flora of the given size at the given cell get from the placed structures.
+    pub(super) fn structure_bonus(&self, x: usize, y: usize, size: (usize, usize)) -> f64 {
+        self.placed_structures()
+            .filter_map(|((sx, sy), structure)| {
+                let data = self.structure_data(structure);
+                let nearby_bonus = data.nearby_bonus?;
+                let (x_size, y_size) = data.size_on_grid();
+                let distance = gap((x, x + size.0), (sx, sx + x_size))
+                    .max(gap((y, y + size.1), (sy, sy + y_size)));
+                (distance < nearby_bonus.radius).then_some(nearby_bonus.bonus)
+            })
+            .sum()
+    }
+}
+
+impl ProgressionCore {
+    pub fn unplaced_structures(&self, map_data: &MapData, structure: Structure) -> u32 {
+        let id = &map_data.structure_data(structure).id;
+        self.unplaced_structures
+            .get(id)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Placed and unplaced structures.
+    pub fn structure_count(&self, map_data: &MapData, structure: Structure) -> u32 {
+        map_data.placed_structure_count(structure) + self.unplaced_structures(map_data, structure)
+    }
+
+    /// The cost of the next structure, it only depends on how many are owned already.
+    pub fn structure_cost(&self, map_data: &MapData, structure: Structure) -> BigNumber {
+        let cost_increase = map_data.structure_data(structure).cost_increase;
+        BigNumber::from(cost_increase * self.structure_count(map_data, structure) as u64)
+    }
+
+    /// Whether there is one in the inventory or the next one is affordable.
+    pub fn can_place_structure(&self, map_data: &MapData, structure: Structure) -> bool {
+        self.unplaced_structures(map_data, structure) > 0
+            || self.points >= self.structure_cost(map_data, structure)
+    }
+
+    /// Buy the next structure into the inventory, returns whether it was bought.
+    pub fn buy_structure(&mut self, map_data: &MapData, structure: Structure) -> bool {
+        let cost = self.structure_cost(map_data, structure);
+        if self.points < cost {
+            return false;
+        }
+
+        self.points -= cost;
+        let id = map_data.structure_data(structure).id.clone();
+        *self.unplaced_structures.entry(id).or_default() += 1;
+        true
+    }
+
+    /// Place the structure at the given cell, one from the inventory if there is any, otherwise a
+    /// newly bought one. Returns whether it was placed.
+    fn place_structure(
+        &mut self,
+        map_data: &mut MapData,
+        x: usize,
+        y: usize,
+        structure: Structure,
+    ) -> bool {
+        let size = map_data.structure_data(structure).size_on_grid();
+        if !map_data.fits_at_pos(map_data.grid_indices_to_pos(x, y), size) {
+            return false;
+        }
+        if self.unplaced_structures(map_data, structure) == 0
+            && !self.buy_structure(map_data, structure)
+        {
+            return false;
+        }
+
+        let id = &map_data.structure_data(structure).id;
+        if let Some(count) = self.unplaced_structures.get_mut(id) {
+            *count -= 1;
+            if *count == 0 {
+                self.unplaced_structures.remove(id);
+            }
+        }
+        map_data.place_structure(x, y, structure);
+        true
+    }
+
+    /// How much a single structure raises the points cap.
+    pub(super) fn structure_capacity_of(
+        &self,
+        map_data: &MapData,
+        structure: Structure,
+    ) -> BigNumber {
+        self.apply_modifiers(
+            map_data,
+            ModifierTarget::SiloCapacity,
+            map_data.structure_data(structure).capacity.into(),
+        )
+    }
+
+    /// The points cap all owned structures add together.
+    pub(super) fn structure_capacity(&self, map_data: &MapData) -> BigNumber {
+        map_data
+            .all_structures()
+            .fold(BigNumber::ZERO, |capacity, structure| {
+                let count = self.structure_count(map_data, structure) as u64;
+                capacity + self.structure_capacity_of(map_data, structure) * count.into()
+            })
+    }
+}
+
+fn spawn_structure(
+    commands: &mut Commands,
+    asset_server: &AssetServer,
+    map_data: &MapData,
+    pos: Vec2,
+    structure: Structure,
+) {
+    let data = map_data.structure_data(structure);
+    let (x_size, y_size) = data.size_on_grid();
+    let center = pos + size_offset(data.size_on_grid());
+
+    commands.spawn((
+        StructureMarker,
+        YSort(data.ysort),
+        Transform::from_translation(center.extend(0.0)),
+        Sprite::from_image(asset_server.load(&data.sprite)),
+        Daylit,
+        StaticCollider::new(
+            0.5 * TILE_SIZE * x_size as f32,
+            0.5 * TILE_SIZE * y_size as f32,
+        ),
+        WORLD_COLLISION_GROUPS,
+        ColliderColor(ORANGE.into()),
+    ));
+}
+
+fn spawn_structures_on_map_data_insertion(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    map_data: Res<MapData>,
+) {
+    for ((x, y), structure) in map_data.placed_structures() {
+        spawn_structure(
+            &mut commands,
+            &asset_server,
+            &map_data,
+            map_data.grid_indices_to_pos(x, y),
+            structure,
+        );
+    }
+}
+
+/// Without the building mode structures go straight into the inventory, otherwise the player
+/// gets a blueprint to place them.
+fn handle_structure_pressed(
+    mut commands: Commands,
+    assets: Res<GameAssets>,
+    mut core: ResMut<ProgressionCore>,
+    map_data: Res<MapData>,
+    bachelor_build: Res<BachelorBuild>,
+    mut ev_structure_pressed: EventReader<StructurePressed>,
+) {
+    for ev in ev_structure_pressed.read() {
+        if !bachelor_build.with_building {
+            core.buy_structure(&map_data, ev.structure);
+            continue;
+        }
+
+        if !core.can_place_structure(&map_data, ev.structure) {
+            continue;
+        }
+
+        spawn_blueprint(
+            &mut commands,
+            &assets,
+            map_data.structure_data(ev.structure).size_on_grid(),
+            Blueprint {
+                item: BlueprintItem::Structure(ev.structure),
+                fits_at_pos: false,
+                moving_from: None,
+            },
+        );
+    }
+}
+
+fn place_structure_on_confirm(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut core: ResMut<ProgressionCore>,
+    mut map_data: ResMut<MapData>,
+    gaming_input: Res<GamingInput>,
+    q_player: Query<&Player>,
+    q_blueprint: Query<(&Transform, &Blueprint)>,
+) {
+    if !gaming_input.confirm {
+        return;
+    }
+
+    let Ok(player) = q_player.single() else {
+        return;
+    };
+    if player.is_over_ui {
+        return;
+    }
+
+    let Ok((transform, blueprint)) = q_blueprint.single() else {
+        return;
+    };
+    let BlueprintItem::Structure(structure) = blueprint.item else {
+        return;
+    };
+    if !blueprint.fits_at_pos {
+        return;
+    }
+
+    let pos = transform.translation.xy();
+    let Some((x, y)) = map_data.pos_to_grid_indices(pos) else {
+        return;
+    };
+    if !core.place_structure(&mut map_data, x, y, structure) {
+        return;
+    }
+    spawn_structure(&mut commands, &asset_server, &map_data, pos, structure);
+}
+
+fn despawn_structures_on_reset(
+    mut commands: Commands,
+    q_structures: Query<Entity, With<StructureMarker>>,
+    mut ev_menu_action: EventReader<MenuActionEvent>,
+    mut ev_prestige_action: EventReader<PrestigeActionEvent>,
+) {
+    let reset = ev_menu_action
+        .read()
+        .any(|ev| ev.action == MenuAction::Reset);
+    let rebirth = ev_prestige_action
+        .read()
+        .any(|ev| ev.action == PrestigeAction::Rebirth);
+    if !reset && !rebirth {
+        return;
+    }
+
+    for entity in &q_structures {
+        commands.entity(entity).despawn();
+    }
+}
+
+pub struct MapStructuresPlugin;
+
+impl Plugin for MapStructuresPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                spawn_structures_on_map_data_insertion
+                    .run_if(resource_exists::<MapData>.and(run_once)),
+                despawn_structures_on_reset,
+                handle_structure_pressed.run_if(
+                    resource_exists::<GameAssets>
+                        .and(resource_exists::<ProgressionCore>)
+                        .and(resource_exists::<MapData>)
+                        .and(resource_exists::<BachelorBuild>),
+                ),
+                place_structure_on_confirm
+                    .run_if(resource_exists::<ProgressionCore>.and(resource_exists::<MapData>)),
+            )
+                .chain()
+                .after(BuildingSystemSet)
+                .after(ProgressionSystemSet),
+        );
+    }
+}
+
+#[test]
+fn validate_structure_json_data() {
+    use crate::assets::STRUCTURE_DATA_CORE;
+
+    let data: Vec<StructureData> = serde_json::from_str(STRUCTURE_DATA_CORE).unwrap();
+    let map_data = MapData::default();
+    assert!(map_data.structure_by_id(SILO_ID).is_some());
+
+    for (i, structure) in data.iter().enumerate() {
+        assert!(data[..i].iter().all(|other| other.id != structure.id));
+        let (x, y) = structure.size_on_grid();
+        assert!(x > 0 && y > 0);
+        // Otherwise building storage would never pay for itself.
+        assert!(
+            structure.capacity >= structure.cost_increase,
+            "{}",
+            structure.id
+        );
+    }
+}
+
+#[test]
+fn validate_structure_placement() {
+    let mut map_data = MapData::default();
+    let mut core = ProgressionCore::default();
+    let silo = map_data.structure_by_id(SILO_ID).unwrap();
+    let corn = map_data.flora_registry().get("corn").unwrap();
+    let (x, y) = map_data.pos_to_grid_indices(Vec2::ZERO).unwrap();
+    let cap = core.structure_capacity(&map_data);
+
+    // The first one is free, the next one isn't.
+    assert!(core.place_structure(&mut map_data, x, y, silo));
+    assert!(!core.place_structure(&mut map_data, x + 3, y, silo));
+    assert!(core.structure_capacity(&map_data) > cap);
+    for (cx, cy) in [(x, y), (x + 1, y), (x, y + 1), (x + 1, y + 1)] {
+        assert_eq!(map_data.grid_index(cx, cy), Some(STRUCTURE_CELL_VALUE));
+        assert_eq!(map_data.anchor_of(cx, cy), Some((x, y)));
+        assert_eq!(map_data.flora_at(cx, cy), None);
+    }
+    assert_eq!(map_data.anchor_of(x + 2, y), None);
+    assert_eq!(map_data.structures.get(&(x, y)), Some(&silo));
+    assert!(!map_data.fits_at_empty_or_grass_position(x + 1, y + 1, 1, 1));
+
+    // Unplaced ones are placed for free, and count towards the cost of the next one.
+    core.unplaced_structures.insert(SILO_ID.to_string(), 1);
+    let cost = core.structure_cost(&map_data, silo);
+    assert_eq!(cost, BigNumber::from(400u64));
+    assert!(core.place_structure(&mut map_data, x + 3, y, silo));
+    assert!(core.unplaced_structures.is_empty());
+    assert_eq!(core.structure_cost(&map_data, silo), cost);
+    assert_eq!(map_data.placed_structures().count(), 2);
+
+    // Flora close to a silo get its bonus, diagonally touching both silos counts twice.
+    let bonus = map_data.adjacency_bonus(x + 6, y, corn);
+    assert!(bonus > 0.0);
+    assert_eq!(map_data.adjacency_bonus(x + 2, y + 2, corn), 2.0 * bonus);
+    assert_eq!(map_data.adjacency_bonus(x + 8, y, corn), 0.0);
+    map_data.set_grid_index(x + 6, y, corn.cell_value());
+    assert_eq!(map_data.adjacency_bonuses()[corn.index()], bonus);
+
+    // Only the anchor is saved.
+    let raw = map_data.to_string();
+    assert!(raw.contains(&format!(";{},{}:{}{};", x, y, STRUCTURE_ID_PREFIX, SILO_ID)));
+    assert!(!raw.contains(&format!(";{},{}:", x + 1, y + 1)));
+    let loaded = MapData::from_str(&raw, FloraRegistry::default()).unwrap();
+    assert_eq!(loaded.grid, map_data.grid);
+    assert_eq!(loaded.anchors, map_data.anchors);
+    assert_eq!(loaded.structures, map_data.structures);
+    assert_eq!(loaded.to_string(), raw);
+}
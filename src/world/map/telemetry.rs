@@ -24,9 +24,6 @@ const PROGRESSION_CORE_INTERVAL: u64 = 1;
 
 const POST_URL: &str = "https://rancic.org:/telemetry";
 
-#[derive(Component)]
-struct PostRequestMarker;
-
 #[derive(Resource, Serialize, Deserialize)]
 pub struct GameTelemetryManager {
     telemetries: Vec<GameTelemetry>,
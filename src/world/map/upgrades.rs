@@ -0,0 +1,298 @@
+//! Upgrades that can be bought in the store.
+//!
+//! All income goes through `ProgressionCore::apply_modifiers`, which combines the bought upgrades
+//! with the running random event and the prestige multipliers. The additive modifiers of a target
+//! are summed up and added to the base value first (negative ones are subtracted, the value never
+//! drops below zero), the result is then scaled by all multiplicative modifiers.
+
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::{ui::UpgradePressed, world::BigNumber};
+
+use super::{FloraRegistry, MapData, PrestigeUpgrade, ProgressionCore, ProgressionSystemSet};
+
+/// The value an upgrade modifies.
+#[derive(Deserialize, Clone, PartialEq, Debug)]
+pub enum ModifierTarget {
+    /// The pps of a single flora, by the flora id.
+    FloraPps(String),
+    /// The pps of the whole garden.
+    Pps,
+    GrassPoints,
+    PointsCap,
+    /// How much the points cap increases per silo (or any other structure), see `structures`.
+    SiloCapacity,
+    /// How fast cut grass grows back, see `regrowth`.
+    GrassRegrowth,
+    /// How much of the time the game was closed gets paid out, see `offline`.
+    OfflineTime,
+}
+
+#[derive(Deserialize, Clone, Copy, PartialEq, Debug)]
+pub enum Modifier {
+    Add(f64),
+    Multiply(f64),
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct UpgradeData {
+    pub id: String,
+    pub name: String,
+    pub cost: BigNumber,
+    target: ModifierTarget,
+    modifier: Modifier,
+}
+
+impl ModifierTarget {
+    fn string(&self, flora_registry: &FloraRegistry) -> String {
+        match self {
+            Self::FloraPps(id) => match flora_registry.get(id) {
+                Some(flora) => format!("{} pps", flora_registry.name(flora)),
+                None => format!("{} pps", id),
+            },
+            Self::Pps => "pps".to_string(),
+            Self::GrassPoints => "Grass Points".to_string(),
+            Self::PointsCap => "Points Cap".to_string(),
+            Self::SiloCapacity => "Silo Capacity".to_string(),
+            Self::GrassRegrowth => "Grass Regrowth".to_string(),
+            Self::OfflineTime => "Offline Time".to_string(),
+        }
+    }
+
+    /// The prestige upgrade that scales this target on top of the bought upgrades.
+    fn prestige_upgrade(&self) -> Option<PrestigeUpgrade> {
+        match self {
+            Self::Pps => Some(PrestigeUpgrade::Pps),
+            Self::GrassPoints => Some(PrestigeUpgrade::Grass),
+            Self::PointsCap => Some(PrestigeUpgrade::Cap),
+            Self::FloraPps(_) | Self::SiloCapacity | Self::GrassRegrowth | Self::OfflineTime => {
+                None
+            }
+        }
+    }
+}
+
+impl Modifier {
+    /// Human readable effect on the target, e.g. "+50% Carrot pps".
+    pub fn description(self, target: &ModifierTarget, flora_registry: &FloraRegistry) -> String {
+        let modifier = match self {
+            Self::Add(value) if value < 0.0 => format!("{}", value),
+            Self::Add(value) => format!("+{}", value),
+            Self::Multiply(value) if value < 1.0 => {
+                format!("-{}%", ((1.0 - value) * 100.0).round())
+            }
+            Self::Multiply(value) if value < 2.0 => {
+                format!("+{}%", ((value - 1.0) * 100.0).round())
+            }
+            Self::Multiply(value) => format!("x{}", value),
+        };
+        format!("{} {}", modifier, target.string(flora_registry))
+    }
+
+    /// Whether it makes the target worse, e.g. for events that hurt the garden.
+    pub fn is_harmful(self) -> bool {
+        match self {
+            Self::Add(value) => value < 0.0,
+            Self::Multiply(value) => value < 1.0,
+        }
+    }
+}
+
+impl UpgradeData {
+    pub fn description(&self, flora_registry: &FloraRegistry) -> String {
+        self.modifier.description(&self.target, flora_registry)
+    }
+}
+
+impl ProgressionCore {
+    pub fn has_upgrade(&self, id: &str) -> bool {
+        self.upgrades.iter().any(|upgrade| upgrade == id)
+    }
+
+    /// Run the given base value through all bought upgrades, the running random event, the current
+    /// phase of the day and the prestige multipliers.
+    pub fn apply_modifiers(
+        &self,
+        map_data: &MapData,
+        target: ModifierTarget,
+        base: BigNumber,
+    ) -> BigNumber {
+        let mut additive = BigNumber::ZERO;
+        // `BigNumber` can't be negative, so penalties are summed up on their own.
+        let mut penalty = BigNumber::ZERO;
+        let mut multiplier = BigNumber::from(1u64);
+
+        let upgrades = map_data
+            .upgrade_data()
+            .iter()
+            .filter(|upgrade| self.has_upgrade(&upgrade.id))
+            .map(|upgrade| (&upgrade.target, upgrade.modifier));
+        let event = self
+            .active_event(map_data)
+            .map(|event| (&event.target, event.modifier));
+        let phase = self
+            .day_phase(map_data)
+            .into_iter()
+            .flat_map(|phase| &phase.modifiers)
+            .map(|phase_modifier| (&phase_modifier.target, phase_modifier.modifier));
+        for (_, modifier) in upgrades
+            .chain(event)
+            .chain(phase)
+            .filter(|(modifier_target, _)| **modifier_target == target)
+        {
+            match modifier {
+                Modifier::Add(value) if value < 0.0 => penalty += (-value).into(),
+                Modifier::Add(value) => additive += value.into(),
+                Modifier::Multiply(value) => multiplier *= value.into(),
+            }
+        }
+
+        if let Some(prestige_upgrade) = target.prestige_upgrade() {
+            multiplier *= self.prestige.multiplier(prestige_upgrade);
+        }
+
+        (base + additive - penalty) * multiplier
+    }
+}
+
+pub fn build_upgrade_data(raw: &str) -> Vec<UpgradeData> {
+    match serde_json::from_str(raw) {
+        Ok(data) => data,
+        Err(err) => {
+            error!("failed to parse upgrade data, {}", err);
+            Vec::new()
+        }
+    }
+}
+
+fn buy_upgrades(
+    mut core: ResMut<ProgressionCore>,
+    map_data: Res<MapData>,
+    mut ev_upgrade_pressed: EventReader<UpgradePressed>,
+) {
+    for ev in ev_upgrade_pressed.read() {
+        let Some(upgrade) = map_data.upgrade_data().get(ev.index) else {
+            error!("pressed upgrade with invalid index: {}", ev.index);
+            continue;
+        };
+
+        if core.has_upgrade(&upgrade.id) || core.points < upgrade.cost {
+            continue;
+        }
+
+        core.points -= upgrade.cost;
+        core.upgrades.push(upgrade.id.clone());
+    }
+}
+
+pub struct MapUpgradesPlugin;
+
+impl Plugin for MapUpgradesPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            buy_upgrades
+                .in_set(ProgressionSystemSet)
+                .run_if(resource_exists::<ProgressionCore>.and(resource_exists::<MapData>)),
+        );
+    }
+}
+
+#[test]
+fn validate_upgrade_json_data() {
+    use crate::assets::UPGRADE_DATA_CORE;
+
+    let data: Vec<UpgradeData> = serde_json::from_str(UPGRADE_DATA_CORE).unwrap();
+    assert!(!data.is_empty());
+
+    for (i, upgrade) in data.iter().enumerate() {
+        assert!(
+            data[..i].iter().all(|other| other.id != upgrade.id),
+            "duplicate upgrade id: {}",
+            upgrade.id
+        );
+        match upgrade.modifier {
+            Modifier::Add(value) => assert!(value > 0.0),
+            Modifier::Multiply(value) => assert!(value > 1.0),
+        }
+        if let ModifierTarget::FloraPps(id) = &upgrade.target {
+            assert!(
+                FloraRegistry::default().get(id).is_some(),
+                "unknown flora: {}",
+                id
+            );
+        }
+    }
+}
+
+#[test]
+fn validate_modifier_pipeline() {
+    let mut map_data = MapData::default();
+    map_data.upgrade_data = build_upgrade_data(
+        r#"[
+            {"id": "a", "name": "A", "cost": 1, "target": "GrassPoints", "modifier": {"Add": 1}},
+            {"id": "b", "name": "B", "cost": 1, "target": "GrassPoints", "modifier": {"Multiply": 3}},
+            {"id": "c", "name": "C", "cost": 1, "target": {"FloraPps": "carrot"}, "modifier": {"Multiply": 1.5}},
+            {"id": "d", "name": "D", "cost": 1, "target": "Pps", "modifier": {"Add": -1}},
+            {"id": "e", "name": "E", "cost": 1, "target": "Pps", "modifier": {"Add": -5}}
+        ]"#,
+    );
+    let mut core = ProgressionCore::default();
+    let base = BigNumber::from(2u64);
+
+    assert_eq!(
+        core.apply_modifiers(&map_data, ModifierTarget::GrassPoints, base),
+        base
+    );
+
+    core.upgrades = vec!["a".to_string(), "b".to_string()];
+    assert_eq!(
+        core.apply_modifiers(&map_data, ModifierTarget::GrassPoints, base),
+        BigNumber::from(9u64)
+    );
+
+    // Prestige multipliers stack on top.
+    core.prestige.grass_level = 1;
+    assert_eq!(
+        core.apply_modifiers(&map_data, ModifierTarget::GrassPoints, base),
+        BigNumber::from(18u64)
+    );
+
+    // Penalties take away from the base, but never below zero.
+    core.upgrades = vec!["d".to_string()];
+    assert_eq!(
+        core.apply_modifiers(&map_data, ModifierTarget::Pps, base),
+        BigNumber::from(1u64)
+    );
+    core.upgrades = vec!["d".to_string(), "e".to_string()];
+    assert!(core
+        .apply_modifiers(&map_data, ModifierTarget::Pps, base)
+        .is_zero());
+
+    // Upgrades only ever touch their own target.
+    assert_eq!(
+        core.apply_modifiers(
+            &map_data,
+            ModifierTarget::FloraPps("carrot".to_string()),
+            base
+        ),
+        base
+    );
+}
+
+#[test]
+fn validate_upgrade_descriptions() {
+    let data = build_upgrade_data(
+        r#"[
+            {"id": "a", "name": "A", "cost": 1, "target": {"FloraPps": "carrot"}, "modifier": {"Multiply": 1.5}},
+            {"id": "b", "name": "B", "cost": 1, "target": "GrassPoints", "modifier": {"Add": 1}},
+            {"id": "c", "name": "C", "cost": 1, "target": "SiloCapacity", "modifier": {"Multiply": 2}}
+        ]"#,
+    );
+
+    let flora_registry = FloraRegistry::default();
+    assert_eq!(data[0].description(&flora_registry), "+50% Carrot pps");
+    assert_eq!(data[1].description(&flora_registry), "+1 Grass Points");
+    assert_eq!(data[2].description(&flora_registry), "x2 Silo Capacity");
+}
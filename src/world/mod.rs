@@ -1,5 +1,6 @@
 pub mod utils;
 
+mod big_number;
 mod camera;
 mod collisions;
 mod debug;
@@ -7,18 +8,27 @@ mod effects;
 mod map;
 mod state;
 
+pub use big_number::BigNumber;
 pub use camera::{MainCamera, YSort};
 pub use collisions::{
     DynamicCollider, StaticSensorCircle, Velocity, PLAYER_COLLISION_GROUPS, SLASH_COLLISION_GROUPS,
 };
 pub use debug::DebugState;
-#[cfg(debug_assertions)]
-pub use map::simulate_progression;
+#[cfg(not(target_arch = "wasm32"))]
+pub use map::run_save_code_command;
+#[cfg(not(target_arch = "wasm32"))]
+pub use map::run_simulation_command;
 pub use map::GameTelemetryManager;
 pub use map::{
-    AutoSave, BuildingSystemSet, Flora, GrassMaterial, ItemBought, MapData, ProgressionCore,
-    ProgressionSystemSet, ZLevel, POINTS_CAP_COST_INCREASE_PER_SILO,
+    can_rebirth, seeds_for_run, PrestigeAction, PrestigeActionEvent, PrestigeUpgrade,
+    PRESTIGE_POINTS_THRESHOLD,
 };
+pub use map::{
+    AchievementUnlocked, AutoSave, BuildingSystemSet, Daylit, Flora, FloraRegistry,
+    FloraRegistryLoadError, GrassMaterial, ItemBought, MapData, OfflineReport, ProgressionCore,
+    ProgressionSystemSet, RandomEventStarted, RngStream, Structure, WorldRng, ZLevel, SILO_ID,
+};
+pub use map::{SaveCodeResult, SaveSlots, SlotAction, SlotActionEvent, MAX_SLOT_NAME_LENGTH};
 
 use bevy::prelude::*;
 
@@ -3,19 +3,19 @@ use bevy::prelude::*;
 use crate::{
     player::GamingInput,
     ui::{MenuAction, MenuActionEvent},
-    world::map::Blueprint,
+    world::map::CursorTool,
     GameState,
 };
 
 fn switch_to_menu_state(
     mut next_state: ResMut<NextState<GameState>>,
     gaming_input: Res<GamingInput>,
-    q_blueprint: Query<&Blueprint>,
+    q_cursor_tools: Query<(), With<CursorTool>>,
 ) {
     if !gaming_input.menu {
         return;
     }
-    if !q_blueprint.is_empty() {
+    if !q_cursor_tools.is_empty() {
         return;
     }
     next_state.set(GameState::Menu);
@@ -33,6 +33,42 @@ fn switch_to_gaming_state_from_menu(
     }
 }
 
+fn switch_to_prestige_state_from_menu(
+    mut next_state: ResMut<NextState<GameState>>,
+    mut ev_menu_action: EventReader<MenuActionEvent>,
+) {
+    if ev_menu_action
+        .read()
+        .any(|ev| ev.action == MenuAction::Prestige)
+    {
+        next_state.set(GameState::Prestige);
+    }
+}
+
+fn switch_to_achievements_state_from_menu(
+    mut next_state: ResMut<NextState<GameState>>,
+    mut ev_menu_action: EventReader<MenuActionEvent>,
+) {
+    if ev_menu_action
+        .read()
+        .any(|ev| ev.action == MenuAction::Achievements)
+    {
+        next_state.set(GameState::Achievements);
+    }
+}
+
+fn switch_to_statistics_state_from_menu(
+    mut next_state: ResMut<NextState<GameState>>,
+    mut ev_menu_action: EventReader<MenuActionEvent>,
+) {
+    if ev_menu_action
+        .read()
+        .any(|ev| ev.action == MenuAction::Statistics)
+    {
+        next_state.set(GameState::Statistics);
+    }
+}
+
 pub struct WorldStatePlugin;
 
 impl Plugin for WorldStatePlugin {
@@ -42,6 +78,9 @@ impl Plugin for WorldStatePlugin {
             (
                 switch_to_menu_state.run_if(in_state(GameState::Gaming)),
                 switch_to_gaming_state_from_menu.run_if(in_state(GameState::Menu)),
+                switch_to_prestige_state_from_menu.run_if(in_state(GameState::Menu)),
+                switch_to_achievements_state_from_menu.run_if(in_state(GameState::Menu)),
+                switch_to_statistics_state_from_menu.run_if(in_state(GameState::Menu)),
             ),
         );
     }
@@ -2,6 +2,8 @@ use bevy::prelude::*;
 #[cfg(all(debug_assertions, not(target_arch = "wasm32")))]
 use bevy::window::PrimaryWindow;
 
+use super::BigNumber;
+
 /// Convert `Vec2` to `Quat` by taking angle between `Vec2::X`.
 /// Returns `Quat::IDENTITY` for `Vec2::ZERO`.
 pub fn quat_from_vec2(direction: Vec2) -> Quat {
@@ -11,53 +13,34 @@ pub fn quat_from_vec2(direction: Vec2) -> Quat {
     Quat::from_euler(EulerRot::XYZ, 0.0, 0.0, Vec2::X.angle_to(direction))
 }
 
-pub fn format_money_string_raw(amount: u64) -> String {
-    if amount < 10_000 {
-        format!("{}", amount)
-    } else if amount < 1_000_000 {
-        let thousands = amount / 1_000;
-        debug_assert!(thousands > 9);
-        debug_assert!(thousands < 1_000);
+pub fn format_money_string_raw(amount: BigNumber) -> String {
+    amount.to_string()
+}
 
-        if thousands < 100 {
-            let remainder = (amount % 1_000) / 100;
-            format!("{}.{}k", thousands, remainder)
-        } else {
-            format!("{}k", thousands)
-        }
-    } else if amount < 1_000_000_000 {
-        let millions = amount / 1_000_000;
-        debug_assert!(millions > 0);
+pub fn format_money_string(amount: BigNumber) -> String {
+    "$".to_string() + &format_money_string_raw(amount)
+}
 
-        if millions < 10 {
-            let remainder = (amount % 1_000_000) / 10_000;
-            format!("{}.{}M", millions, remainder)
-        } else if millions < 100 {
-            let remainder = (amount % 1_000_000) / 100_000;
-            format!("{}.{}M", millions, remainder)
-        } else {
-            format!("{}M", millions)
-        }
-    } else {
-        let billions = amount / 1_000_000_000;
-        debug_assert!(billions > 0);
+/// Format seconds into the two most significant units, e.g. "3h 12m" or "45s".
+pub fn format_duration(seconds: u64) -> String {
+    let (days, hours, minutes, seconds) = (
+        seconds / 86_400,
+        seconds / 3600 % 24,
+        seconds / 60 % 60,
+        seconds % 60,
+    );
 
-        if billions < 10 {
-            let remainder = (amount % 1_000_000_000) / 10_000_000;
-            format!("{}.{}B", billions, remainder)
-        } else if billions < 100 {
-            let remainder = (amount % 1_000_000_000) / 100_000_000;
-            format!("{}.{}B", billions, remainder)
-        } else {
-            format!("{}B", billions)
-        }
+    if days > 0 {
+        format!("{}d {}h", days, hours)
+    } else if hours > 0 {
+        format!("{}h {}m", hours, minutes)
+    } else if minutes > 0 {
+        format!("{}m {}s", minutes, seconds)
+    } else {
+        format!("{}s", seconds)
     }
 }
 
-pub fn format_money_string(amount: u64) -> String {
-    "$".to_string() + &format_money_string_raw(amount)
-}
-
 #[cfg(not(target_arch = "wasm32"))]
 pub fn save_screenshot(
     mut commands: Commands,
@@ -108,11 +91,29 @@ pub fn toggle_full_screen(
 
 #[test]
 fn validate_money_format_string() {
-    assert_eq!(format_money_string_raw(24_300_001), "24.3M".to_string());
-    assert_eq!(format_money_string(24_300_001), "$24.3M".to_string());
-    assert_eq!(format_money_string(24_000_300_001), "$24.0B".to_string());
     assert_eq!(
-        format_money_string(24_000_000_300_001),
-        "$24000B".to_string()
+        format_money_string_raw(24_300_001u64.into()),
+        "24.3M".to_string()
+    );
+    assert_eq!(
+        format_money_string(24_300_001u64.into()),
+        "$24.3M".to_string()
+    );
+    assert_eq!(
+        format_money_string(24_000_300_001u64.into()),
+        "$24.0B".to_string()
     );
+    assert_eq!(
+        format_money_string(24_000_000_300_001u64.into()),
+        "$24.0T".to_string()
+    );
+}
+
+#[test]
+fn validate_duration_formatting() {
+    assert_eq!(format_duration(0), "0s");
+    assert_eq!(format_duration(45), "45s");
+    assert_eq!(format_duration(61), "1m 1s");
+    assert_eq!(format_duration(3 * 3600 + 12 * 60 + 5), "3h 12m");
+    assert_eq!(format_duration(2 * 86_400 + 3600), "2d 1h");
 }